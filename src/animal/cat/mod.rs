@@ -1,15 +1,27 @@
 mod test;
 
+use boundary::Boundary;
 use pvector::PVector;
 use consts::*;
-use animal::{Animal, Cat, Rat};
+use animal::{Animal, Cat, Rat, Species, Gene, age_seconds, mutate, next_id, reproduce, Lineage};
 use rand::prelude::*;
+use random;
+use config;
+use fitness;
+use fitness::Score;
+use obstacle;
+use vision;
+use vision::Vision;
+use steering;
+use steering::Neighbors;
 use quad_tree::QuadTree;
+use neural;
+use neural::Brain;
 
 impl Animal for Cat {
     // 初期化
     fn new() -> Self {
-        let mut rng = rand::thread_rng();
+        let mut rng = random::rng();
         let theta: f64 = rng.gen::<f64>() * 2.0 * (std::f64::consts::PI);
         let velocity = PVector::new(theta.cos(), theta.sin()).mult(CAT_VELOCITY);
         let x = rng.gen::<f64>() * WIDTH;
@@ -21,9 +33,15 @@ impl Animal for Cat {
             separate_weight: rng.gen::<f64>() * SEPARATE_MAX,
             align_weight: rng.gen::<f64>() * ALIGN_MAX,
             cohension_weight: rng.gen::<f64>() * COHENSION_MAX,
+            vision: Vision::new(&config::get().cat.vision),
+            lead_time: 0.0, // 先読みしないところから始め、進化で伸ばす
+            brain: Brain::new(Species::Cat),
             energy: ENERGY_MAX,
+            spent: 0.0,
+            age: 0,
             ate: 0,
-            id: rng.gen::<u64>(),
+            id: next_id(),
+            lineage: Lineage::new(),
         }
     }
     
@@ -36,30 +54,29 @@ impl Animal for Cat {
         <Cat as Animal>::life_manage(&ret)
     }
     
-    // 速度ベクトル分だけ動く
+    // 速度ベクトルの1ステップ分だけ動く
     fn move_self(&self) -> Cat {
-        let mut new_pos = self.position().add(self.as_velocity());
+        let config = config::get();
+        let new_pos = self.position().add(self.as_velocity().mult(config.dt));
         let mut ret = self.clone();
         
-        if new_pos.x > WIDTH {
-            new_pos.x -= WIDTH;
-        }
-        
-        if new_pos.x < 0.0 {
-            new_pos.x += WIDTH;
-        }
+        // 老いるほど多くのエネルギーを使う
+        let used = config.cat.life.metabolism(config.cat.metabolism, age_seconds(self)) * config.dt;
+        ret.energy -= used;
+        ret.spent += used;
+        ret.age += 1;
         
-        if new_pos.y > HEIGHT {
-            new_pos.y -= HEIGHT;
-        }
+        // 障害物と隠れ家には入れない
+        let (new_pos, velocity) = obstacle::collide(Species::Cat, &new_pos, &self.velocity);
         
-        if new_pos.y < 0.0 {
-            new_pos.y += HEIGHT;
+        // 画面からはみ出た時の操作。吸収されたら寿命を尽きさせて取り除く
+        match config.boundary.apply(&new_pos, &velocity) {
+            Some((position, velocity)) => {
+                ret.position = position;
+                ret.velocity = velocity;
+            }
+            None => ret.energy = 0.0,
         }
-        
-        ret.energy -= 1;
-        
-        ret.position = new_pos;
         ret
     }
     
@@ -75,15 +92,22 @@ impl Animal for Cat {
         ret
     }
     
+    //位置の変更
+    fn set_position(&self, position: &PVector) -> Self {
+        let mut ret = self.clone();
+        ret.position = position.clone();
+        ret
+    }
+    
     // 一定半径以内にいるかどうか
     fn is_within<T: Animal>(&self, other: &T, radious: f64) -> bool {
-        self.offset(other).len() < radious
+        self.offset(other, config::get().boundary).len() < radious
     }
     // 相対位置の計算
-    fn offset<T: Animal>(&self, other: &T) -> PVector {
+    fn offset<T: Animal>(&self, other: &T, boundary: Boundary) -> PVector {
         let self_vec = self.position();
         let other_vec = other.position();
-        self_vec.offset(&other_vec)
+        self_vec.offset(&other_vec, boundary)
     }
     
     // 現在の位置
@@ -91,21 +115,17 @@ impl Animal for Cat {
         self.position.clone()
     }
     
-    // 一定半径以内にいる個体を集める
-    fn collect_near_pvectors<T: Animal>(&self, animals: &QuadTree<T>, radious: f64) -> Vec<T> {
-        animals
-            .search(self, radious)
-            .into_iter()
-            .filter(|animal| !animal.is_same(self))
-            .map(|animal| animal.clone())
-            .collect()
+    // 一定半径以内に見えている個体を集める。neighborsの個体は視線を遮る
+    fn collect_near_pvectors<T: Animal>(&self, animals: &QuadTree<T>, neighbors: &Neighbors, radious: f64) -> Vec<T> {
+        vision::collect_visible(self, animals, neighbors, radious)
     }
     
     // 相対位置の平均を計算
     fn calculate_direction<T: Animal>(&self, animals: Vec<T>) -> PVector {
+        let boundary = config::get().boundary;
         animals
             .into_iter()
-            .map(|animal| self.offset(&animal))
+            .map(|animal| self.offset(&animal, boundary))
             .fold(PVector::zero(), |folded, vector| vector.add(folded))
             .normalize()
     }
@@ -117,23 +137,59 @@ impl Animal for Cat {
         ret.separate_weight = Cat::mutate(self.separate_weight, SEPARATE_MAX);
         ret.align_weight = Cat::mutate(self.align_weight, ALIGN_MAX);
         ret.cohension_weight = Cat::mutate(self.cohension_weight, COHENSION_MAX);
+        ret.lead_time = mutate(self.lead_time, LEAD_TIME_MUTATE_ABS, LEAD_TIME_MAX);
+        if config::get().cat.vision.evolvable {
+            ret.vision = self.vision.mutate();
+        }
+        ret.brain = self.brain.as_ref().map(|brain| brain.mutate());
         ret.ate = 0;
+        ret.lineage = Lineage::child(self);
         ret
     }
     
-    // 死んだ個体の削除、および確率的に子孫を作成
+    // 死んだ個体の削除、およびエネルギーに応じて確率的に子孫を作成
     fn life_manage(animals: &Vec<Self>) -> Vec<Self> {
-        let mut rng = rand::thread_rng();
-        let mut ret: Vec<Self> = Vec::new();
-        for animal in animals {
-            if animal.energy <= 0{
-                continue;
-            }
-            if rng.gen::<f32>() < 1.0 / (ENERGY_MAX as f32) {
-                ret.push(animal.clone().descendant());
-            }
-            ret.push(animal.clone());
-        }
+        reproduce(animals)
+    }
+    
+    // 残りのエネルギー
+    fn energy(&self) -> f64 {
+        self.energy
+    }
+    
+    // エネルギーの変更
+    fn set_energy(&self, energy: f64) -> Self {
+        let mut ret = self.clone();
+        ret.energy = energy;
+        ret
+    }
+    
+    // 生まれてから進んだステップ数
+    fn age(&self) -> u64 {
+        self.age
+    }
+    
+    // 親と生まれた時
+    fn lineage(&self) -> Lineage {
+        self.lineage.clone()
+    }
+    
+    // 系統の変更
+    fn set_lineage(&self, lineage: &Lineage) -> Self {
+        let mut ret = self.clone();
+        ret.lineage = lineage.clone();
+        ret
+    }
+    
+    // 操舵のネットワーク
+    fn brain(&self) -> Option<Brain> {
+        self.brain.clone()
+    }
+    
+    // 操舵のネットワークの変更
+    fn set_brain(&self, brain: Option<Brain>) -> Self {
+        let mut ret = self.clone();
+        ret.brain = brain;
         ret
     }
     
@@ -146,128 +202,77 @@ impl Animal for Cat {
     fn id(&self) -> u64 {
         self.id
     }
-}
-
-impl Cat{
-    // 加速度ベクトルを計算し、速度ベクトルに足す
-    pub fn chase(&self, cats_tree: &QuadTree<Cat>, rats_tree: &QuadTree<Rat>) -> Cat {
-        let next_velocity = self
-            .as_velocity()
-            .add(self.chase_vector(rats_tree))
-            .add(self.separate_same(cats_tree))
-            .add(self.align(cats_tree))
-            .add(self.cohension(cats_tree))
-            .normalize()
-            .mult(self.velocity.len());
-        
-        self
-            .apply_velocity(&next_velocity)
-            .eat(rats_tree)
-            //.move_self()
-    }
     
-    // 追いかける方向の計算
-    fn chase_vector(&self, rats_tree: &QuadTree<Rat>) -> PVector {
-        let near_rats = self.collect_near_pvectors(rats_tree, CHASE_RADIOUS);
-        
-        if near_rats.len() <= 0 {
-            return PVector::zero();
-        }
-        
-        self
-            .calculate_direction(near_rats)
-            .mult(self.chase_weight)
+    // 種類
+    fn species(&self) -> Species {
+        Species::Cat
     }
     
-    // BOIDの個体同士を引き離す操作
-    fn separate_same(&self, cats: &QuadTree<Cat>) -> PVector {
-        let near_animal = self.collect_near_pvectors(cats, SEPARATE_RADIOUS);
-        
-        if near_animal.len() <= 0 {
-            return PVector::zero();
-        }
-        self
-            .calculate_direction(near_animal)
-            .mult(-1.0 * self.separate_weight)
+    // 視野
+    fn vision(&self) -> Vision {
+        self.vision.clone()
     }
     
-    // BOIDの整列処理
-    fn align(&self, cats: &QuadTree<Cat>) -> PVector{
-        let near_cats = self.collect_near_pvectors(cats, ALIGN_RADIOUS);
-        
-        if near_cats.len() <= 0 {
-            return PVector::zero();
+    // 振る舞いの重みの遺伝子
+    fn gene(&self, gene: Gene) -> Option<f64> {
+        match gene {
+            Gene::Chase => Some(self.chase_weight),
+            Gene::Separate => Some(self.separate_weight),
+            Gene::Align => Some(self.align_weight),
+            Gene::Cohension => Some(self.cohension_weight),
+            Gene::LeadTime => Some(self.lead_time),
         }
-        self
-            .add_velocity(&near_cats)
-            .mult(self.align_weight)
     }
-    
-    // BOIDの個体が多い場所に行く操作
-    fn cohension(&self, same_kind: &QuadTree<Cat>) -> PVector {
-        let near_animals = self.collect_near_pvectors(same_kind, COHENSION_RADIOUS);
+}
+
+impl Cat{
+    // 加速度ベクトルを計算し、速度ベクトルに足す。ネットワークを持っていればその出力を使う
+    pub fn chase(&self, cats_tree: &QuadTree<Cat>, rats_tree: &QuadTree<Rat>) -> Cat {
+        let neighbors = Neighbors { cats: cats_tree, rats: rats_tree };
+        let force = match self.brain {
+            Some(ref brain) => neural::steer(self, brain, &neighbors),
+            None => steering::steer(self, &neighbors),
+        };
+        let next_velocity = steering::integrate(self, &force);
         
-        if near_animals.len() <= 0 {
-            return PVector::zero();
-        }
         self
-            .calculate_direction(near_animals)
-            .mult(self.cohension_weight)
+            .apply_velocity(&next_velocity)
+            .move_self()
     }
     
-    // 一定半径以内にいるなら食べる
-    fn eat(&self, rats_tree: &QuadTree<Rat>) -> Cat {
+    // 食べた分のエネルギーと食べた数を足す
+    pub fn feed(&self, energy: f64, ate: u32) -> Cat {
         let mut ret = self.clone();
-        let eaten_cats = rats_tree.search(self, EATEN_RADIOUS);
-        if eaten_cats.len() > 0 {
-            ret.energy += EAT_ENERGY;
-            ret.ate += 1;
-        }
+        ret.energy += energy;
+        ret.ate += ate;
         ret
     }
     
-    // 整列処理のために近くにいる個体の速度ベクトルの平均をとる
-    fn add_velocity(&self, animals: &Vec<Cat>) -> PVector {
-        animals
-            .into_iter()
-            .map(|animal| animal.as_velocity())
-            .fold(PVector::zero(), |folded, vector| vector.add(folded))
-            .normalize()
-    }
-    
     // 子孫を残す時にパラメータを少し変化させる
     fn mutate(value: f64, value_max: f64) -> f64{
-        let mut rng = rand::thread_rng();
-        (rng.gen::<f64>() * MUTATE_ABS * 2.0 - MUTATE_ABS + value).min(value_max).max(0.0)
+        mutate(value, MUTATE_ABS, value_max)
     }
     
-    // 遺伝的アルゴリズムでたくさん食べた個体だけが次の世代で生き残る
-    fn collect_servive(cats: &Vec<Cat>) -> Vec<Cat> {
-        let len = cats.len();
-        let mut ret = cats.clone();
-        ret.sort_by(Cat::comp_by_ate);
-         ret
+    // 遺伝的アルゴリズムで適応度の高い個体だけが次の世代で生き残る
+    // paretoなら複数の目的について支配されていない個体から選ぶ
+    fn collect_servive(cats: &Vec<Cat>, scores: &Vec<Score>) -> Vec<Cat> {
+        let count = (cats.len() / 10).max(1);
+        fitness::rank(scores)
             .into_iter()
-            .take(len / 10)
+            .take(count)
+            .map(|n| cats[n].clone())
             .collect()
     }
     
-    // 次の世代に行く時に食べた順にソートする
-    fn comp_by_ate(a1: &Cat, a2: &Cat) -> std::cmp::Ordering {
-        if a1.ate < a2.ate {
-            std::cmp::Ordering::Less
-        } else if a1.ate > a2.ate {
-            std::cmp::Ordering::Greater
-        }else{
-            std::cmp::Ordering::Equal
-        }
-    }
-    
-    // 生き残った個体を複製し、次の世代にする
-    pub fn next_generation(cats: &Vec<Cat>) -> Vec<Cat>{
+    // scoresで選んで生き残った個体を複製し、size匹の次の世代にする
+    pub fn next_generation(cats: &Vec<Cat>, scores: &Vec<Score>, size: usize) -> Vec<Cat>{
         let mut ret: Vec<Cat> = Vec::new();
-        let superior = Cat::collect_servive(cats);
-        while ret.len() < 20 {
+        let superior = Cat::collect_servive(cats, scores);
+        // 全滅していたら新しい個体からやり直す
+        if superior.is_empty() {
+            return (0..size).map(|_| <Cat as Animal>::new()).collect();
+        }
+        while ret.len() < size {
             let mut appended = superior
                 .clone()
                 .into_iter()
@@ -279,13 +284,47 @@ impl Cat{
         
         ret
             .into_iter()
-            .take(20)
+            .take(size)
             .collect()
     }
     
-    pub fn set_position(&self, position: &PVector) -> Cat{
+    // 同じ遺伝子を持つ新しい個体。別々の場所で同じ遺伝子を試すのに使う
+    // 系統の上では元の個体の子として扱う
+    pub fn twin(&self) -> Cat {
+        let mut ret = Cat::new();
+        ret.chase_weight = self.chase_weight;
+        ret.separate_weight = self.separate_weight;
+        ret.align_weight = self.align_weight;
+        ret.cohension_weight = self.cohension_weight;
+        ret.lead_time = self.lead_time;
+        ret.vision = self.vision.clone();
+        ret.brain = self.brain.clone();
+        ret.lineage = Lineage::child(self);
+        ret
+    }
+    
+    // 記録から読み直した個体のidを戻す
+    pub fn with_id(&self, id: u64) -> Cat {
         let mut ret = self.clone();
-        ret.position = position.clone();
+        ret.id = id;
+        ret
+    }
+    
+    // 食べたねずみの数
+    pub fn ate(&self) -> u32 {
+        self.ate
+    }
+    
+    // 生まれてから使ったエネルギー
+    pub fn spent(&self) -> f64 {
+        self.spent
+    }
+    
+    // 狩りに失敗するなどしてエネルギーを使う
+    pub fn tire(&self, energy: f64) -> Cat {
+        let mut ret = self.clone();
+        ret.energy -= energy;
+        ret.spent += energy;
         ret
     }
 }
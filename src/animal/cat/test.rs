@@ -1,9 +1,15 @@
 #[cfg(test)]
 mod tests{
+    use boundary::Boundary;
     use animal::{Animal, Cat, Rat};
+    use config;
+    use config::Config;
     use consts::*;
+    use fitness;
+    use pareto;
     use pvector::PVector;
     use quad_tree::QuadTree;
+    use steering::Neighbors;
     
     macro_rules! assert_float{
         (
@@ -39,11 +45,13 @@ mod tests{
     
     #[test]
     fn cat_move_self_test(){
+        // 1ステップを1秒にして速度ベクトルの分だけ動かす
+        config::set(Config { dt: 1.0, ..Config::default() });
         let x = 50.0;
         let y = 100.0;
         let vx = 4.0;
         let vy = 2.0;
-        let energy = 100;
+        let energy = 100.0;
         let mut cat = <Cat as Animal>::new();
         
         setpos(&mut cat, &PVector{x: x, y: y});
@@ -53,7 +61,8 @@ mod tests{
         
         cat = cat.move_self();
         
-        assert_eq!(energy - 1, cat.energy);
+        // 1秒分のエネルギーを使う
+        assert_eq!(energy - config::get().cat.metabolism, cat.energy);
         // 画面の外にはみ出さない場合
         assert_eq!(cat.position(), PVector::new(x + vx, y + vy));
         
@@ -74,6 +83,13 @@ mod tests{
        cat.velocity.y *= -1.0;
        cat.position.y = 1.0;
        assert_eq!(HEIGHT - vy + 1.0, cat.move_self().position().y);
+        
+        // 1ステップが短ければその分だけ進む
+        config::set(Config { dt: 0.25, ..Config::default() });
+        setpos(&mut cat, &PVector{x: x, y: y});
+        cat = cat.apply_velocity(&PVector{x: vx, y: vy});
+        assert_eq!(cat.move_self().position(), PVector::new(x + vx * 0.25, y + vy * 0.25));
+        config::set(Config::default());
     }
     
     #[test]
@@ -108,7 +124,7 @@ mod tests{
         let mut cat2 = <Cat as Animal>::new();
         let diff = PVector::new(1.0, 2.0);
         setpos(&mut cat2, &cat1.position().add(diff));
-        let offset = cat1.offset(&cat2);
+        let offset = cat1.offset(&cat2, Boundary::Torus);
         assert_float!(offset.x, offset.x);
         assert_float!(offset.y, offset.y);
     }
@@ -124,6 +140,9 @@ mod tests{
     fn cat_id_test(){
         let cat = <Cat as Animal>::new();
         assert_eq!(cat.id, cat.id());
+        // idは作った順に増えていく
+        let next = <Cat as Animal>::new();
+        assert!(next.id() > cat.id());
     }
     
     #[test]
@@ -143,14 +162,18 @@ mod tests{
             assert!((parent.separate_weight - child.separate_weight).abs() < MUTATE_ABS);
             assert!((parent.align_weight - child.align_weight).abs() < MUTATE_ABS);
             assert!((parent.cohension_weight - child.cohension_weight).abs() < MUTATE_ABS);
+            assert!((parent.lead_time - child.lead_time).abs() <= LEAD_TIME_MUTATE_ABS);
+            assert!(0.0 <= child.lead_time && child.lead_time <= LEAD_TIME_MAX);
+            assert_eq!(Some(parent.id()), child.lineage().parent);
         }
+        assert_eq!(None, parent.lineage().parent);
     }
     
     #[test]
     fn cat_life_manage_test(){
         // 全員死んでいるので、消される
         let mut dead = <Cat as Animal>::new();
-        dead.energy = 0;
+        dead.energy = 0.0;
         let mut dead_cats: Vec<Cat> = Vec::with_capacity(100);
         for _ in 0..100{
             dead_cats.push(dead.clone());
@@ -158,13 +181,122 @@ mod tests{
         let expect_none = Cat::life_manage(&dead_cats);
         assert_eq!(expect_none.len(), 0);
         
-        // 一定の確率で子孫が誕生するので元々の数より多くなる
+        // エネルギーが下限を超えていれば一定の確率で子孫が誕生するので元々の数より多くなる
         let mut all_alive: Vec<Cat> = Vec::with_capacity(100000);
         for _ in 0..100000{
-            all_alive.push(<Cat as Animal>::new());
+            all_alive.push(<Cat as Animal>::new().set_energy(CAT_BIRTH_ENERGY));
         }
         let more_than_handret = Cat::life_manage(&all_alive);
         assert!(more_than_handret.len() > 100000);
+        
+        // 下限に届かなければ増えない
+        let hungry: Vec<Cat> = (0..10000).map(|_| <Cat as Animal>::new()).collect();
+        assert_eq!(10000, Cat::life_manage(&hungry).len());
+    }
+    
+    #[test]
+    fn cat_lifespan_test(){
+        let mut config = Config::default();
+        config.dt = 1.0;
+        config.cat.life.lifespan = Some(3.0);
+        config::set(config);
+        
+        // 動くたびに1ステップ年を取り、寿命を迎えると取り除かれる
+        let mut cat = <Cat as Animal>::new();
+        assert_eq!(0, cat.age());
+        cat = cat.move_self().move_self();
+        assert_eq!(2, cat.age());
+        assert_eq!(1, Cat::life_manage(&vec![cat.clone()]).len());
+        cat = cat.move_self();
+        assert_eq!(0, Cat::life_manage(&vec![cat.clone()]).len());
+        // 子は0歳から
+        assert_eq!(0, cat.descendant().age());
+        config::set(Config::default());
+    }
+    
+    #[test]
+    fn cat_collect_servive_test(){
+        // 適応度の高い上位1割が残る
+        let cats: Vec<Cat> = (0..20).map(|n| <Cat as Animal>::new().feed(0.0, n)).collect();
+        let superior = Cat::collect_servive(&cats, &fitness::score_all(&cats));
+        assert_eq!(2, superior.len());
+        assert_eq!(19, superior[0].ate());
+        assert_eq!(18, superior[1].ate());
+        
+        // 設定した適応度で選ぶ
+        let mut config = Config::default();
+        config.fitness = fitness::WeightedFitness::parse_list("1 final_energy").unwrap();
+        config::set(config);
+        let cats: Vec<Cat> = (0..10).map(|n| <Cat as Animal>::new().feed(0.0, n).set_energy(100.0 - n as f64)).collect();
+        let superior = Cat::collect_servive(&cats, &fitness::score_all(&cats));
+        assert_eq!(0, superior[0].ate());
+        
+        // paretoなら食べた数とエネルギーの両方で支配されていない猫から選ぶ
+        let mut config = Config::default();
+        config.selection = pareto::Selection::Pareto;
+        config.objectives = pareto::parse_objectives("kills; final_energy").unwrap();
+        config::set(config);
+        let mut cats: Vec<Cat> = (0..19).map(|_| <Cat as Animal>::new().set_energy(10.0)).collect();
+        cats.push(<Cat as Animal>::new().feed(0.0, 5).set_energy(10.0));
+        cats.push(<Cat as Animal>::new().set_energy(500.0));
+        let superior = Cat::collect_servive(&cats, &fitness::score_all(&cats));
+        config::set(Config::default());
+        assert_eq!(2, superior.len());
+        assert!(superior.iter().any(|cat| cat.ate() == 5));
+        assert!(superior.iter().any(|cat| cat.energy() == 500.0));
+    }
+    
+    #[test]
+    fn cat_birth_share_test(){
+        let mut config = Config::default();
+        config.cat.birth.rate = std::f64::MAX;
+        config.cat.birth.share = 0.25;
+        config::set(config);
+        
+        let parent = <Cat as Animal>::new().set_energy(2000.0);
+        let family = Cat::life_manage(&vec![parent.clone()]);
+        config::set(Config::default());
+        
+        // 子は親の位置に生まれ、エネルギーを分け合う
+        assert_eq!(2, family.len());
+        let child = family.iter().find(|cat| cat.id() != parent.id()).unwrap();
+        let after = family.iter().find(|cat| cat.id() == parent.id()).unwrap();
+        assert_eq!(parent.position(), child.position());
+        assert_eq!(500.0, child.energy);
+        assert_eq!(1500.0, after.energy);
+    }
+    
+    #[test]
+    fn cat_capacity_test(){
+        let mut config = Config::default();
+        config.cat.birth.rate = std::f64::MAX;
+        config.cat.birth.capacity = Some(50.0);
+        config::set(config);
+        
+        // 毎回エネルギーを満たしても収容力より増えない
+        let mut cats: Vec<Cat> = (0..5).map(|_| <Cat as Animal>::new()).collect();
+        for _ in 0..100 {
+            let fed: Vec<Cat> = cats.iter().map(|cat| cat.set_energy(2.0 * CAT_BIRTH_ENERGY)).collect();
+            cats = Cat::life_manage(&fed);
+            assert!(cats.len() <= 50);
+        }
+        assert_eq!(50, cats.len());
+        
+        // 収容力に近いほど生まれにくい
+        let mut config = Config::default();
+        config.cat.birth.capacity = Some(1000.0);
+        config::set(config);
+        let rich: Vec<Cat> = (0..900).map(|_| <Cat as Animal>::new().set_energy(2.0 * CAT_BIRTH_ENERGY)).collect();
+        let sparse: Vec<Cat> = rich.iter().take(100).cloned().collect();
+        let mut crowded_births = 0;
+        let mut sparse_births = 0;
+        for _ in 0..20 {
+            crowded_births += Cat::life_manage(&rich).len() - rich.len();
+            sparse_births += Cat::life_manage(&sparse).len() - sparse.len();
+        }
+        config::set(Config::default());
+        // 1匹あたりの出生は混んでいる方が少ない
+        assert!(crowded_births * 100 < sparse_births * 900);
     }
     
     #[test]
@@ -196,159 +328,13 @@ mod tests{
             cats.push(other.clone());
         }
         let cats_tree = QuadTree::new(&cats);
-        let expect_none = cat.collect_near_pvectors(&cats_tree, 1.0);
+        let rats_tree: QuadTree<Rat> = QuadTree::new(&Vec::new());
+        let neighbors = Neighbors { cats: &cats_tree, rats: &rats_tree };
+        let expect_none = cat.collect_near_pvectors(&cats_tree, &neighbors, 1.0);
         assert_eq!(expect_none.len(), 0);
         
         //全部一定半径内にいる場合 
-        let not_dicrease = cat.collect_near_pvectors(&cats_tree, 2.0);
+        let not_dicrease = cat.collect_near_pvectors(&cats_tree, &neighbors, 2.0);
         assert!(not_dicrease.len() == 100);
     }
-    
-    #[test]
-    fn add_velocity(){
-        let cat = <Cat as Animal>::new();
-        let mut other = <Cat as Animal>::new();
-        let x = 0.6;
-        let y = 0.8;
-        other = other.apply_velocity(&PVector::new(x, y));
-        let mut cats: Vec<Cat> = Vec::with_capacity(100);
-        for _ in 0..100 {
-            cats.push(other.clone());
-        }
-        
-        let ret = cat.add_velocity(&cats);
-        assert_float!(x, ret.x);
-        assert_float!(y, ret.y);
-    }
-    
-    #[test]
-    fn cat_eat_test(){
-        let mut cat = <Cat as Animal>::new();
-        let rat = <Rat as Animal>::new();
-        let eaten_diff = EATEN_RADIOUS / 2.0;
-        let mut offset = PVector::new(eaten_diff, eaten_diff);
-        setpos(&mut cat, &rat.position.add(offset));
-        let mut eaten: Vec<Rat> = Vec::with_capacity(100);
-        for _ in 0..100 {
-            eaten.push(rat.clone());
-        }
-        let eaten_tree = QuadTree::new(&eaten);
-        let result = cat.eat(&eaten_tree);
-        assert_eq!(cat.energy + EAT_ENERGY, result.energy);
-        assert_eq!(result.ate, 1);
-        
-        let not_eat_diff = EATEN_RADIOUS;
-        offset = PVector::new(not_eat_diff, not_eat_diff);
-        setpos(&mut cat, &rat.position.add(offset));
-        
-        let not_eat = cat.eat(&eaten_tree);
-        assert_eq!(cat.energy, not_eat.energy);
-        assert_eq!(not_eat.ate, 0);
-    }
-    
-    #[test]
-    fn cat_chase_vector_test(){
-        let mut cat = <Cat as Animal>::new();
-        let rat = <Rat as Animal>::new();
-        let chased_diff = CHASE_RADIOUS / 2.0;
-        let dx = 0.6;
-        let dy = 0.8;
-        let mut offset = PVector::new(-1.0 * dx * chased_diff, -1.0 * dy * chased_diff);
-        setpos(&mut cat, &rat.position().add(offset));
-        let mut chased: Vec<Rat> = Vec::with_capacity(100);
-        for _ in 0..100 {
-            chased.push(rat.clone());
-        }
-        let chased_tree = QuadTree::new(&chased);
-        let result = cat.chase_vector(&chased_tree);
-        
-        assert_float!(dx * cat.chase_weight, result.x);
-        assert_float!(dy * cat.chase_weight, result.y);
-        
-        let not_chase_diff = CHASE_RADIOUS;
-        offset = PVector::new(-0.6 * not_chase_diff, -0.8 * not_chase_diff);
-        setpos(&mut cat, &rat.position().add(offset));
-        
-        let not_chase = cat.chase_vector(&chased_tree);
-        assert_eq!(not_chase.x, 0.0);
-        assert_eq!(not_chase.y, 0.0);
-    }
-    
-    #[test]
-    fn cat_separate_same_test(){
-        let mut cat = <Cat as Animal>::new();
-        let other = <Cat as Animal>::new();
-        let separate_diff = SEPARATE_RADIOUS / 2.0;
-        let x = 0.6;
-        let y = 0.8;
-        setpos(&mut cat, &PVector::new(separate_diff * x, separate_diff * y).add(other.position()));
-        let mut others: Vec<Cat> = Vec::with_capacity(100);
-        for _ in 0..100 {
-            others.push(other.clone());
-        }
-        let others_tree = QuadTree::new(&others);
-        let result = cat.separate_same(&others_tree);
-        
-        assert_float!(x * cat.separate_weight, result.x);
-        assert_float!(y * cat.separate_weight, result.y);
-        
-        let not_separate_diff = SEPARATE_RADIOUS;
-        setpos(&mut cat, &PVector::new(not_separate_diff, not_separate_diff).add(other.position()));
-        
-        let not_separate = cat.separate_same(&others_tree);
-        assert_eq!(not_separate.x, 0.0);
-        assert_eq!(not_separate.y, 0.0);
-    }
-    
-    #[test]
-    fn cat_align_test(){
-        let mut cat = <Cat as Animal>::new();
-        let align_diff = ALIGN_RADIOUS / 2.0;
-        let x = 0.6;
-        let y = 0.8;
-        let other = <Cat as Animal>::new().apply_velocity(&PVector{x: x, y: y});
-        setpos(&mut cat, &PVector::new(-align_diff, -align_diff).add(other.position()));
-        let mut others: Vec<Cat> = Vec::with_capacity(100);
-        for _ in 0..100 {
-            others.push(other.clone());
-        }
-        let others_tree = QuadTree::new(&others);
-        let result = cat.align(&others_tree);
-        
-        assert_float!(x * cat.align_weight, result.x);
-        assert_float!(y * cat.align_weight, result.y);
-        
-        let not_align_diff = ALIGN_RADIOUS;
-        setpos(&mut cat, &PVector::new(-not_align_diff, -not_align_diff).add(other.position()));
-        
-        let not_align = cat.align(&others_tree);
-        assert_eq!(not_align.x, 0.0);
-        assert_eq!(not_align.y, 0.0);
-    }
-    
-    #[test]
-    fn cat_cohension_test(){
-        let mut cat = <Cat as Animal>::new();
-        let other = <Cat as Animal>::new();
-        let cohension_diff = COHENSION_RADIOUS / 2.0;
-        let x = 0.6;
-        let y = 0.8;
-        setpos(&mut cat, &PVector::new(-cohension_diff * x, -cohension_diff * y).add(other.position()));
-        let mut others: Vec<Cat> = Vec::with_capacity(100);
-        for _ in 0..100 {
-            others.push(other.clone());
-        }
-        let others_tree = QuadTree::new(&others);
-        let result = cat.cohension(&others_tree);
-        
-        assert_float!(x * cat.cohension_weight, result.x);
-        assert_float!(y * cat.cohension_weight, result.y);
-        
-        let not_cohension_diff = COHENSION_RADIOUS;
-        setpos(&mut cat, &PVector::new(-not_cohension_diff, -not_cohension_diff).add(other.position()));
-        
-        let not_cohension = cat.cohension(&others_tree);
-        assert_eq!(not_cohension.x, 0.0);
-        assert_eq!(not_cohension.y, 0.0);
-    }
 }
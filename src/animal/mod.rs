@@ -2,8 +2,44 @@ mod cat;
 //mod test;
 mod rat;
 
+use boundary::Boundary;
 use pvector::PVector;
 use quad_tree::QuadTree;
+use vision::Vision;
+use rand::prelude::*;
+use random;
+use config;
+use neural::Brain;
+use steering::Neighbors;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+// 次に割り当てる個体のid。スレッドをまたいでも重ならない
+static NEXT_ID: AtomicU64 = AtomicU64::new(1);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Species {
+    Cat,
+    Rat,
+}
+
+// 振る舞いの重みや先読み時間として使える遺伝子
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Gene {
+    Chase,
+    Separate,
+    Align,
+    Cohension,
+    LeadTime,
+}
+
+// 系統。親のid、生まれたステップと世代
+#[derive(Debug, Clone, PartialEq)]
+pub struct Lineage {
+    pub parent: Option<u64>, // 最初の世代や新しく作った個体はNone
+    pub mate: Option<u64>,   // 交叉で生まれた時のもう一方の親。親が一匹ならNone
+    pub born: u64,           // 世代が始まってから生まれるまでのステップ数
+    pub generation: u32,     // 何世代目に生まれたか。1から数える
+}
 
 #[derive(Debug, Clone)]
 pub struct Cat{
@@ -13,17 +49,28 @@ pub struct Cat{
     pub separate_weight: f64,
     pub align_weight: f64,
     pub cohension_weight: f64,
+    pub vision: Vision,
+    pub lead_time: f64,
+    pub brain: Option<Brain>, // ネットワークで操舵する時のネットワーク
     ate: u32,
-    energy: u64,
+    energy: f64,
+    spent: f64, // 生まれてから使ったエネルギー
+    age: u64,
     id: u64,
+    lineage: Lineage,
 }
 
 #[derive(Clone)]
 pub struct Rat{
     position: PVector,
     velocity: PVector,
-    energy: u64,
+    pub vision: Vision,
+    pub lead_time: f64,
+    pub brain: Option<Brain>, // ネットワークで操舵する時のネットワーク
+    energy: f64,
+    age: u64,
     id: u64,
+    lineage: Lineage,
 }
 
 pub trait Animal : Clone {
@@ -32,13 +79,134 @@ pub trait Animal : Clone {
     fn move_self(&self) -> Self; // 速度ベクトル分だけ移動
     fn as_velocity(&self) -> PVector; // 速度ベクトルを返す
     fn apply_velocity(&self, &PVector) -> Self; // 速度ベクトルの変更
+    fn set_position(&self, position: &PVector) -> Self; // 位置の変更
     fn is_within<T: Animal>(&self, other: &T, radious: f64) -> bool; // 一定半径以内にいるかどうか
-    fn offset<T: Animal>(&self, other: &T) -> PVector; // ２匹の距離を計算
-    fn collect_near_pvectors<T: Animal>(&self, animals: &QuadTree<T>, radious: f64) -> Vec<T>; // 一定半径以内にいるものを集める
+    fn offset<T: Animal>(&self, other: &T, boundary: Boundary) -> PVector; // ２匹の距離を計算
+    fn collect_near_pvectors<T: Animal>(&self, animals: &QuadTree<T>, neighbors: &Neighbors, radious: f64) -> Vec<T>; // 一定半径以内に見えているものを集める
     fn position(&self) -> PVector; // 現在の位置を返す
     fn calculate_direction<T: Animal>(&self, animals: Vec<T>) -> PVector; // 相対位置の平均を計算
     fn descendant(&self) -> Self; // 子孫。増殖のために使う
     fn life_manage(animals: &Vec<Self>) -> Vec<Self>; // 死んだ個体の削除、もしくは確率的に個体を増殖させる
     fn is_same<T: Animal>(&self, other: &T) -> bool; // 二つの個体が同じか
     fn id(&self) -> u64; // 個体の識別に使う
+    fn species(&self) -> Species; // 種類
+    fn vision(&self) -> Vision; // 視野
+    fn gene(&self, gene: Gene) -> Option<f64>; // 遺伝子の値。持っていなければNone
+    fn energy(&self) -> f64; // 残りのエネルギー
+    fn set_energy(&self, energy: f64) -> Self; // エネルギーの変更
+    fn age(&self) -> u64; // 生まれてから進んだステップ数
+    fn lineage(&self) -> Lineage; // 親と生まれた時
+    fn set_lineage(&self, lineage: &Lineage) -> Self; // 系統の変更
+    fn brain(&self) -> Option<Brain>; // 操舵のネットワーク。boidsならNone
+    fn set_brain(&self, brain: Option<Brain>) -> Self; // 操舵のネットワークの変更
+}
+
+impl Species {
+    // 設定ファイルの名前から変換
+    pub fn parse(name: &str) -> Result<Species, String> {
+        match name {
+            "cat" => Ok(Species::Cat),
+            "rat" => Ok(Species::Rat),
+            _ => Err(format!("unknown species `{}`", name)),
+        }
+    }
+    
+    // 設定ファイルや出力で使う名前
+    pub fn name(&self) -> &'static str {
+        match *self {
+            Species::Cat => "cat",
+            Species::Rat => "rat",
+        }
+    }
+}
+
+impl Gene {
+    // 設定ファイルの名前から変換
+    pub fn parse(name: &str) -> Result<Gene, String> {
+        match name {
+            "chase" => Ok(Gene::Chase),
+            "separate" => Ok(Gene::Separate),
+            "align" => Ok(Gene::Align),
+            "cohension" => Ok(Gene::Cohension),
+            "lead_time" => Ok(Gene::LeadTime),
+            _ => Err(format!("unknown gene `{}`", name)),
+        }
+    }
+    
+    // 種がこの遺伝子を持っているか。ねずみは先読み時間だけを持つ
+    pub fn carried_by(&self, species: Species) -> bool {
+        match species {
+            Species::Cat => true,
+            Species::Rat => *self == Gene::LeadTime,
+        }
+    }
+}
+
+impl Lineage {
+    // 親のいない最初の世代の個体
+    pub fn new() -> Lineage {
+        Lineage { parent: None, mate: None, born: 0, generation: 1 }
+    }
+    
+    // parentの子として今のステップに生まれた系統
+    pub fn child<A: Animal>(parent: &A) -> Lineage {
+        let lineage = parent.lineage();
+        Lineage {
+            parent: Some(parent.id()),
+            mate: None,
+            born: lineage.born + parent.age(),
+            generation: lineage.generation,
+        }
+    }
+}
+
+// 重ならないidを順に割り当てる
+pub fn next_id() -> u64 {
+    NEXT_ID.fetch_add(1, Ordering::Relaxed)
+}
+
+// 子孫を残す時にパラメータを±absの範囲で変化させ、0からvalue_maxに収める
+pub fn mutate(value: f64, abs: f64, value_max: f64) -> f64 {
+    let mut rng = random::rng();
+    (rng.gen::<f64>() * abs * 2.0 - abs + value).min(value_max).max(0.0)
+}
+
+// 生まれてからの秒数
+pub fn age_seconds<A: Animal>(animal: &A) -> f64 {
+    animal.age() as f64 * config::get().dt
+}
+
+// エネルギーが尽きたか、寿命を迎えたか
+pub fn is_dead<A: Animal>(animal: &A) -> bool {
+    let config = config::get();
+    animal.energy() <= 0.0 || config.species(animal.species()).life.is_expired(age_seconds(animal))
+}
+
+// 死んだ個体を取り除き、エネルギーが閾値を超えた個体を確率的に増やす
+// 子はbirth_shareの割合のエネルギーを親から受け取り、親の位置に生まれる
+// 環境収容力があれば生まれる確率は混み具合に応じて下がり、数は収容力を超えない
+pub fn reproduce<A: Animal>(animals: &Vec<A>) -> Vec<A> {
+    let config = config::get();
+    let birth = &config.species(animals.first().map_or(Species::Cat, |animal| animal.species())).birth;
+    let mut rng = random::rng();
+    let alive: Vec<&A> = animals.iter().filter(|animal| !is_dead(*animal)).collect();
+    let population = alive.len() as f64;
+    let (crowding, room) = match birth.capacity {
+        Some(capacity) => ((1.0 - population / capacity).max(0.0), (capacity - population).max(0.0) as usize),
+        None => (1.0, alive.len()),
+    };
+    let chance = birth.rate * config.dt * crowding;
+    let mut born = 0;
+    let mut ret: Vec<A> = Vec::with_capacity(alive.len());
+    for animal in alive {
+        if born < room && animal.energy() >= birth.energy && rng.gen::<f64>() < chance {
+            let share = animal.energy() * birth.share;
+            ret.push(animal.descendant().set_position(&animal.position()).set_energy(share));
+            ret.push(animal.set_energy(animal.energy() - share));
+            born += 1;
+        } else {
+            ret.push(animal.clone());
+        }
+    }
+    ret
 }
@@ -1,16 +1,27 @@
 mod test;
 
+use boundary::Boundary;
 use pvector::PVector;
-use animal::{Animal, Rat, Cat};
+use animal::{Animal, Rat, Cat, Species, Gene, age_seconds, mutate, next_id, reproduce, Lineage};
 use consts::*;
 use rand::prelude::*;
+use random;
+use config;
+use food::Food;
+use obstacle;
+use vision;
+use vision::Vision;
+use neural;
+use neural::Brain;
+use steering;
+use steering::Neighbors;
 use quad_tree::{QuadTree, Rectangle};
 
 
 impl Animal for Rat {
     // インスタンス初期化
     fn new() -> Self {
-        let mut rng = rand::thread_rng();
+        let mut rng = random::rng();
         let theta: f64 = rng.gen::<f64>() * 2.0 * (std::f64::consts::PI);
         let x = rng.gen::<f64>() * WIDTH;
         let y = rng.gen::<f64>() * HEIGHT;
@@ -18,44 +29,45 @@ impl Animal for Rat {
         Rat {
             position: PVector::new(x, y),
             velocity: PVector::new(theta.cos(), theta.sin()).mult(velocity),
+            vision: Vision::new(&config::get().rat.vision),
+            lead_time: 0.0, // 先読みしないところから始め、進化で伸ばす
+            brain: Brain::new(Species::Rat),
             energy: ENERGY_MAX,
-            id: rng.gen::<u64>(),
+            age: 0,
+            id: next_id(),
+            lineage: Lineage::new(),
         }
     }
     
     // 次のフレームの計算
-    fn next_states(rats: &Vec<Self>, cats_tree: &QuadTree<Cat>, _rats_tree: &QuadTree<Rat>) -> Vec<Self> {
-        let alive_rats = Rat::delete_eaten(cats_tree, rats);
-        let ret = alive_rats
+    fn next_states(rats: &Vec<Self>, cats_tree: &QuadTree<Cat>, rats_tree: &QuadTree<Rat>) -> Vec<Self> {
+        let ret = rats
             .into_iter()
-            .map(|rat| rat.run_away(cats_tree))
+            .map(|rat| rat.run_away(cats_tree, rats_tree))
             .collect();
         <Rat as Animal>::life_manage(&ret)
     }
     
-    // 現在位置に速度ベクトルを足す
+    // 現在位置に速度ベクトルの1ステップ分を足す
     fn move_self(&self) -> Rat {
+        let config = config::get();
         let mut ret = self.clone();
-        let mut new_pos = ret.position.add(self.clone().velocity);
+        let new_pos = ret.position.add(self.velocity.mult(config.dt));
+        // 老いるほど多くのエネルギーを使う
+        ret.energy -= config.rat.life.metabolism(config.rat.metabolism, age_seconds(self)) * config.dt;
+        ret.age += 1;
         
-        // 画面からはみ出た時の操作
-        if new_pos.x > WIDTH {
-            new_pos.x -= WIDTH;
-        }
-        
-        if new_pos.x < 0.0 {
-            new_pos.x += WIDTH;
-        }
+        // 障害物には入れない
+        let (new_pos, velocity) = obstacle::collide(Species::Rat, &new_pos, &self.velocity);
         
-        if new_pos.y > HEIGHT {
-            new_pos.y -= HEIGHT;
-        }
-        
-        if new_pos.y < 0.0 {
-            new_pos.y += HEIGHT;
+        // 画面からはみ出た時の操作。吸収されたら寿命を尽きさせて取り除く
+        match config.boundary.apply(&new_pos, &velocity) {
+            Some((position, velocity)) => {
+                ret.position = position;
+                ret.velocity = velocity;
+            }
+            None => ret.energy = 0.0,
         }
-        
-        ret.position = new_pos;
         ret
     }
     
@@ -71,9 +83,16 @@ impl Animal for Rat {
         ret
     }
     
+    //位置の変更
+    fn set_position(&self, position: &PVector) -> Self {
+        let mut ret = self.clone();
+        ret.position = position.clone();
+        ret
+    }
+    
     // 一定半径にいるかどうか
     fn is_within<T: Animal>(&self, other: &T, radious: f64) -> bool {
-        self.offset(other).len() < radious
+        self.offset(other, config::get().boundary).len() < radious
     }
     
     // 現在位置を返す
@@ -82,27 +101,23 @@ impl Animal for Rat {
     }
     
     // 相対位置の計算
-    fn offset<T: Animal>(&self, other: &T) -> PVector {
+    fn offset<T: Animal>(&self, other: &T, boundary: Boundary) -> PVector {
         let self_vec = self.position();
         let other_vec = other.position();
-        self_vec.offset(&other_vec)
+        self_vec.offset(&other_vec, boundary)
     }
     
-    // 近くにいる個体を集める
-    fn collect_near_pvectors<T: Animal>(&self, animals: &QuadTree<T>, radious: f64) -> Vec<T> {
-        animals
-            .search(self, radious)
-            .into_iter()
-            .filter(|animal| !animal.is_same(self))
-            .map(|animal| animal.clone())
-            .collect()
+    // 近くに見えている個体を集める。neighborsの個体は視線を遮る
+    fn collect_near_pvectors<T: Animal>(&self, animals: &QuadTree<T>, neighbors: &Neighbors, radious: f64) -> Vec<T> {
+        vision::collect_visible(self, animals, neighbors, radious)
     }
     
     // 相対位置の平均を計算
     fn calculate_direction<T: Animal>(&self, animals: Vec<T>) -> PVector {
+        let boundary = config::get().boundary;
         animals
             .into_iter()
-            .map(|animal| self.offset(&animal))
+            .map(|animal| self.offset(&animal, boundary))
             .fold(PVector::zero(), |folded, vector| vector.add(folded))
             .normalize()
     }
@@ -111,6 +126,12 @@ impl Animal for Rat {
     fn descendant(&self) -> Self{
         let mut ret = Rat::new();
         ret.energy = ENERGY_MAX;
+        if config::get().rat.vision.evolvable {
+            ret.vision = self.vision.mutate();
+        }
+        ret.lead_time = mutate(self.lead_time, LEAD_TIME_MUTATE_ABS, LEAD_TIME_MAX);
+        ret.brain = self.brain.as_ref().map(|brain| brain.mutate());
+        ret.lineage = Lineage::child(self);
         ret
     }
     
@@ -119,19 +140,49 @@ impl Animal for Rat {
         self.id
     }
     
-    // 死んだ個体の削除、および確率的に子孫を作成
+    // 死んだ個体の削除、およびエネルギーに応じて確率的に子孫を作成
     fn life_manage(animals: &Vec<Self>) -> Vec<Self> {
-        let mut rng = rand::thread_rng();
-        let mut ret: Vec<Self> = Vec::new();
-        for animal in animals {
-            if animal.energy <= 0{
-                continue;
-            }
-            if rng.gen::<f32>() < 1.0 / (ENERGY_MAX as f32) {
-                ret.push(animal.clone().descendant());
-            }
-            ret.push(animal.clone());
-        }
+        reproduce(animals)
+    }
+    
+    // 残りのエネルギー
+    fn energy(&self) -> f64 {
+        self.energy
+    }
+    
+    // エネルギーの変更
+    fn set_energy(&self, energy: f64) -> Self {
+        let mut ret = self.clone();
+        ret.energy = energy;
+        ret
+    }
+    
+    // 生まれてから進んだステップ数
+    fn age(&self) -> u64 {
+        self.age
+    }
+    
+    // 親と生まれた時
+    fn lineage(&self) -> Lineage {
+        self.lineage.clone()
+    }
+    
+    // 系統の変更
+    fn set_lineage(&self, lineage: &Lineage) -> Self {
+        let mut ret = self.clone();
+        ret.lineage = lineage.clone();
+        ret
+    }
+    
+    // 操舵のネットワーク
+    fn brain(&self) -> Option<Brain> {
+        self.brain.clone()
+    }
+    
+    // 操舵のネットワークの変更
+    fn set_brain(&self, brain: Option<Brain>) -> Self {
+        let mut ret = self.clone();
+        ret.brain = brain;
         ret
     }
     
@@ -139,45 +190,61 @@ impl Animal for Rat {
     fn is_same<T: Animal>(&self, other: &T) -> bool{
         self.id() == other.id()
     }
+    
+    // 種類
+    fn species(&self) -> Species {
+        Species::Rat
+    }
+    
+    // 視野
+    fn vision(&self) -> Vision {
+        self.vision.clone()
+    }
+    
+    // ねずみは先読み時間の遺伝子だけを持つ
+    fn gene(&self, gene: Gene) -> Option<f64> {
+        match gene {
+            Gene::LeadTime => Some(self.lead_time),
+            _ => None,
+        }
+    }
 }
 
 impl Rat {
     // 1個体の次の状態
-    fn run_away(&self, cats_tree: &QuadTree<Cat>) -> Rat {
-        let next_velocity = self
-            .as_velocity()
-            .add(self.run_away_vector(cats_tree))
-            .normalize()
-            .mult(self.velocity.len());
+    fn run_away(&self, cats_tree: &QuadTree<Cat>, rats_tree: &QuadTree<Rat>) -> Rat {
+        let neighbors = Neighbors { cats: cats_tree, rats: rats_tree };
+        let force = match self.brain {
+            Some(ref brain) => neural::steer(self, brain, &neighbors),
+            None => steering::steer(self, &neighbors),
+        };
+        let next_velocity = steering::integrate(self, &force);
         self
             .apply_velocity(&next_velocity)
-            //.move_self()
+            .move_self()
     }
     
-    // 逃げる方向をか速度ベクトルにする
-    fn run_away_vector(&self, cats_tree: &QuadTree<Cat>) -> PVector {
-        let near_cats = self.collect_near_pvectors(cats_tree, RUNAWAY_RADIOUS);
-        
-        if near_cats.len() <= 0 {
-            return PVector::zero();
-        }
-        
-        self
-            .calculate_direction(near_cats)
-            .mult(-1.0)
-    }
-    
-    // 食べられているかどうかを判定
-    fn eaten(&self, cats_tree: &QuadTree<Cat>) -> bool{
-        cats_tree.search(self, 1.0).len() > 0
+    // いるマスの草を食べてエネルギーにする。エネルギーはENERGY_MAXまで
+    pub fn graze(&self, food: &mut Food) -> Rat {
+        let config = config::get();
+        let room = (ENERGY_MAX - self.energy).max(0.0);
+        let bite = (config.rat.graze_rate * config.dt).min(room);
+        let mut ret = self.clone();
+        ret.energy += food.graze(&self.position, bite);
+        ret
     }
     
-    // 食べられたらいなくなる
-    fn delete_eaten(cats_tree: &QuadTree<Cat>, rats: &Vec<Rat>) -> Vec<Rat> {
+    // 世代の終わりまで生き残ったねずみの子孫をsize匹作る。先読みや視覚の遺伝子が世代をまたいで進化する
+    // 全滅していたら新しい個体からやり直す
+    pub fn next_generation(rats: &Vec<Rat>, size: usize) -> Vec<Rat> {
+        if rats.is_empty() {
+            return (0..size).map(|_| <Rat as Animal>::new()).collect();
+        }
         rats
-            .into_iter()
-            .filter(|rat| !rat.eaten(cats_tree))
-            .map(|rat| rat.clone())
+            .iter()
+            .cycle()
+            .take(size)
+            .map(|rat| rat.descendant())
             .collect()
     }
 }
@@ -1,9 +1,14 @@
 #[cfg(test)]
 mod tests{
+    use boundary::Boundary;
     use animal::{Animal, Cat, Rat};
+    use config;
+    use config::Config;
     use consts::*;
+    use food::Food;
     use pvector::PVector;
     use quad_tree::QuadTree;
+    use steering::Neighbors;
     
     fn setpos(animal: &mut Rat, pos: &PVector){
         animal.position = pos.clone();
@@ -34,13 +39,15 @@ mod tests{
     
     #[test]
     fn rat_move_self_test(){
+        // 1ステップを1秒にして速度ベクトルの分だけ動かす
+        config::set(Config { dt: 1.0, ..Config::default() });
         let position = PVector::new(50.0, 100.0);
         let velocity = PVector::new(4.0, 2.0);
         let x = 50.0;
         let y = 100.0;
         let vx = 4.0;
         let vy = 2.0;
-        let energy = 100;
+        let energy = 100.0;
         let mut rat = <Rat as Animal>::new();
         
         setpos(&mut rat, &position);
@@ -50,6 +57,9 @@ mod tests{
         
         rat = rat.move_self();
         
+        // 1秒分のエネルギーを使う
+        assert_eq!(energy - config::get().rat.metabolism, rat.energy);
+        
         // 画面の外にはみ出さない場合
         assert_eq!(x + vx, rat.position.x);
         assert_eq!(y + vy, rat.position.y);
@@ -71,6 +81,7 @@ mod tests{
        rat.velocity.y *= -1.0;
        rat.position.y = 1.0;
        assert_eq!(HEIGHT - vy + 1.0, rat.move_self().position.y);
+        config::set(Config::default());
     }
     
     #[test]
@@ -108,7 +119,7 @@ mod tests{
         let dy = 2.0;
         let offset = PVector::new(dx, dy);
         setpos(&mut rat2, &rat1.position.add(offset));
-        let offset = rat1.offset(&rat2);
+        let offset = rat1.offset(&rat2, Boundary::Torus);
         assert_eq!(dx, offset.x);
         assert_eq!(dy, offset.y);
     }
@@ -151,7 +162,7 @@ mod tests{
     fn rat_life_manage_test(){
         // 全員死んでいるので、消される
         let mut dead = <Rat as Animal>::new();
-        dead.energy = 0;
+        dead.energy = 0.0;
         let mut dead_rats: Vec<Rat> = Vec::with_capacity(100);
         for _ in 0..100{
             dead_rats.push(dead.clone());
@@ -199,75 +210,57 @@ mod tests{
             rats.push(other.clone());
         }
         let rats_tree = QuadTree::new(&rats);
-        let expect_none = rat.collect_near_pvectors(&rats_tree, 1.0);
+        let cats_tree: QuadTree<Cat> = QuadTree::new(&Vec::new());
+        let neighbors = Neighbors { cats: &cats_tree, rats: &rats_tree };
+        let expect_none = rat.collect_near_pvectors(&rats_tree, &neighbors, 1.0);
         assert_eq!(expect_none.len(), 0);
         
         //全部一定半径内にいる場合 
-        let not_dicrease = rat.collect_near_pvectors(&rats_tree, 2.0);
+        let not_dicrease = rat.collect_near_pvectors(&rats_tree, &neighbors, 2.0);
         assert!(not_dicrease.len() == 100);
     }
     
     #[test]
-    fn rat_runaway_vector_test(){
+    fn rat_graze_test(){
+        config::set(Config { dt: 1.0, ..Config::default() });
+        let mut food = Food::new();
         let mut rat = <Rat as Animal>::new();
-        let cat = <Cat as Animal>::new();
-        let runaway_diff = RUNAWAY_RADIOUS / 2.0;
-        let x = 0.6;
-        let y = 0.8;
-        setpos(&mut rat, &PVector::new(runaway_diff * x, runaway_diff * y).add(cat.position()));
-        let mut cats: Vec<Cat> = Vec::with_capacity(100);
-        for _ in 0..100 {
-            cats.push(cat.clone());
-        }
-        let cats_tree = QuadTree::new(&cats);
-        let result = rat.run_away_vector(&cats_tree);
+        setpos(&mut rat, &PVector::new(100.0, 100.0));
         
-        assert_float!(x, result.x);
-        assert_float!(y, result.y);
+        // 満腹なら食べない
+        assert_eq!(ENERGY_MAX, rat.graze(&mut food).energy);
+        assert_eq!(FOOD_CAPACITY, food.amount(&rat.position));
         
-        let not_chase_diff = CHASE_RADIOUS;
-        setpos(&mut rat, &PVector::new(not_chase_diff, not_chase_diff).add(cat.position()));
+        // 1秒に食べられる量までエネルギーになる
+        rat.energy = 100.0;
+        let fed = rat.graze(&mut food);
+        assert_eq!(100.0 + RAT_GRAZE_RATE, fed.energy);
+        assert_eq!(FOOD_CAPACITY - RAT_GRAZE_RATE, food.amount(&rat.position));
         
-        let not_chase = rat.run_away_vector(&cats_tree);
-        assert_eq!(not_chase.x, 0.0);
-        assert_eq!(not_chase.y, 0.0);
+        // 草がなければ飢えて死ぬ
+        food.graze(&rat.position, FOOD_CAPACITY);
+        rat.energy = RAT_METABOLISM / 2.0;
+        let starved = rat.apply_velocity(&PVector::zero()).move_self().graze(&mut food);
+        assert_eq!(0, Rat::life_manage(&vec![starved]).len());
+        config::set(Config::default());
     }
     
     #[test]
-    fn rat_eaten_test(){
-        let mut rat = <Rat as Animal>::new();
-        let cat = <Cat as Animal>::new();
-        let eaten_diff = EATEN_RADIOUS / 2.0;
-        setpos(&mut rat, &PVector::new(eaten_diff, eaten_diff).add(cat.position()));
-        let cats_tree = QuadTree::new(&vec![cat.clone()]);
-        assert!(rat.eaten(&cats_tree));
-        
-        let not_eaten_diff = EATEN_RADIOUS;
-        setpos(&mut rat, &PVector::new(not_eaten_diff, not_eaten_diff).add(cat.position()));
-        assert!(!rat.eaten(&cats_tree));
-    }
-    
-    #[test]
-    fn rat_delete_eaten_test(){
-        let mut rat = <Rat as Animal>::new();
-        let cat = <Cat as Animal>::new();
-        let eaten_diff = EATEN_RADIOUS / 2.0;
-        let cats_tree = QuadTree::new(&vec![cat.clone()]);
-        
-        setpos(&mut rat, &PVector::new(eaten_diff, eaten_diff).add(cat.position()));
-        let mut rats: Vec<Rat> = Vec::with_capacity(100);
-        for _ in 0..100 {
-            rats.push(rat.clone());
-        }
-        assert_eq!(Rat::delete_eaten(&cats_tree, &rats).len(), 0);
-        
-        let not_eaten_diff = EATEN_RADIOUS;
-        setpos(&mut rat, &PVector::new(not_eaten_diff, not_eaten_diff).add(cat.position()));
-        rats.clear();
-        for _ in 0..100 {
-            rats.push(rat.clone());
+    fn rat_next_generation_test(){
+        // 先読みしないところから始める
+        let parent = <Rat as Animal>::new();
+        assert_eq!(0.0, parent.lead_time);
+        let mut survivor = parent.clone();
+        survivor.lead_time = LEAD_TIME_MAX / 2.0;
+        let next = Rat::next_generation(&vec![survivor.clone()], 5);
+        assert_eq!(5, next.len());
+        for rat in &next {
+            assert_eq!(Some(survivor.id()), rat.lineage().parent);
+            assert!((rat.lead_time - survivor.lead_time).abs() <= LEAD_TIME_MUTATE_ABS);
         }
-        
-        assert_eq!(Rat::delete_eaten(&cats_tree, &rats).len(), 100);
+        // 全滅していたら新しい個体
+        let fresh = Rat::next_generation(&Vec::new(), 3);
+        assert_eq!(3, fresh.len());
+        assert!(fresh.iter().all(|rat| rat.lineage().parent.is_none()));
     }
 }
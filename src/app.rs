@@ -1,65 +1,49 @@
 use animal::{Animal, Cat, Rat};
 use piston::input::RenderArgs;
-use consts::{WIDTH, HEIGHT};
+use consts::{WIDTH, HEIGHT, CELL_WIDTH};
 use glutin_window::GlutinWindow as Window;
 use piston::window::WindowSettings;
 use opengl_graphics::{ GlGraphics, OpenGL };
-use graphics::{rectangle, clear};
+use graphics::{rectangle, ellipse, polygon, clear};
 use graphics::rectangle::square;
 use graphics::Transformed;
 use graphics::context::Context;
 use piston::event_loop::*;
 use piston::input::*;
-use quad_tree::QuadTree;
+use clock::Clock;
+use config;
+use food::Food;
+use obstacle;
+use obstacle::Obstacle;
+use world::World;
 
 // #[derive(Clone)]
 pub struct App {
     pub gl: GlGraphics,
     pub window: Window,
-    pub cats: Vec<Cat>,
-    pub rats: Vec<Rat>,
-    pub cats_tree: QuadTree<Cat>,
-    pub rats_tree: QuadTree<Rat>,
+    pub clock: Clock,
 }
 
 impl App {
     const GREEN: [f32; 4] = [0.0, 1.0, 0.0, 1.0];
     const RED:   [f32; 4] = [1.0, 0.0, 0.0, 1.0];
     const BLUE:   [f32; 4] = [0.0, 0.0, 1.0, 1.0];
+    const GRAY:   [f32; 4] = [0.5, 0.5, 0.5, 1.0];
+    const LIGHT_GREEN: [f32; 4] = [0.6, 1.0, 0.6, 1.0];
+    const SOIL:   [f32; 4] = [0.6, 0.4, 0.2, 1.0];
     const ANIMAL_SIZE: f64 = 5.0;
     // 初期化
     pub fn new() -> App {
         let opengl = OpenGL::V3_2;
         let window = App::new_window(opengl);
-        let cats = App::new_cats();
-        let rats = App::new_rats();
-        let cats_tree = QuadTree::new(&cats);
-        let rats_tree = QuadTree::new(&rats);
         
         App {
             gl: GlGraphics::new(opengl),
-            window, cats, rats, cats_tree, rats_tree,
+            window,
+            clock: Clock::new(config::get().dt),
         }
     }
     
-    // １世代目に捕食者の初期化
-    fn new_cats() -> Vec<Cat> {
-        let mut ret: Vec<Cat> = Vec::new();
-        for _ in 0..10 {
-            ret.push(<Cat as Animal>::new());
-        }
-        ret
-    }
-    
-    // 世代の初めに捕食者の初期化
-    fn new_rats() -> Vec<Rat> {
-        let mut ret: Vec<Rat> = Vec::new();
-        for _ in 0..200 {
-            ret.push(<Rat as Animal>::new());
-        }
-        ret
-    }
-    
     // ウィンドウの初期化
     fn new_window(opengl: OpenGL) -> Window{
         WindowSettings::new(
@@ -72,48 +56,98 @@ impl App {
             .unwrap()
     }
     
-    // 優秀な捕食者だけを次の世代へ
-    pub fn next_generation(&mut self){
-        self.cats = Cat::next_generation(&self.cats);
-        self.rats = App::new_rats();
-    }
-    
     // １世代終わるまでウインドウを表示
-    pub fn show_window(&mut self){
+    // 経過時間に倍速をかけた分だけ決まった秒数のステップを進める
+    pub fn show_window(&mut self, world: &mut World){
         let mut events = Events::new(EventSettings::new());
+        self.clock.reset();
         
         while let Some(e) = events.next(&mut self.window) {
             if let Some(r) = e.render_args(){ 
-                self.render(&r);
+                self.render(&r, world);
+            }
+            
+            if let Some(Button::Keyboard(key)) = e.press_args() {
+                self.key_pressed(key);
             }
             
-            if let Some(_) = e.update_args() {
-                if self.update(){
+            if let Some(u) = e.update_args() {
+                if self.update(world, u.dt){
                     break
                 }
             }
         }
     }
     
+    // +で速く、-で遅くする
+    fn key_pressed(&mut self, key: Key) {
+        match key {
+            Key::Plus | Key::Equals | Key::NumPadPlus => self.clock.faster(),
+            Key::Minus | Key::NumPadMinus => self.clock.slower(),
+            _ => {}
+        }
+    }
+    
     // 描画
-    pub fn render(&mut self, args: &RenderArgs){
+    pub fn render(&mut self, args: &RenderArgs, world: &World){
         
         let square: graphics::types::Rectangle = square(0.0, 0.0, App::ANIMAL_SIZE);
 
         //const TRIANGLE:   &[[f32; 2]; 3] = &[[1.0, 0.0], [0.0, 1.732], [2.0, 1.732]];
 
-        let cats = &self.cats;
-        let rats = &self.rats;
+        let cats = &world.cats;
+        let rats = &world.rats;
+        let food = &world.food;
+        let config = config::get();
 
         self.gl.draw(args.viewport(), |c, gl|{
             clear(App::GREEN, gl);
             
+            App::draw_food(&c, gl, food, config.food_capacity);
+            
+            App::draw_obstacles(&c, gl, &config.refuges, App::LIGHT_GREEN);
+            App::draw_obstacles(&c, gl, &config.obstacles, App::GRAY);
+            
             App::draw_cat(&c, gl, cats, square);
             App::draw_rat(&c, gl, rats, square);
 
         });
     }
     
+    // 草の描画。食べ尽くされたマスほど土の色に近づく
+    fn draw_food(c: &Context, gl: &mut GlGraphics, food: &Food, capacity: f64) {
+        let cell = square(0.0, 0.0, CELL_WIDTH);
+        for (index, &amount) in food.cells.iter().enumerate() {
+            let ratio = if capacity > 0.0 { (amount / capacity) as f32 } else { 0.0 };
+            let color = [
+                App::SOIL[0] + (App::GREEN[0] - App::SOIL[0]) * ratio,
+                App::SOIL[1] + (App::GREEN[1] - App::SOIL[1]) * ratio,
+                App::SOIL[2] + (App::GREEN[2] - App::SOIL[2]) * ratio,
+                1.0,
+            ];
+            let position = food.cell_position(index);
+            rectangle(color, cell, c.transform.trans(position.x, position.y), gl);
+        }
+    }
+    
+    // 障害物と隠れ家の描画
+    fn draw_obstacles(c: &Context, gl: &mut GlGraphics, obstacles: &Vec<Obstacle>, color: [f32; 4]) {
+        for obstacle in obstacles {
+            match *obstacle {
+                Obstacle::Circle { ref center, radious } => {
+                    ellipse(color, ellipse::circle(center.x, center.y, radious), c.transform, gl);
+                },
+                // polygonは扇形に塗るので、凹んだ多角形は三角形に分けてから塗る
+                Obstacle::Polygon { ref points } => {
+                    for triangle in obstacle::triangulate(points) {
+                        let corners: Vec<[f64; 2]> = triangle.iter().map(|&n| [points[n].x, points[n].y]).collect();
+                        polygon(color, &corners, c.transform, gl);
+                    }
+                },
+            }
+        }
+    }
+    
     // 捕食者の描画
     fn draw_cat(c: &Context, gl: &mut GlGraphics, cats: &Vec<Cat>, square: graphics::types::Rectangle) {
         for cat in cats {
@@ -134,61 +168,13 @@ impl App {
         }
     }
     
-    // 非捕食者がいなくなったら世代が終わる
-    fn is_finished(rats: &Vec<Rat>) -> bool {
-        rats.len() == 0
-    }
-    
-    // 捕食者のパラメータの平均の計算
-    fn chase_average(animals: &Vec<Cat>) -> f64 {
-        animals
-            .into_iter()
-            .fold(0.0, |a, b| a + b.chase_weight)
-            / animals.len() as f64
-    }
-    
-    // 捕食者のパラメータの平均の計算
-    fn align_average(animals: &Vec<Cat>) -> f64 {
-        animals
-            .into_iter()
-            .fold(0.0, |a, b| a + b.align_weight)
-            / animals.len() as f64
-    }
-    
-    // 捕食者のパラメータの平均の計算
-    fn separate_average(animals: &Vec<Cat>) -> f64 {
-        animals
-            .into_iter()
-            .fold(0.0, |a, b| a + b.separate_weight)
-            / animals.len() as f64
-    }
-    
-    // 捕食者のパラメータの平均の計算
-    fn cohension_average(animals: &Vec<Cat>) -> f64 {
-        animals
-            .into_iter()
-            .fold(0.0, |a, b| a + b.cohension_weight) 
-            / animals.len() as f64
-    }
-
-    // １フレーム進める
-    pub fn update(&mut self)  -> bool {
-        let cats = self.cats.clone();
-        let rats = self.rats.clone();
-        self.cats = <Cat as Animal>::next_states(&cats, &self.cats_tree, &self.rats_tree);
-        self.rats = <Rat as Animal>::next_states(&rats, &self.cats_tree ,&self.rats_tree);
-        self.cats_tree = QuadTree::new(&self.cats);
-        self.rats_tree = QuadTree::new(&self.rats);
-        App::is_finished(&self.rats)
-    }
-    
-    // 世代の最後にパラメータを標準出力へ
-    pub fn print_params(&self, ord: i32) {
-        print!("{},", ord);
-        print!("{},", App::chase_average(&self.cats));
-        print!("{},", App::align_average(&self.cats));
-        print!("{},", App::cohension_average(&self.cats));
-        print!("{}",  App::separate_average(&self.cats));
-        println!("");
+    // elapsed秒ぶんのステップを進める。世代が終わったらtrue
+    pub fn update(&mut self, world: &mut World, elapsed: f64) -> bool {
+        for _ in 0..self.clock.ticks(elapsed) {
+            if world.step() {
+                return true;
+            }
+        }
+        false
     }
 }
@@ -0,0 +1,70 @@
+mod test;
+
+use consts::*;
+use pvector::PVector;
+
+// 画面の端での扱い
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Boundary {
+    Torus,    // 反対側につながる
+    Reflect,  // 壁で跳ね返る
+    Absorb,   // 壁の外へ出たらいなくなる
+    SoftWall, // 壁に近づくと押し返され、壁は越えられない
+}
+
+impl Boundary {
+    // 設定ファイルの名前から読む
+    pub fn parse(text: &str) -> Result<Boundary, String> {
+        match text {
+            "torus" => Ok(Boundary::Torus),
+            "reflect" => Ok(Boundary::Reflect),
+            "absorb" => Ok(Boundary::Absorb),
+            "soft_wall" => Ok(Boundary::SoftWall),
+            _ => Err(format!("unknown boundary `{}`", text)),
+        }
+    }
+    
+    // 画面の端をまたいで距離を測るかどうか
+    pub fn wraps(&self) -> bool {
+        *self == Boundary::Torus
+    }
+    
+    // 動いた後の位置と速度を画面の中に収める。吸収されたらNone
+    pub fn apply(&self, position: &PVector, velocity: &PVector) -> Option<(PVector, PVector)> {
+        let (x, vx) = self.apply_axis(position.x, velocity.x, WIDTH)?;
+        let (y, vy) = self.apply_axis(position.y, velocity.y, HEIGHT)?;
+        Some((PVector::new(x, y), PVector::new(vx, vy)))
+    }
+    
+    fn apply_axis(&self, pos: f64, vel: f64, max: f64) -> Option<(f64, f64)> {
+        let inside = 0.0 <= pos && pos <= max;
+        match *self {
+            Boundary::Torus if pos > max => Some((pos - max, vel)),
+            Boundary::Torus if pos < 0.0 => Some((pos + max, vel)),
+            Boundary::Reflect if pos > max => Some(((2.0 * max - pos).max(0.0), -vel.abs())),
+            Boundary::Reflect if pos < 0.0 => Some(((-pos).min(max), vel.abs())),
+            Boundary::Absorb if !inside => None,
+            Boundary::SoftWall if pos > max => Some((max, vel.min(0.0))),
+            Boundary::SoftWall if pos < 0.0 => Some((0.0, vel.max(0.0))),
+            _ => Some((pos, vel)),
+        }
+    }
+    
+    // 柔らかい壁から内側へ押し返す力。壁からmarginより遠ければ零ベクトル
+    // 壁に近いほど強く、壁の上で長さ1になる
+    pub fn wall_force(&self, position: &PVector, margin: f64) -> PVector {
+        if *self != Boundary::SoftWall {
+            return PVector::zero();
+        }
+        let push = |pos: f64, max: f64| {
+            if pos < margin {
+                1.0 - pos.max(0.0) / margin
+            } else if pos > max - margin {
+                -(1.0 - (max - pos).max(0.0) / margin)
+            } else {
+                0.0
+            }
+        };
+        PVector::new(push(position.x, WIDTH), push(position.y, HEIGHT)).truncate(1.0)
+    }
+}
@@ -0,0 +1,104 @@
+#[cfg(test)]
+mod tests{
+    use boundary::Boundary;
+    use config;
+    use config::Config;
+    use consts::*;
+    use pvector::PVector;
+    use quad_tree::{QuadTree, Rectangle};
+    use animal::{Animal, Cat};
+    
+    fn moved(boundary: Boundary, x: f64, y: f64, vx: f64, vy: f64) -> Option<(PVector, PVector)> {
+        boundary.apply(&PVector::new(x, y), &PVector::new(vx, vy))
+    }
+    
+    #[test]
+    fn parse_test(){
+        assert_eq!(Ok(Boundary::Torus), Boundary::parse("torus"));
+        assert_eq!(Ok(Boundary::Reflect), Boundary::parse("reflect"));
+        assert_eq!(Ok(Boundary::Absorb), Boundary::parse("absorb"));
+        assert_eq!(Ok(Boundary::SoftWall), Boundary::parse("soft_wall"));
+        assert!(Boundary::parse("wall").is_err());
+    }
+    
+    #[test]
+    fn apply_test(){
+        // 画面の中ならどれも変わらない
+        for &boundary in &[Boundary::Torus, Boundary::Reflect, Boundary::Absorb, Boundary::SoftWall] {
+            let (position, velocity) = moved(boundary, 10.0, 20.0, 1.0, -1.0).unwrap();
+            assert_eq!(PVector::new(10.0, 20.0), position);
+            assert_eq!(PVector::new(1.0, -1.0), velocity);
+        }
+        
+        let (position, velocity) = moved(Boundary::Torus, WIDTH + 2.0, -1.0, 3.0, -2.0).unwrap();
+        assert_eq!(PVector::new(2.0, HEIGHT - 1.0), position);
+        assert_eq!(PVector::new(3.0, -2.0), velocity);
+        
+        // 壁で折り返して速度の向きが変わる
+        let (position, velocity) = moved(Boundary::Reflect, WIDTH + 2.0, -1.0, 3.0, -2.0).unwrap();
+        assert_eq!(PVector::new(WIDTH - 2.0, 1.0), position);
+        assert_eq!(PVector::new(-3.0, 2.0), velocity);
+        
+        assert!(moved(Boundary::Absorb, WIDTH + 2.0, 10.0, 3.0, 0.0).is_none());
+        assert!(moved(Boundary::Absorb, 10.0, -0.1, 0.0, -1.0).is_none());
+        
+        // 壁の上で止まり、壁に沿った速度だけ残る
+        let (position, velocity) = moved(Boundary::SoftWall, -2.0, HEIGHT + 1.0, -3.0, 2.0).unwrap();
+        assert_eq!(PVector::new(0.0, HEIGHT), position);
+        assert_eq!(PVector::new(0.0, 0.0), velocity);
+    }
+    
+    #[test]
+    fn wall_force_test(){
+        let center = PVector::new(WIDTH / 2.0, HEIGHT / 2.0);
+        assert_eq!(PVector::zero(), Boundary::SoftWall.wall_force(&center, 20.0));
+        assert_eq!(PVector::zero(), Boundary::Torus.wall_force(&PVector::new(1.0, 1.0), 20.0));
+        
+        // 左の壁に近いほど右へ強く押す
+        let near = Boundary::SoftWall.wall_force(&PVector::new(5.0, HEIGHT / 2.0), 20.0);
+        let far = Boundary::SoftWall.wall_force(&PVector::new(15.0, HEIGHT / 2.0), 20.0);
+        assert!(near.x > far.x && far.x > 0.0);
+        assert_eq!(0.0, near.y);
+        
+        // 右下の角では左上へ押し、長さは1を超えない
+        let corner = Boundary::SoftWall.wall_force(&PVector::new(WIDTH, HEIGHT), 20.0);
+        assert!(corner.x < 0.0 && corner.y < 0.0);
+        assert!(corner.len() <= 1.0 + 1.0e-9);
+    }
+    
+    #[test]
+    fn absorb_test(){
+        config::set(Config { boundary: Boundary::Absorb, dt: 1.0, ..Config::default() });
+        let cat = Cat::new()
+            .set_position(&PVector::new(WIDTH - 1.0, 100.0))
+            .apply_velocity(&PVector::new(2.0, 0.0));
+        let cats = vec![cat.move_self()];
+        assert_eq!(0, <Cat as Animal>::life_manage(&cats).len());
+        config::set(Config::default());
+    }
+    
+    #[test]
+    fn offset_test(){
+        let left = PVector::new(1.0, 1.0);
+        let right = PVector::new(WIDTH - 1.0, 1.0);
+        assert_eq!(-2.0, left.offset(&right, Boundary::Torus).x);
+        
+        // 画面がつながっていなければ端をまたがない
+        assert_eq!(WIDTH - 2.0, left.offset(&right, Boundary::Reflect).x);
+        
+        // 木は作った時の設定で測る
+        config::set(Config { boundary: Boundary::Reflect, ..Config::default() });
+        
+        let cats = vec![Cat::new().set_position(&PVector::new(WIDTH - 1.5, 100.5))];
+        let tree = QuadTree::new(&cats);
+        assert_eq!(0, tree.search_around(&PVector::new(1.5, 100.5), 10.0).len());
+        assert_eq!(0, tree.search_rect(&Rectangle::new(-10.0, 90.0, 20.0, 20.0)).len());
+        assert_eq!(1, tree.search_rect(&Rectangle::new(WIDTH - 10.0, 90.0, 20.0, 20.0)).len());
+        
+        config::set(Config::default());
+        assert_eq!(0, tree.search_around(&PVector::new(1.5, 100.5), 10.0).len());
+        let tree = QuadTree::new(&cats);
+        assert_eq!(1, tree.search_around(&PVector::new(1.5, 100.5), 10.0).len());
+        assert_eq!(1, tree.search_rect(&Rectangle::new(-10.0, 90.0, 20.0, 20.0)).len());
+    }
+}
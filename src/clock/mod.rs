@@ -0,0 +1,45 @@
+mod test;
+
+use consts::*;
+
+// 描画の間隔に関係なく、決まった秒数ずつシミュレーションを進めるための時計
+#[derive(Debug, Clone)]
+pub struct Clock {
+    pub dt: f64,    // 1ステップの秒数
+    pub speed: f64, // 実時間に対する倍率
+    accumulator: f64,
+}
+
+impl Clock {
+    pub fn new(dt: f64) -> Clock {
+        Clock { dt, speed: 1.0, accumulator: 0.0 }
+    }
+    
+    // 経過した実時間を受け取り、進めるべきステップ数を返す
+    // 追いつけないほど溜まった分は捨てる
+    pub fn ticks(&mut self, elapsed: f64) -> u32 {
+        self.accumulator += elapsed * self.speed;
+        let steps = (self.accumulator / self.dt).floor();
+        if steps > MAX_STEPS_PER_UPDATE as f64 {
+            self.accumulator = 0.0;
+            return MAX_STEPS_PER_UPDATE;
+        }
+        self.accumulator -= steps * self.dt;
+        steps as u32
+    }
+    
+    // 倍速を2倍にする
+    pub fn faster(&mut self) {
+        self.speed = (self.speed * 2.0).min(SPEED_MAX);
+    }
+    
+    // 倍速を半分にする
+    pub fn slower(&mut self) {
+        self.speed = (self.speed / 2.0).max(SPEED_MIN);
+    }
+    
+    // 世代の切り替わりで溜まった時間を捨てる
+    pub fn reset(&mut self) {
+        self.accumulator = 0.0;
+    }
+}
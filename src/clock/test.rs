@@ -0,0 +1,43 @@
+#[cfg(test)]
+mod tests{
+    use clock::Clock;
+    use consts::*;
+    
+    #[test]
+    fn ticks_test(){
+        let mut clock = Clock::new(0.25);
+        assert_eq!(0, clock.ticks(0.125));
+        // 端数は次に持ち越す
+        assert_eq!(1, clock.ticks(0.25));
+        assert_eq!(4, clock.ticks(1.0));
+        assert_eq!(1, clock.ticks(0.125));
+        assert_eq!(0, clock.ticks(0.0));
+        
+        clock.reset();
+        clock.speed = 4.0;
+        assert_eq!(4, clock.ticks(0.25));
+    }
+    
+    #[test]
+    fn ticks_limit_test(){
+        let mut clock = Clock::new(0.001);
+        assert_eq!(MAX_STEPS_PER_UPDATE, clock.ticks(10.0));
+        // 溜まりすぎた分は捨てる
+        assert_eq!(0, clock.ticks(0.0));
+    }
+    
+    #[test]
+    fn speed_test(){
+        let mut clock = Clock::new(0.1);
+        clock.faster();
+        assert_eq!(2.0, clock.speed);
+        for _ in 0..20 {
+            clock.faster();
+        }
+        assert_eq!(SPEED_MAX, clock.speed);
+        for _ in 0..20 {
+            clock.slower();
+        }
+        assert_eq!(SPEED_MIN, clock.speed);
+    }
+}
@@ -0,0 +1,574 @@
+mod test;
+
+use animal::Species;
+use boundary::Boundary;
+use evaluation::Evaluation;
+use consts::*;
+use falloff::Falloff;
+use island::Topology;
+use neural::Controller;
+use fitness;
+use fitness::{Measure, WeightedFitness};
+use pareto;
+use pareto::Selection;
+use obstacle::Obstacle;
+use predation::Credit;
+use termination::{Combine, Condition, Termination};
+use steering;
+use steering::WeightedBehavior;
+use std::cell::RefCell;
+use std::fs::File;
+use std::io::Read;
+use std::rc::Rc;
+
+// 実行時に変更できる設定。既定値はconstsの値に合わせる
+// 設定ファイルは「キー = 値」の行を並べたもので、#以降はコメント
+#[derive(Debug, Clone)]
+pub struct Config {
+    pub cat: SpeciesConfig,
+    pub rat: SpeciesConfig,
+    pub obstacles: Vec<Obstacle>,
+    pub refuges: Vec<Obstacle>, // ねずみだけが入れる場所
+    pub dt: f64, // 1ステップで進める秒数
+    pub boundary: Boundary,
+    pub wall_margin: f64, // 柔らかい壁が押し返し始める距離
+    pub wall_weight: f64, // 柔らかい壁の力の重み
+    pub food_capacity: f64, // 1マスの草の最大量
+    pub food_regrowth: f64, // 1マスで1秒に生える草の量
+    pub predation: PredationConfig,
+    pub termination: Termination, // 世代の終わり方
+    pub fitness: Vec<WeightedFitness>, // 猫の選択に使う適応度。重みをかけて足し合わせる
+    pub selection: Selection,
+    pub objectives: Vec<Measure>, // paretoで選ぶ時の目的
+    pub evaluation: EvaluationConfig,
+    pub islands: IslandConfig,
+    pub hall_of_fame: usize, // 世代ごとに殿堂入りさせる猫の数
+    pub diversity: DiversityConfig,
+    pub controller: ControllerConfig,
+    pub neat: NeatConfig,
+    pub seed: Option<u64>, // 乱数のシード。Noneなら実行ごとに変わる
+}
+
+// 構造ごと進化させるネットワークの突然変異、交叉、種分け
+#[derive(Debug, Clone)]
+pub struct NeatConfig {
+    pub add_node: f64,       // 子を作る時にニューロンを足す確率
+    pub add_connection: f64, // 子を作る時に結合を足す確率
+    pub compatibility: f64,  // 代表とこの距離より近ければ同じ種
+    pub crossover: f64,      // 子を交叉で作る確率
+    pub survival: f64,       // 種の中で親になれる上位の割合
+}
+
+// ネットワークで操舵する時の設定。操舵のしかたは種ごとに選ぶ
+#[derive(Debug, Clone)]
+pub struct ControllerConfig {
+    pub hidden: usize,   // 隠れ層のニューロンの数
+    pub nearest: usize,  // 入力に使う近い猫とねずみの数
+    pub radious: f64,    // 近くの個体を感じ取る範囲
+    pub mutation: f64,   // 子孫を残す時に重みを変える幅
+}
+
+// 遺伝的な多様性の測り方と、収束した時に止める条件
+#[derive(Debug, Clone)]
+pub struct DiversityConfig {
+    pub bins: usize,             // エントロピーを求める時に遺伝子を分けるビンの数
+    pub plateau: Option<usize>,  // 多様性と平均適応度がこの世代数だけ頭打ちなら止める。Noneなら止めない
+    pub tolerance: f64,          // 頭打ちとみなす変化の割合
+}
+
+// 島モデル。countが1なら島に分けない
+#[derive(Debug, Clone)]
+pub struct IslandConfig {
+    pub count: usize,
+    pub interval: usize, // 何世代ごとに個体を移すか
+    pub migrants: usize, // 一つの島から一度に移す個体の数
+    pub topology: Topology,
+    pub overrides: Vec<(usize, String, String)>, // 島の番号ごとの設定の上書き
+}
+
+// 猫の評価のしかた
+#[derive(Debug, Clone)]
+pub struct EvaluationConfig {
+    pub mode: Evaluation,
+    pub trials: usize,  // isolatedで遺伝子ごとに試す回数
+    pub team: usize,    // 一つの場所に置く同じ遺伝子の猫の数
+    pub threads: usize, // 並列に試すスレッドの数
+}
+
+// 捕食の解決のしかた
+#[derive(Debug, Clone)]
+pub struct PredationConfig {
+    pub radious: f64,      // ねずみを捕まえられる距離
+    pub credit: Credit,    // 複数の猫が届く時に誰が食べるか
+    pub pack_radious: f64, // 食べた場所からこの距離以内の猫でエネルギーを分け合う。0なら分けない
+    pub capture: CaptureConfig,
+}
+
+// 襲った時に捕まえられる確率。既定では必ず捕まえる
+#[derive(Debug, Clone)]
+pub struct CaptureConfig {
+    pub chance: f64,            // 条件が一番良い時の確率
+    pub speed_weight: f64,      // 近づく速さの効き方。0なら速さによらない
+    pub angle_weight: f64,      // 正面からのずれの効き方。0なら向きによらない
+    pub confusion: f64,         // 周りのねずみ1匹あたりに確率を割る度合い
+    pub confusion_radious: f64, // 目移りさせるねずみを数える範囲
+    pub miss_cost: f64,         // 逃げられた時に失うエネルギー
+}
+
+// 種ごとの設定
+#[derive(Debug, Clone)]
+pub struct SpeciesConfig {
+    pub vision: VisionConfig,
+    pub falloff: FalloffConfig,
+    pub behaviors: Vec<WeightedBehavior>, // 足し合わせる振る舞いと重み
+    pub controller: Controller, // 振る舞いで進むか、ネットワークで進むか
+    pub motion: MotionConfig,
+    pub metabolism: f64, // 1秒に使うエネルギー
+    pub graze_rate: f64, // 1秒に食べられる草の量。猫は草を食べない
+    pub birth: BirthConfig,
+    pub life: LifeConfig,
+}
+
+// 増え方
+#[derive(Debug, Clone)]
+pub struct BirthConfig {
+    pub energy: f64,           // 子を作れるエネルギーの下限
+    pub share: f64,            // 子に分けるエネルギーの割合
+    pub rate: f64,             // 下限を超えている間に1秒あたり子を作る確率
+    pub capacity: Option<f64>, // 環境収容力。Noneなら上限なし
+}
+
+// 寿命と老化。年齢は秒で数える
+#[derive(Debug, Clone)]
+pub struct LifeConfig {
+    pub lifespan: Option<f64>, // 寿命。Noneなら年では死なない
+    pub senescence: f64,       // 寿命のうち老化が始まる割合
+    pub slowdown: f64,         // 寿命を迎えた時に落ちている最高速の割合
+    pub metabolism: f64,       // 寿命を迎えた時に増えている代謝の割合
+}
+
+// 動きの制限。どれも秒あたりで、角度はラジアンで持ち設定ファイルでは度で書く
+#[derive(Debug, Clone)]
+pub struct MotionConfig {
+    pub max_force: f64,     // 1秒で変えられる速度ベクトルの大きさ
+    pub max_turn_rate: f64, // 1秒で曲がれる角度
+    pub max_speed: f64,
+}
+
+// 視覚の設定。角度はラジアンで持ち、設定ファイルでは度で書く
+#[derive(Debug, Clone)]
+pub struct VisionConfig {
+    pub view_angle: f64, // 正面を中心とした視野角。2πなら全周が見える
+    pub blind_spot: f64, // 真後ろを中心とした死角の角度
+    pub occlusion: bool, // 他の個体に遮られたものは見えない
+    pub evolvable: bool, // 視野角と死角を遺伝させ、突然変異させる
+}
+
+// 力ごとの距離による減衰。猫はrun_away以外、ねずみはrun_awayだけを使う
+#[derive(Debug, Clone)]
+pub struct FalloffConfig {
+    pub chase: Falloff,
+    pub separate: Falloff,
+    pub align: Falloff,
+    pub cohension: Falloff,
+    pub run_away: Falloff,
+}
+
+thread_local! {
+    static CONFIG: RefCell<Rc<Config>> = RefCell::new(Rc::new(Config::default()));
+}
+
+// 現在の設定を返す
+pub fn get() -> Rc<Config> {
+    CONFIG.with(|config| config.borrow().clone())
+}
+
+// 設定を置き換える。スレッドごとに別の設定を持つ
+pub fn set(config: Config) {
+    CONFIG.with(|current| *current.borrow_mut() = Rc::new(config));
+}
+
+impl Default for Config {
+    fn default() -> Config {
+        Config {
+            cat: SpeciesConfig::new(steering::cat_behaviors(), MotionConfig {
+                max_force: CAT_MAX_FORCE,
+                max_turn_rate: CAT_MAX_TURN_RATE,
+                max_speed: CAT_VELOCITY,
+            }, CAT_METABOLISM, 0.0, CAT_BIRTH_ENERGY),
+            rat: SpeciesConfig::new(steering::rat_behaviors(), MotionConfig {
+                max_force: RAT_MAX_FORCE,
+                max_turn_rate: RAT_MAX_TURN_RATE,
+                max_speed: RAT_VELOCITY,
+            }, RAT_METABOLISM, RAT_GRAZE_RATE, RAT_BIRTH_ENERGY),
+            obstacles: Vec::new(),
+            refuges: Vec::new(),
+            dt: 1.0 / FRAME_RATE,
+            boundary: Boundary::Torus,
+            wall_margin: WALL_MARGIN,
+            wall_weight: WALL_WEIGHT,
+            food_capacity: FOOD_CAPACITY,
+            food_regrowth: FOOD_REGROWTH,
+            predation: PredationConfig {
+                radious: EATEN_RADIOUS,
+                credit: Credit::Nearest,
+                pack_radious: PACK_RADIOUS,
+                capture: CaptureConfig {
+                    chance: 1.0,
+                    speed_weight: 0.0,
+                    angle_weight: 0.0,
+                    confusion: 0.0,
+                    confusion_radious: CONFUSION_RADIOUS,
+                    miss_cost: 0.0,
+                },
+            },
+            termination: Termination {
+                conditions: vec![Condition::RatsBelow(1), Condition::CatsDead, Condition::Frames(GENERATION_FRAMES)],
+                combine: Combine::Any,
+            },
+            fitness: fitness::default_fitness(),
+            selection: Selection::Weighted,
+            objectives: fitness::default_objectives(),
+            evaluation: EvaluationConfig {
+                mode: Evaluation::Shared,
+                trials: TRIALS,
+                team: TEAM_SIZE,
+                threads: EVALUATION_THREADS,
+            },
+            islands: IslandConfig {
+                count: 1,
+                interval: MIGRATION_INTERVAL,
+                migrants: MIGRANTS,
+                topology: Topology::Ring,
+                overrides: Vec::new(),
+            },
+            hall_of_fame: HALL_OF_FAME,
+            diversity: DiversityConfig {
+                bins: DIVERSITY_BINS,
+                plateau: None,
+                tolerance: PLATEAU_TOLERANCE,
+            },
+            controller: ControllerConfig {
+                hidden: NEURAL_HIDDEN,
+                nearest: NEURAL_NEAREST,
+                radious: NEURAL_RADIOUS,
+                mutation: WEIGHT_MUTATE_ABS,
+            },
+            neat: NeatConfig {
+                add_node: NEAT_ADD_NODE,
+                add_connection: NEAT_ADD_CONNECTION,
+                compatibility: COMPATIBILITY,
+                crossover: CROSSOVER_RATE,
+                survival: NEAT_SURVIVAL,
+            },
+            seed: None,
+        }
+    }
+}
+
+impl Default for VisionConfig {
+    fn default() -> VisionConfig {
+        VisionConfig {
+            view_angle: 2.0 * std::f64::consts::PI,
+            blind_spot: 0.0,
+            occlusion: false,
+            evolvable: false,
+        }
+    }
+}
+
+impl Default for FalloffConfig {
+    fn default() -> FalloffConfig {
+        FalloffConfig {
+            chase: Falloff::Constant,
+            separate: Falloff::Constant,
+            align: Falloff::Constant,
+            cohension: Falloff::Constant,
+            run_away: Falloff::Constant,
+        }
+    }
+}
+
+impl Config {
+    // 種ごとの設定を返す
+    pub fn species(&self, species: Species) -> &SpeciesConfig {
+        match species {
+            Species::Cat => &self.cat,
+            Species::Rat => &self.rat,
+        }
+    }
+    
+    // 設定ファイルを読み込む
+    pub fn load(path: &str) -> Result<Config, String> {
+        let mut text = String::new();
+        File::open(path)
+            .and_then(|mut file| file.read_to_string(&mut text))
+            .map_err(|e| format!("{}: {}", path, e))?;
+        let mut config = Config::default();
+        config.parse(&text).map_err(|e| format!("{}: {}", path, e))?;
+        Ok(config)
+    }
+    
+    // 設定の文字列を読んで上書きする
+    pub fn parse(&mut self, text: &str) -> Result<(), String> {
+        for (n, line) in text.lines().enumerate() {
+            let line = line.split('#').next().unwrap_or("").trim();
+            if line.is_empty() {
+                continue;
+            }
+            let mut pair = line.splitn(2, '=');
+            let key = pair.next().unwrap_or("").trim();
+            let value = pair
+                .next()
+                .ok_or_else(|| format!("line {}: expected `key = value`", n + 1))?
+                .trim();
+            self.set(key, value).map_err(|e| format!("line {}: {}", n + 1, e))?;
+        }
+        Ok(())
+    }
+    
+    // キーに対応する値を一つ設定する。obstacleとrefugeは書いた数だけ追加される
+    pub fn set(&mut self, key: &str, value: &str) -> Result<(), String> {
+        match key {
+            "obstacle" => self.obstacles.push(Obstacle::parse(value)?),
+            "refuge" => self.refuges.push(Obstacle::parse(value)?),
+            "dt" => self.dt = parse_positive(value)?,
+            "boundary" => self.boundary = Boundary::parse(value)?,
+            "wall_margin" => self.wall_margin = parse_positive(value)?,
+            "wall_weight" => self.wall_weight = parse_f64(value)?,
+            "food_capacity" => self.food_capacity = parse_non_negative(value)?,
+            "food_regrowth" => self.food_regrowth = parse_non_negative(value)?,
+            "catch_radious" => self.predation.radious = parse_positive(value)?,
+            "kill_credit" => self.predation.credit = Credit::parse(value)?,
+            "pack_radious" => self.predation.pack_radious = parse_non_negative(value)?,
+            "capture_chance" => self.predation.capture.chance = parse_ratio(value)?,
+            "capture_speed_weight" => self.predation.capture.speed_weight = parse_non_negative(value)?,
+            "capture_angle_weight" => self.predation.capture.angle_weight = parse_non_negative(value)?,
+            "confusion" => self.predation.capture.confusion = parse_non_negative(value)?,
+            "confusion_radious" => self.predation.capture.confusion_radious = parse_positive(value)?,
+            "miss_cost" => self.predation.capture.miss_cost = parse_non_negative(value)?,
+            "end" => self.termination.conditions = Termination::parse_list(value)?,
+            "end_when" => self.termination.combine = Combine::parse(value)?,
+            "fitness" => self.fitness = WeightedFitness::parse_list(value)?,
+            "selection" => self.selection = Selection::parse(value)?,
+            "objectives" => self.objectives = pareto::parse_objectives(value)?,
+            "evaluation" => self.evaluation.mode = Evaluation::parse(value)?,
+            "trials" => self.evaluation.trials = parse_count(value)?,
+            "team_size" => self.evaluation.team = parse_count(value)?,
+            "threads" => self.evaluation.threads = parse_count(value)?,
+            "islands" => self.islands.count = parse_count(value)?,
+            "migration_interval" => self.islands.interval = parse_count(value)?,
+            "migrants" => self.islands.migrants = parse_count(value)?,
+            "topology" => self.islands.topology = Topology::parse(value)?,
+            "hall_of_fame" => self.hall_of_fame = parse_count(value)?,
+            "diversity_bins" => self.diversity.bins = parse_count(value)?,
+            "plateau" if value == "none" => self.diversity.plateau = None,
+            "plateau" => self.diversity.plateau = Some(parse_count(value)?),
+            "plateau_tolerance" => self.diversity.tolerance = parse_non_negative(value)?,
+            // 種をつけなければ猫の操舵のしかた
+            "controller" => self.cat.controller = Controller::parse(value)?,
+            "hidden" => self.controller.hidden = parse_count(value)?,
+            "nearest" => self.controller.nearest = parse_count(value)?,
+            "sense_radious" => self.controller.radious = parse_positive(value)?,
+            "weight_mutation" => self.controller.mutation = parse_non_negative(value)?,
+            "add_node" => self.neat.add_node = parse_ratio(value)?,
+            "add_connection" => self.neat.add_connection = parse_ratio(value)?,
+            "compatibility" => self.neat.compatibility = parse_positive(value)?,
+            "crossover_rate" => self.neat.crossover = parse_ratio(value)?,
+            "survival" => self.neat.survival = parse_ratio(value)?,
+            "seed" if value == "none" => self.seed = None,
+            "seed" => self.seed = Some(value.parse::<u64>().map_err(|_| format!("`{}` is not a seed", value))?),
+            _ if key.starts_with("island.") => return self.set_island(key, value),
+            _ => return self.set_species(key, value),
+        }
+        Ok(())
+    }
+    
+    // 「island.番号.キー」の形の設定。番号の島だけで使う設定として取っておく
+    fn set_island(&mut self, key: &str, value: &str) -> Result<(), String> {
+        let mut path = key.splitn(3, '.').skip(1);
+        let n = path
+            .next()
+            .and_then(|n| n.parse::<usize>().ok())
+            .ok_or_else(|| format!("expected `island.N.key` in `{}`", key))?;
+        let rest = path.next().unwrap_or("");
+        // 書いた時点で正しい値かを確かめる
+        Config::default().set(rest, value).map_err(|e| format!("{} in `{}`", e, key))?;
+        self.islands.overrides.push((n, String::from(rest), String::from(value)));
+        Ok(())
+    }
+    
+    // 「種.キー」の形の設定
+    fn set_species(&mut self, key: &str, value: &str) -> Result<(), String> {
+        let mut path = key.splitn(2, '.');
+        let species = match path.next() {
+            Some("cat") => &mut self.cat,
+            Some("rat") => &mut self.rat,
+            _ => return Err(format!("unknown key `{}`", key)),
+        };
+        species
+            .set(path.next().unwrap_or(""), value)
+            .map_err(|e| format!("{} in `{}`", e, key))?;
+        // 種が持っていない遺伝子は使えない
+        for &(species, ref config) in &[(Species::Cat, &self.cat), (Species::Rat, &self.rat)] {
+            let missing = config
+                .behaviors
+                .iter()
+                .flat_map(|weighted| weighted.genes())
+                .find(|gene| !gene.carried_by(species));
+            if let Some(gene) = missing {
+                return Err(format!("{:?} has no gene {:?} in `{}`", species, gene, key));
+            }
+        }
+        Ok(())
+    }
+}
+
+impl SpeciesConfig {
+    fn new(behaviors: Vec<WeightedBehavior>, motion: MotionConfig, metabolism: f64, graze_rate: f64, birth_energy: f64) -> SpeciesConfig {
+        SpeciesConfig {
+            vision: VisionConfig::default(),
+            falloff: FalloffConfig::default(),
+            behaviors,
+            controller: Controller::Boids,
+            motion,
+            metabolism,
+            graze_rate,
+            birth: BirthConfig {
+                energy: birth_energy,
+                share: BIRTH_SHARE,
+                rate: BIRTH_RATE,
+                capacity: None,
+            },
+            life: LifeConfig {
+                lifespan: None,
+                senescence: SENESCENCE,
+                slowdown: SENESCENT_SLOWDOWN,
+                metabolism: SENESCENT_METABOLISM,
+            },
+        }
+    }
+    
+    // behaviorsは「重み 名前 引数...」を「;」で区切って並べ、既定の一覧を置き換える
+    fn set(&mut self, key: &str, value: &str) -> Result<(), String> {
+        match key {
+            "view_angle" => self.vision.view_angle = parse_angle(value)?,
+            "blind_spot" => self.vision.blind_spot = parse_angle(value)?,
+            "occlusion" => self.vision.occlusion = parse_bool(value)?,
+            "evolve_vision" => self.vision.evolvable = parse_bool(value)?,
+            "chase_falloff" => self.falloff.chase = Falloff::parse(value)?,
+            "separate_falloff" => self.falloff.separate = Falloff::parse(value)?,
+            "align_falloff" => self.falloff.align = Falloff::parse(value)?,
+            "cohension_falloff" => self.falloff.cohension = Falloff::parse(value)?,
+            "run_away_falloff" => self.falloff.run_away = Falloff::parse(value)?,
+            "behaviors" => self.behaviors = WeightedBehavior::parse_list(value)?,
+            "controller" => self.controller = Controller::parse(value)?,
+            "max_force" => self.motion.max_force = parse_positive(value)?,
+            "max_turn_rate" => self.motion.max_turn_rate = parse_positive(value)?.to_radians(),
+            "max_speed" => self.motion.max_speed = parse_positive(value)?,
+            "metabolism" => self.metabolism = parse_non_negative(value)?,
+            "graze_rate" => self.graze_rate = parse_non_negative(value)?,
+            "birth_energy" => self.birth.energy = parse_non_negative(value)?,
+            "birth_share" => self.birth.share = parse_ratio(value)?,
+            "birth_rate" => self.birth.rate = parse_non_negative(value)?,
+            "capacity" if value == "none" => self.birth.capacity = None,
+            "capacity" => self.birth.capacity = Some(parse_positive(value)?),
+            "lifespan" if value == "none" => self.life.lifespan = None,
+            "lifespan" => self.life.lifespan = Some(parse_positive(value)?),
+            "senescence" => self.life.senescence = parse_ratio(value)?,
+            "senescent_slowdown" => self.life.slowdown = parse_ratio(value)?,
+            "senescent_metabolism" => self.life.metabolism = parse_non_negative(value)?,
+            _ => return Err(String::from("unknown key")),
+        }
+        Ok(())
+    }
+}
+
+impl LifeConfig {
+    // 老化の進み具合。老化が始まるまでは0で、寿命で1になる
+    pub fn decline(&self, age: f64) -> f64 {
+        match self.lifespan {
+            Some(lifespan) => {
+                let start = lifespan * self.senescence;
+                if age <= start {
+                    0.0
+                } else if lifespan <= start {
+                    1.0
+                } else {
+                    ((age - start) / (lifespan - start)).min(1.0)
+                }
+            }
+            None => 0.0,
+        }
+    }
+    
+    // 寿命を迎えたかどうか
+    pub fn is_expired(&self, age: f64) -> bool {
+        self.lifespan.map_or(false, |lifespan| age >= lifespan)
+    }
+    
+    // 老化で落ちた最高速
+    pub fn max_speed(&self, max_speed: f64, age: f64) -> f64 {
+        max_speed * (1.0 - self.slowdown * self.decline(age))
+    }
+    
+    // 老化で増えた代謝
+    pub fn metabolism(&self, metabolism: f64, age: f64) -> f64 {
+        metabolism * (1.0 + self.metabolism * self.decline(age))
+    }
+}
+
+pub fn parse_f64(value: &str) -> Result<f64, String> {
+    value
+        .parse::<f64>()
+        .map_err(|_| format!("`{}` is not a number", value))
+}
+
+pub fn parse_positive(value: &str) -> Result<f64, String> {
+    let number = parse_f64(value)?;
+    if number <= 0.0 {
+        return Err(format!("`{}` must be positive", value));
+    }
+    Ok(number)
+}
+
+pub fn parse_non_negative(value: &str) -> Result<f64, String> {
+    let number = parse_f64(value)?;
+    if number < 0.0 {
+        return Err(format!("`{}` must not be negative", value));
+    }
+    Ok(number)
+}
+
+pub fn parse_ratio(value: &str) -> Result<f64, String> {
+    let number = parse_f64(value)?;
+    if number < 0.0 || 1.0 < number {
+        return Err(format!("`{}` must be between 0 and 1", value));
+    }
+    Ok(number)
+}
+
+// 1以上の整数
+pub fn parse_count(value: &str) -> Result<usize, String> {
+    match value.parse::<usize>() {
+        Ok(count) if count > 0 => Ok(count),
+        _ => Err(format!("`{}` is not a positive integer", value)),
+    }
+}
+
+pub fn parse_bool(value: &str) -> Result<bool, String> {
+    match value {
+        "true" | "yes" | "on" => Ok(true),
+        "false" | "no" | "off" => Ok(false),
+        _ => Err(format!("`{}` is not a boolean", value)),
+    }
+}
+
+// 度で書かれた角度をラジアンにする
+pub fn parse_angle(value: &str) -> Result<f64, String> {
+    let degree = parse_f64(value)?;
+    if degree < 0.0 || degree > 360.0 {
+        return Err(format!("angle `{}` is out of 0..360", value));
+    }
+    Ok(degree.to_radians())
+}
@@ -0,0 +1,228 @@
+#[cfg(test)]
+mod tests{
+    use boundary::Boundary;
+    use config;
+    use config::Config;
+    use consts::*;
+    use evaluation::Evaluation;
+    use falloff::Falloff;
+use island::Topology;
+use neural::Controller;
+    use pareto::Selection;
+    use predation::Credit;
+    use termination::{Combine, Condition};
+    
+    macro_rules! assert_float{
+        (
+            $x: expr ,$y: expr
+        ) => {
+            {
+                assert!((($x - $y) / $x).abs() < 1.0e-9);
+            }
+        }
+    }
+    
+    #[test]
+    fn default_test(){
+        let config = Config::default();
+        assert_float!(2.0 * std::f64::consts::PI, config.cat.vision.view_angle);
+        assert_eq!(0.0, config.rat.vision.blind_spot);
+        assert!(!config.cat.vision.occlusion);
+        assert!(!config.rat.vision.evolvable);
+        // ねずみの方が小回りがきく
+        assert!(config.rat.motion.max_turn_rate > config.cat.motion.max_turn_rate);
+    }
+    
+    #[test]
+    fn parse_test(){
+        let mut config = Config::default();
+        let text = "
+            # 猫は前方だけ見える
+            cat.view_angle = 120
+            rat.blind_spot = 30   # ねずみは真後ろが見えない
+            rat.occlusion = true
+            cat.evolve_vision = yes
+            cat.separate_falloff = inverse_square
+            rat.run_away_falloff = gaussian
+            rat.max_turn_rate = 45
+            cat.max_speed = 1.5
+            cat.max_force = 0.05
+            dt = 0.01
+            boundary = soft_wall
+            wall_margin = 30
+            food_regrowth = 5
+            rat.metabolism = 10
+            rat.capacity = 300
+            cat.birth_share = 0.3
+            cat.lifespan = 120
+            cat.senescent_slowdown = 0.2
+            kill_credit = random
+            pack_radious = 15
+            capture_chance = 0.8
+            confusion = 0.5
+            end = frames 600; stagnation 120
+            end_when = all
+            fitness = 1 kills; 0.01 survival
+            selection = pareto
+            objectives = kills; cohesion
+            evaluation = isolated
+            trials = 5
+            islands = 4
+            topology = full
+            island.2.cat.max_speed = 3
+            island.1.seed = 11
+            seed = 10
+            hall_of_fame = 5
+            diversity_bins = 20
+            plateau = 8
+            controller = neural
+            hidden = 4
+            rat.controller = neat
+            add_node = 0.1
+            compatibility = 2.5
+            survival = 0.5
+        ";
+        config.parse(text).unwrap();
+        assert_float!(120.0_f64.to_radians(), config.cat.vision.view_angle);
+        assert_float!(30.0_f64.to_radians(), config.rat.vision.blind_spot);
+        assert!(config.rat.vision.occlusion);
+        assert!(!config.cat.vision.occlusion);
+        assert!(config.cat.vision.evolvable);
+        assert_eq!(Falloff::InverseSquare, config.cat.falloff.separate);
+        assert_eq!(Falloff::Constant, config.cat.falloff.chase);
+        assert_eq!(Falloff::Gaussian, config.rat.falloff.run_away);
+        assert_float!(45.0_f64.to_radians(), config.rat.motion.max_turn_rate);
+        assert_float!(1.5, config.cat.motion.max_speed);
+        assert_float!(0.05, config.cat.motion.max_force);
+        assert_float!(0.01, config.dt);
+        assert_eq!(Boundary::SoftWall, config.boundary);
+        assert_float!(30.0, config.wall_margin);
+        assert_float!(5.0, config.food_regrowth);
+        assert_float!(10.0, config.rat.metabolism);
+        assert_eq!(Some(300.0), config.rat.birth.capacity);
+        assert_eq!(None, config.cat.birth.capacity);
+        assert_float!(0.3, config.cat.birth.share);
+        assert_eq!(Some(120.0), config.cat.life.lifespan);
+        assert_eq!(None, config.rat.life.lifespan);
+        assert_float!(0.2, config.cat.life.slowdown);
+        assert_eq!(Credit::Random, config.predation.credit);
+        assert_float!(15.0, config.predation.pack_radious);
+        assert_float!(EATEN_RADIOUS, config.predation.radious);
+        assert_float!(0.8, config.predation.capture.chance);
+        assert_float!(0.5, config.predation.capture.confusion);
+        assert_eq!(vec![Condition::Frames(600), Condition::Stagnation(120)], config.termination.conditions);
+        assert_eq!(Combine::All, config.termination.combine);
+        assert_eq!(2, config.fitness.len());
+        assert_eq!(Selection::Pareto, config.selection);
+        assert_eq!(2, config.objectives.len());
+        assert_eq!(Evaluation::Isolated, config.evaluation.mode);
+        assert_eq!(5, config.evaluation.trials);
+        assert_eq!(TEAM_SIZE, config.evaluation.team);
+        assert_eq!(4, config.islands.count);
+        assert_eq!(MIGRATION_INTERVAL, config.islands.interval);
+        assert_eq!(Topology::Full, config.islands.topology);
+        assert_eq!((2, String::from("cat.max_speed"), String::from("3")), config.islands.overrides[0]);
+        assert_eq!((1, String::from("seed"), String::from("11")), config.islands.overrides[1]);
+        assert_eq!(Some(10), config.seed);
+        assert_eq!(5, config.hall_of_fame);
+        assert_eq!(20, config.diversity.bins);
+        assert_eq!(Some(8), config.diversity.plateau);
+        assert_float!(PLATEAU_TOLERANCE, config.diversity.tolerance);
+        assert_eq!(Controller::Neural, config.cat.controller);
+        assert_eq!(Controller::Neat, config.rat.controller);
+        assert_float!(0.1, config.neat.add_node);
+        assert_float!(NEAT_ADD_CONNECTION, config.neat.add_connection);
+        assert_float!(2.5, config.neat.compatibility);
+        assert_float!(0.5, config.neat.survival);
+        assert_eq!(4, config.controller.hidden);
+        assert_eq!(NEURAL_NEAREST, config.controller.nearest);
+    }
+    
+    #[test]
+    fn life_test(){
+        let mut config = Config::default();
+        // 寿命がなければ老いない
+        assert_eq!(0.0, config.cat.life.decline(1.0e9));
+        assert!(!config.cat.life.is_expired(1.0e9));
+        config.parse("cat.lifespan = 100\ncat.senescence = 0.5\ncat.senescent_metabolism = 2").unwrap();
+        let life = &config.cat.life;
+        assert_eq!(0.0, life.decline(50.0));
+        assert_float!(0.5, life.decline(75.0));
+        assert_float!(1.0, life.decline(150.0));
+        assert!(!life.is_expired(99.0));
+        assert!(life.is_expired(100.0));
+        // 寿命を迎える頃には遅くなり、多く食べる
+        assert_float!(60.0 * (1.0 - SENESCENT_SLOWDOWN), life.max_speed(60.0, 100.0));
+        assert_float!(30.0, life.metabolism(10.0, 100.0));
+        assert_float!(10.0, life.metabolism(10.0, 10.0));
+    }
+    
+    #[test]
+    fn parse_obstacle_test(){
+        let mut config = Config::default();
+        assert_eq!(config.obstacles.len(), 0);
+        config.parse("obstacle = circle 10 10 5\nobstacle = polygon 100 50 120 50 110 70").unwrap();
+        assert_eq!(config.obstacles.len(), 2);
+        config.parse("refuge = circle 300 200 30").unwrap();
+        assert_eq!(config.refuges.len(), 1);
+        assert!(config.parse("obstacle = circle 10").is_err());
+    }
+    
+    #[test]
+    fn parse_error_test(){
+        let mut config = Config::default();
+        assert!(config.parse("cat.view_angle").is_err());
+        assert!(config.parse("dog.view_angle = 10").is_err());
+        assert!(config.parse("cat.tail = 10").is_err());
+        assert!(config.parse("cat.view_angle = wide").is_err());
+        assert!(config.parse("cat.view_angle = 400").is_err());
+        assert!(config.parse("rat.occlusion = maybe").is_err());
+        assert!(config.parse("cat.chase_falloff = cubic").is_err());
+        assert!(config.parse("cat.max_speed = 0").is_err());
+        assert!(config.parse("rat.max_force = -1").is_err());
+        assert!(config.parse("dt = 0").is_err());
+        assert!(config.parse("boundary = sphere").is_err());
+        assert!(config.parse("rat.graze_rate = -1").is_err());
+        assert!(config.parse("cat.birth_share = 1.5").is_err());
+        assert!(config.parse("rat.capacity = 0").is_err());
+        assert!(config.parse("cat.lifespan = -5").is_err());
+        assert!(config.parse("rat.senescence = 2").is_err());
+        assert!(config.parse("kill_credit = first").is_err());
+        assert!(config.parse("catch_radious = 0").is_err());
+        assert!(config.parse("capture_chance = 1.5").is_err());
+        assert!(config.parse("miss_cost = -1").is_err());
+        assert!(config.parse("end = forever").is_err());
+        assert!(config.parse("end =").is_err());
+        assert!(config.parse("end_when = some").is_err());
+        assert!(config.parse("fitness = 1 luck").is_err());
+        assert!(config.parse("selection = tournament").is_err());
+        assert!(config.parse("trials = 0").is_err());
+        assert!(config.parse("threads = 1.5").is_err());
+        assert!(config.parse("migrants = 0").is_err());
+        assert!(config.parse("topology = star").is_err());
+        assert!(config.parse("hall_of_fame = 0").is_err());
+        assert!(config.parse("plateau = forever").is_err());
+        assert!(config.parse("plateau_tolerance = -0.1").is_err());
+        assert!(config.parse("controller = fuzzy").is_err());
+        assert!(config.parse("sense_radious = 0").is_err());
+        assert!(config.parse("rat.controller = fuzzy").is_err());
+        assert!(config.parse("add_node = 1.5").is_err());
+        assert!(config.parse("compatibility = 0").is_err());
+        assert!(config.parse("island.x.trials = 2").is_err());
+        assert!(config.parse("island.1.trials = 0").is_err());
+        assert!(config.parse("seed = -1").is_err());
+        // エラーには行番号が入る
+        let message = config.parse("\ncat.view_angle = 90\ncat.view_angle = x").unwrap_err();
+        assert!(message.starts_with("line 3"));
+    }
+    
+    #[test]
+    fn set_get_test(){
+        let mut changed = Config::default();
+        changed.rat.vision.view_angle = 1.0;
+        config::set(changed);
+        assert_eq!(1.0, config::get().rat.vision.view_angle);
+        config::set(Config::default());
+        assert_float!(2.0 * std::f64::consts::PI, config::get().rat.vision.view_angle);
+    }
+}
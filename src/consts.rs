@@ -1,21 +1,85 @@
 pub const WIDTH: f64 = 640.0;
 pub const HEIGHT: f64 = 480.0;
-pub const ENERGY_MAX: u64 = 1000;
-pub const RAT_VELOCITY: f64 = 0.5;
-pub const CAT_VELOCITY: f64 = 1.0;
+pub const CAT_COUNT: usize = 10;
+pub const RAT_COUNT: usize = 200;
+pub const CAT_OFFSPRING: usize = 20; // 二世代目からの猫の数
+pub const ENERGY_MAX: f64 = 1000.0;
+// 速さなどは秒あたりで表す
+pub const FRAME_RATE: f64 = 60.0;
+pub const RAT_VELOCITY: f64 = 30.0;
+pub const CAT_VELOCITY: f64 = 60.0;
+pub const CAT_MAX_FORCE: f64 = 720.0;
+pub const RAT_MAX_FORCE: f64 = 1800.0;
+pub const CAT_MAX_TURN_RATE: f64 = 10.47; // 約600度
+pub const RAT_MAX_TURN_RATE: f64 = 31.42; // 約1800度
+pub const SPEED_MIN: f64 = 0.25;
+pub const SPEED_MAX: f64 = 64.0;
+pub const GENERATION_FRAMES: u64 = 36000; // これだけステップを進めたら世代を終える
+pub const TRIALS: usize = 3;        // 別々の場所で試す回数
+pub const TEAM_SIZE: usize = 1;     // 一緒に試す同じ遺伝子の猫の数
+pub const EVALUATION_THREADS: usize = 4;
+pub const MIGRATION_INTERVAL: usize = 5; // 何世代ごとに島の間で個体を移すか
+pub const MIGRANTS: usize = 1;            // 一度に一つの島から移る個体の数
+pub const HALL_OF_FAME: usize = 3;       // 世代ごとに殿堂入りさせる猫の数
+pub const DIVERSITY_BINS: usize = 10;     // 遺伝子のエントロピーを求める時のビンの数
+pub const PLATEAU_TOLERANCE: f64 = 0.01;  // 変化がこの割合以下なら頭打ちとみなす
+pub const NEURAL_HIDDEN: usize = 8;       // ニューラルネットワークの隠れ層のニューロンの数
+pub const NEURAL_NEAREST: usize = 3;      // 入力に使う近い猫とねずみの数
+pub const NEURAL_RADIOUS: f64 = 50.0;     // ネットワークが近くの個体を感じ取る範囲
+pub const NEURAL_FORCE: f64 = 480.0;      // ネットワークの出力にかける力。振る舞いの重みの上限とそろえる
+pub const WEIGHT_MUTATE_ABS: f64 = 0.1;   // 子孫を残す時にネットワークの重みを変える幅
+pub const NEAT_ADD_NODE: f64 = 0.03;       // 子を作る時にニューロンを足す確率
+pub const NEAT_ADD_CONNECTION: f64 = 0.05; // 子を作る時に結合を足す確率
+pub const NEAT_REPLACE_RATE: f64 = 0.1;    // 重みを少し変える代わりに新しく選び直す確率
+pub const NEAT_DISABLE_RATE: f64 = 0.75;   // どちらかの親で無効な結合を子でも無効にする確率
+pub const NEAT_ATTEMPTS: usize = 20;       // 足せる結合を探す回数
+pub const NEAT_EXCESS: f64 = 1.0;          // 種の距離での余剰の遺伝子の係数
+pub const NEAT_DISJOINT: f64 = 1.0;        // 種の距離での離散の遺伝子の係数
+pub const NEAT_WEIGHT: f64 = 0.4;          // 種の距離での重みの差の係数
+pub const COMPATIBILITY: f64 = 3.0;        // 代表とこの距離より近ければ同じ種
+pub const CROSSOVER_RATE: f64 = 0.75;      // 子を交叉で作る確率
+pub const NEAT_SURVIVAL: f64 = 0.2;        // 種の中で親になれる上位の割合
+pub const NEAT_ELITE_SIZE: usize = 5;      // この数以上の種は一番良い個体をそのまま残す
+pub const MAX_STEPS_PER_UPDATE: u32 = 256;
 pub const MUTATE_ABS: f64 = 10.0;
 pub const RUNAWAY_RADIOUS: f64 = 10.0;
 pub const COHENSION_RADIOUS: f64 = 15.0;
 pub const EATEN_RADIOUS: f64 = 1.0;
+pub const PACK_RADIOUS: f64 = 0.0; // 獲物を分け合う範囲。0なら食べた猫だけのもの
+pub const CONFUSION_RADIOUS: f64 = 20.0; // 目移りさせるねずみを数える範囲
 pub const ALIGN_RADIOUS: f64 = 10.0;
 pub const SEPARATE_RADIOUS: f64 = 5.0;
 pub const CHASE_RADIOUS: f64 = 10.0;
-pub const EAT_ENERGY: u64 = 300;
+pub const EAT_ENERGY: f64 = 300.0;
 pub const COHENSION_MAX: f64 = 480.0;
 pub const CHASE_MAX: f64 = 480.0;
 pub const SEPARATE_MAX: f64 = 480.0;
 pub const ALIGN_MAX: f64 = 480.0;
 pub const CELL_WIDTH: f64 = 64.0;
+pub const FOOD_CAPACITY: f64 = 2000.0; // 1マスの草の最大量
+pub const FOOD_REGROWTH: f64 = 20.0;   // 1マスで1秒に生える草の量
+pub const CAT_METABOLISM: f64 = 60.0;  // 1秒に使うエネルギー
+pub const RAT_METABOLISM: f64 = 30.0;
+pub const RAT_GRAZE_RATE: f64 = 120.0; // 1秒に食べられる草の量
+pub const CAT_BIRTH_ENERGY: f64 = 1500.0; // 子を作れるエネルギーの下限
+pub const RAT_BIRTH_ENERGY: f64 = 900.0;
+pub const BIRTH_SHARE: f64 = 0.5;         // 子に分けるエネルギーの割合
+pub const BIRTH_RATE: f64 = 1.0;          // 1秒あたりに子を作る確率
+pub const SENESCENCE: f64 = 0.7;          // 寿命のうち老化が始まる割合
+pub const SENESCENT_SLOWDOWN: f64 = 0.5;  // 寿命を迎えた時に落ちている最高速の割合
+pub const SENESCENT_METABOLISM: f64 = 1.0; // 寿命を迎えた時に増えている代謝の割合
+pub const VISION_MUTATE_ABS: f64 = 0.1;
+pub const OCCLUDER_RADIOUS: f64 = 1.0;
+pub const FALLOFF_SOFTENING: f64 = 1.0;
+pub const LEAD_TIME_MAX: f64 = 0.5;
+pub const LEAD_TIME_MUTATE_ABS: f64 = 0.02;
+pub const AVOID_PROBES: usize = 4;
+pub const AVOID_MARGIN: f64 = 2.0;
+pub const AVOID_LOOKAHEAD: f64 = 20.0;
+pub const CAT_AVOID_WEIGHT: f64 = 500.0;
+pub const RAT_AVOID_WEIGHT: f64 = 1.0;
+pub const WALL_MARGIN: f64 = 20.0;
+pub const WALL_WEIGHT: f64 = 500.0;
 
 // 下の二つの変数はquad_treeの使用によって変わります
 pub const MIN_WIDTH: f64 = 5.0;
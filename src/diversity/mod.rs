@@ -0,0 +1,139 @@
+mod test;
+
+use animal::{Animal, Cat};
+use config;
+use consts::*;
+use neural::Brain;
+use std::f64::consts::PI;
+use std::io;
+use std::io::Write;
+
+// 多様性の記録の見出し。分散は遺伝子ごと
+pub const HEADER: &str = "gen,distance,entropy,mean_fitness,var_chase,var_separate,var_align,var_cohension,var_lead_time,var_view_angle,var_blind_spot";
+
+// 見出しに並べる遺伝子の数
+const GENES: usize = 7;
+
+// 一世代の猫の遺伝的な多様性。遺伝子は上限で割って0から1にそろえる
+#[derive(Debug, Clone, PartialEq)]
+pub struct Diversity {
+    pub variances: Vec<f64>, // 遺伝子ごとの分散
+    pub distance: f64,       // 二匹の遺伝子の距離の平均
+    pub entropy: f64,        // 遺伝子ごとのビンに分けたエントロピー(ビット)の平均
+}
+
+// 上限で割った遺伝子。並びは見出しと同じ
+// 重みだけを進化させるネットワークで操舵するなら、重みをtanhで0から1に写して後ろに続ける
+pub fn genome(cat: &Cat) -> Vec<f64> {
+    let mut ret = vec![
+        cat.chase_weight / CHASE_MAX,
+        cat.separate_weight / SEPARATE_MAX,
+        cat.align_weight / ALIGN_MAX,
+        cat.cohension_weight / COHENSION_MAX,
+        cat.lead_time / LEAD_TIME_MAX,
+        cat.vision.view_angle / (2.0 * PI),
+        cat.vision.blind_spot / (2.0 * PI),
+    ];
+    if let Some(Brain::Network(network)) = cat.brain() {
+        ret.extend(network.weights.iter().map(|weight| (weight.tanh() + 1.0) / 2.0));
+    }
+    ret
+}
+
+// 猫の遺伝子の多様性を測る。猫がいなければすべて0
+// 構造ごと進化させるネットワークは並びをそろえられないので、種分けの距離を二匹の距離に足す
+pub fn measure(cats: &Vec<Cat>) -> Diversity {
+    let genomes: Vec<Vec<f64>> = cats.iter().map(genome).collect();
+    let brains: Vec<Option<Brain>> = cats.iter().map(|cat| cat.brain()).collect();
+    let count = genomes.len();
+    let genes = genomes.iter().map(|genome| genome.len()).min().unwrap_or(GENES);
+    let column = |m: usize| -> Vec<f64> { genomes.iter().map(|genome| genome[m]).collect() };
+    let variances = (0..GENES).map(|m| variance(&column(m))).collect();
+    let bins = config::get().diversity.bins;
+    let entropy = if count == 0 {
+        0.0
+    } else {
+        (0..genes).map(|m| entropy(&column(m), bins)).sum::<f64>() / genes as f64
+    };
+    let mut total = 0.0;
+    for i in 0..count {
+        for j in (i + 1)..count {
+            let structure = match (&brains[i], &brains[j]) {
+                (&Some(Brain::Neat(ref a)), &Some(Brain::Neat(ref b))) => a.distance(b),
+                _ => 0.0,
+            };
+            let genes: f64 = genomes[i][..genes]
+                .iter()
+                .zip(&genomes[j][..genes])
+                .map(|(a, b)| (a - b) * (a - b))
+                .sum();
+            total += (genes + structure * structure).sqrt();
+        }
+    }
+    let pairs = count * count.saturating_sub(1) / 2;
+    Diversity {
+        variances,
+        distance: if pairs > 0 { total / pairs as f64 } else { 0.0 },
+        entropy,
+    }
+}
+
+impl Diversity {
+    // 「世代,距離,エントロピー,平均適応度,遺伝子ごとの分散...」の行で書き出す
+    pub fn write<W: Write>(&self, out: &mut W, ord: i32, mean_fitness: f64) -> io::Result<()> {
+        let variances: Vec<String> = self.variances.iter().map(|variance| variance.to_string()).collect();
+        writeln!(out, "{},{},{},{},{}", ord, self.distance, self.entropy, mean_fitness, variances.join(","))
+    }
+}
+
+// 母分散。値がなければ0
+pub fn variance(values: &[f64]) -> f64 {
+    if values.is_empty() {
+        return 0.0;
+    }
+    let mean = values.iter().sum::<f64>() / values.len() as f64;
+    values.iter().map(|value| (value - mean) * (value - mean)).sum::<f64>() / values.len() as f64
+}
+
+// 0から1の値をbins個のビンに分けた時のエントロピー(ビット)
+pub fn entropy(values: &[f64], bins: usize) -> f64 {
+    let mut counts = vec![0; bins];
+    for value in values {
+        let bin = ((value * bins as f64).floor().max(0.0) as usize).min(bins - 1);
+        counts[bin] += 1;
+    }
+    let total = values.len() as f64;
+    counts
+        .iter()
+        .filter(|&&count| count > 0)
+        .map(|&count| {
+            let p = count as f64 / total;
+            -p * p.log2()
+        })
+        .sum()
+}
+
+// 最後のwindow世代の間の変化の幅が、絶対値の最大のtolerance倍以下か
+// window世代の間の変化を見るには、その前の世代を含めてwindow + 1個の値がいる
+fn is_flat(values: &[f64], window: usize, tolerance: f64) -> bool {
+    if values.len() < window + 1 {
+        return false;
+    }
+    let recent = &values[values.len() - window - 1..];
+    let high = recent.iter().cloned().fold(std::f64::NEG_INFINITY, f64::max);
+    let low = recent.iter().cloned().fold(std::f64::INFINITY, f64::min);
+    let scale = recent.iter().fold(0.0, |scale: f64, value| scale.max(value.abs()));
+    high - low <= tolerance * scale
+}
+
+// 世代ごとの(距離, 平均適応度)の記録から、設定した世代数だけ両方が頭打ちになったか
+pub fn has_converged(history: &[(f64, f64)]) -> bool {
+    let config = config::get();
+    let window = match config.diversity.plateau {
+        Some(window) => window,
+        None => return false,
+    };
+    let distances: Vec<f64> = history.iter().map(|&(distance, _)| distance).collect();
+    let fitness: Vec<f64> = history.iter().map(|&(_, fitness)| fitness).collect();
+    is_flat(&distances, window, config.diversity.tolerance) && is_flat(&fitness, window, config.diversity.tolerance)
+}
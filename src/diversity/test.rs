@@ -0,0 +1,107 @@
+#[cfg(test)]
+mod tests{
+    use animal::{Animal, Cat};
+    use config;
+    use config::Config;
+    use consts::*;
+    use diversity::{entropy, genome, has_converged, measure, variance};
+    use neural::{Brain, Controller, Network, inputs};
+    use rand::prelude::*;
+
+    #[test]
+    fn genome_test(){
+        let mut cat = Cat::new();
+        cat.chase_weight = CHASE_MAX / 2.0;
+        cat.lead_time = LEAD_TIME_MAX;
+        let genome = genome(&cat);
+        assert_eq!(7, genome.len());
+        assert_eq!(0.5, genome[0]);
+        assert_eq!(1.0, genome[4]);
+        assert!(genome.iter().all(|&gene| 0.0 <= gene && gene <= 1.0));
+    }
+
+    #[test]
+    fn variance_entropy_test(){
+        assert_eq!(0.0, variance(&[]));
+        assert_eq!(0.25, variance(&[0.0, 1.0]));
+        // 一つのビンに集まれば0、二つに半分ずつなら1ビット
+        assert_eq!(0.0, entropy(&[0.31, 0.32, 0.33], 10));
+        assert_eq!(1.0, entropy(&[0.05, 0.95], 10));
+        // 端の値は端のビンに入る
+        assert_eq!(0.0, entropy(&[1.0, 0.95], 10));
+        assert_eq!(0.0, entropy(&[], 10));
+    }
+
+    #[test]
+    fn measure_test(){
+        // 同じ遺伝子の猫ばかりなら多様性はない
+        let cat = Cat::new();
+        let clones = vec![cat.clone(), cat.twin(), cat.twin()];
+        let same = measure(&clones);
+        assert!(same.distance < 1.0e-12);
+        assert_eq!(0.0, same.entropy);
+        assert!(same.variances.iter().all(|&variance| variance < 1.0e-12));
+
+        let mut far = cat.twin();
+        far.chase_weight = if cat.chase_weight < CHASE_MAX / 2.0 { CHASE_MAX } else { 0.0 };
+        let mixed = measure(&vec![cat.clone(), far.clone()]);
+        assert!((mixed.distance - (cat.chase_weight - far.chase_weight).abs() / CHASE_MAX).abs() < 1.0e-9);
+        assert!(mixed.variances[0] > 0.0);
+        assert_eq!(0.0, mixed.variances[1]);
+        assert!(mixed.entropy > 0.0);
+
+        // ネットワークで操舵するなら重みの違いも多様性になる
+        let mut config = Config::default();
+        config.cat.controller = Controller::Neural;
+        config::set(config);
+        let cat = Cat::new();
+        let mut other = cat.twin();
+        other.brain = Some(Brain::Network(Network::random(inputs(NEURAL_NEAREST), NEURAL_HIDDEN, 2)));
+        assert_eq!(7 + Network::weight_count(inputs(NEURAL_NEAREST), NEURAL_HIDDEN, 2), genome(&cat).len());
+        assert!(measure(&vec![cat.clone(), cat.twin()]).distance < 1.0e-12);
+        assert!(measure(&vec![cat.clone(), other]).distance > 0.0);
+
+        // 構造ごと進化させるネットワークは種分けの距離で比べる
+        let mut config = Config::default();
+        config.cat.controller = Controller::Neat;
+        config::set(config);
+        let cat = Cat::new();
+        let mut other = cat.twin();
+        other.brain = match cat.brain() {
+            Some(Brain::Neat(genome)) => Some(Brain::Neat(genome.add_node(&mut thread_rng()))),
+            _ => panic!("expected a NEAT brain"),
+        };
+        assert_eq!(7, genome(&cat).len());
+        assert!(measure(&vec![cat.clone(), cat.twin()]).distance < 1.0e-12);
+        assert!(measure(&vec![cat.clone(), other]).distance > 0.0);
+        config::set(Config::default());
+
+        let empty = measure(&Vec::new());
+        assert_eq!(0.0, empty.distance);
+        assert_eq!(0.0, empty.entropy);
+    }
+
+    #[test]
+    fn converged_test(){
+        let history = vec![(0.5, 1.0), (0.3, 2.0), (0.3, 2.0), (0.301, 2.01), (0.3, 2.0)];
+        // plateauを設定しなければ止めない
+        assert!(!has_converged(&history));
+
+        let mut config = Config::default();
+        config.diversity.plateau = Some(3);
+        config::set(config.clone());
+        assert!(has_converged(&history));
+        assert!(!has_converged(&history[..4]));
+
+        config.diversity.plateau = Some(4);
+        config::set(config.clone());
+        assert!(!has_converged(&history));
+
+        // 1世代の変化を見るにも二つの世代がいる
+        config.diversity.plateau = Some(1);
+        config::set(config);
+        assert!(!has_converged(&history[..1]));
+        assert!(has_converged(&history[..3]));
+        config::set(Config::default());
+    }
+}
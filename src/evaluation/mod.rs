@@ -0,0 +1,90 @@
+mod test;
+
+use animal::{Animal, Cat, Rat};
+use config;
+use fitness::Score;
+use rand::prelude::*;
+use random;
+use scenario::Scenario;
+use std::collections::HashSet;
+use std::thread;
+use world::World;
+
+// 猫の評価のしかた
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Evaluation {
+    Shared,   // 全ての猫が一つの場所で一緒に狩る
+    Isolated, // 遺伝子ごとに別の場所で何度か試し、平均をとる
+}
+
+impl Evaluation {
+    // 設定ファイルの名前から読む
+    pub fn parse(text: &str) -> Result<Evaluation, String> {
+        match text {
+            "shared" => Ok(Evaluation::Shared),
+            "isolated" => Ok(Evaluation::Isolated),
+            _ => Err(format!("unknown evaluation `{}`", text)),
+        }
+    }
+}
+
+// 遺伝子ごとに新しい場所でtrials回試し、値の平均を返す
+// 遺伝子はthreads本のスレッドに分けて並列に試す。設定はスレッドごとに写し、乱数のシードは今の乱数から配る
+pub fn evaluate(genomes: &Vec<Cat>, scenario: &Scenario) -> Vec<Score> {
+    let config = config::get();
+    let threads = config.evaluation.threads.max(1).min(genomes.len().max(1));
+    let handles: Vec<thread::JoinHandle<Vec<(usize, Score)>>> = (0..threads)
+        .map(|t| {
+            let config = (*config).clone();
+            let seed: u64 = random::rng().gen();
+            let scenario = scenario.clone();
+            let jobs: Vec<(usize, Cat)> = genomes
+                .iter()
+                .cloned()
+                .enumerate()
+                .filter(|&(n, _)| n % threads == t)
+                .collect();
+            thread::spawn(move || {
+                config::set(config);
+                random::seed(seed);
+                jobs
+                    .into_iter()
+                    .map(|(n, genome)| (n, evaluate_genome(&genome, &scenario)))
+                    .collect()
+            })
+        })
+        .collect();
+    let mut ret: Vec<Option<Score>> = vec![None; genomes.len()];
+    for handle in handles {
+        for (n, score) in handle.join().expect("evaluation thread panicked") {
+            ret[n] = Some(score);
+        }
+    }
+    ret.into_iter().map(|score| score.expect("genome was not evaluated")).collect()
+}
+
+// 一つの遺伝子を何度か試した平均
+pub fn evaluate_genome(genome: &Cat, scenario: &Scenario) -> Score {
+    let trials = config::get().evaluation.trials.max(1);
+    let scores: Vec<Score> = (0..trials).map(|_| trial(genome, scenario)).collect();
+    Score::mean(&scores)
+}
+
+// 同じ遺伝子の猫team匹を新しく作った場所に置き、世代が終わるまで狩らせる
+// 最初に置いた猫の値の平均をとる。死んだ猫は死ぬ直前の値で数え、途中で生まれた猫は数えない
+fn trial(genome: &Cat, scenario: &Scenario) -> Score {
+    let team: Vec<Cat> = (0..config::get().evaluation.team.max(1)).map(|_| genome.twin()).collect();
+    let cats = scenario.cats.place(&team);
+    let ids: HashSet<u64> = cats.iter().map(|cat| cat.id()).collect();
+    let rats: Vec<Rat> = scenario.rats.spawn();
+    let mut world = World::with_animals(cats, rats);
+    world.run();
+    let (cats, scores) = world.candidates();
+    let scores: Vec<Score> = cats
+        .iter()
+        .zip(scores)
+        .filter(|&(cat, _)| ids.contains(&cat.id()))
+        .map(|(_, score)| score)
+        .collect();
+    Score::mean(&scores)
+}
@@ -0,0 +1,82 @@
+#[cfg(test)]
+mod tests{
+    use animal::{Animal, Cat};
+    use config;
+    use config::Config;
+    use evaluation::{Evaluation, evaluate, evaluate_genome};
+    use fitness::{Measure, Survival, WeightedFitness};
+    use scenario::Scenario;
+    use termination::Condition;
+    use world::World;
+
+    // 数ステップで終わる小さな場所
+    fn short_config() -> Config {
+        let mut config = Config::default();
+        config.termination.conditions = vec![Condition::Frames(3)];
+        config.evaluation.trials = 2;
+        config.evaluation.threads = 3;
+        config
+    }
+
+    fn small_scenario() -> Scenario {
+        let mut scenario = Scenario::default();
+        scenario.rats.count = 20;
+        scenario
+    }
+
+    #[test]
+    fn parse_test(){
+        assert_eq!(Ok(Evaluation::Isolated), Evaluation::parse("isolated"));
+        assert!(Evaluation::parse("alone").is_err());
+    }
+
+    #[test]
+    fn evaluate_test(){
+        config::set(short_config());
+        // 遺伝子ごとに一つずつ、順番どおりに値が返る
+        let genomes: Vec<Cat> = (0..5).map(|_| Cat::new()).collect();
+        let scores = evaluate(&genomes, &small_scenario());
+        assert_eq!(5, scores.len());
+        for score in &scores {
+            assert!(score.fitness >= 0.0);
+            assert_eq!(config::get().objectives.len(), score.objectives.len());
+        }
+        // 3ステップではねずみを食べ尽くせない
+        let genome = evaluate_genome(&genomes[0], &small_scenario());
+        assert!(genome.fitness <= 3.0 * 20.0);
+        config::set(Config::default());
+    }
+
+    #[test]
+    fn team_test(){
+        // 毎ステップ子を産んでも、値は最初に置いた猫だけで測る
+        let mut config = short_config();
+        config.evaluation.trials = 1;
+        config.evaluation.team = 1;
+        config.cat.birth.energy = 0.0;
+        config.cat.birth.rate = 1.0 / config.dt;
+        config.cat.birth.capacity = None;
+        config.fitness = vec![WeightedFitness { weight: 1.0, measure: Measure::Survival(Survival) }];
+        let dt = config.dt;
+        config::set(config);
+        let score = evaluate_genome(&Cat::new(), &small_scenario());
+        config::set(Config::default());
+        assert!((score.fitness - 3.0 * dt).abs() < 1.0e-9);
+    }
+
+    #[test]
+    fn world_evaluate_test(){
+        config::set(short_config());
+        let mut world = World::new(small_scenario());
+        let steps = world.steps;
+        world.evaluate();
+        // この場所は進めずに、評価した値で次の世代を選ぶ
+        assert_eq!(steps, world.steps);
+        assert_eq!(Some(String::from("isolated")), world.ended);
+        assert_eq!(world.cats.len(), world.scores().len());
+        world.next_generation();
+        assert!(world.scores.is_none());
+        assert_eq!(world.scenario.cats.next_count(), world.cats.len());
+        config::set(Config::default());
+    }
+}
@@ -0,0 +1,66 @@
+mod test;
+
+use consts::*;
+use pvector::PVector;
+
+// 距離による力の減衰のしかた
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Falloff {
+    Constant,     // 距離によらず一定。ベクトルの和を正規化する従来の方法
+    Inverse,      // 距離に反比例
+    InverseSquare, // 距離の二乗に反比例
+    Linear,       // 半径の位置で0になるように直線的に減る
+    Gaussian,     // 半径の半分を標準偏差とする正規分布
+}
+
+impl Falloff {
+    // 設定ファイルの名前から変換
+    pub fn parse(name: &str) -> Result<Falloff, String> {
+        match name {
+            "constant" => Ok(Falloff::Constant),
+            "inverse" => Ok(Falloff::Inverse),
+            "inverse_square" => Ok(Falloff::InverseSquare),
+            "linear" => Ok(Falloff::Linear),
+            "gaussian" => Ok(Falloff::Gaussian),
+            _ => Err(format!("unknown falloff `{}`", name)),
+        }
+    }
+    
+    // 距離distにいる個体の重み。距離0で1になり、遠いほど小さくなる
+    // 反比例の二つは距離0で無限大にならないようにFALLOFF_SOFTENINGだけずらす
+    pub fn weight(&self, dist: f64, radious: f64) -> f64 {
+        match *self {
+            Falloff::Constant => 1.0,
+            Falloff::Inverse => FALLOFF_SOFTENING / (FALLOFF_SOFTENING + dist),
+            Falloff::InverseSquare => (FALLOFF_SOFTENING / (FALLOFF_SOFTENING + dist)).powi(2),
+            Falloff::Linear => (1.0 - dist / radious).max(0.0),
+            Falloff::Gaussian => {
+                let sigma = radious / 2.0;
+                (-(dist * dist) / (2.0 * sigma * sigma)).exp()
+            },
+        }
+    }
+}
+
+// ベクトルと距離の組を重み付けして足し合わせる
+// Constantはベクトルの和を正規化し、それ以外は単位ベクトルに重みを掛けた和を長さ1までに切り詰める
+pub fn weighted_sum(vectors: Vec<(PVector, f64)>, falloff: Falloff, radious: f64) -> PVector {
+    let sum = vectors
+        .into_iter()
+        .filter(|&(ref vector, _)| vector.len() > 0.0)
+        .map(|(vector, dist)| {
+            if falloff == Falloff::Constant {
+                vector
+            } else {
+                vector.normalize().mult(falloff.weight(dist, radious))
+            }
+        })
+        .fold(PVector::zero(), |folded, vector| vector.add(folded));
+    if sum.len() == 0.0 {
+        PVector::zero()
+    } else if falloff == Falloff::Constant || sum.len() > 1.0 {
+        sum.normalize()
+    } else {
+        sum
+    }
+}
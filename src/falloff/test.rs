@@ -0,0 +1,133 @@
+#[cfg(test)]
+mod tests{
+    use consts::*;
+    use falloff::{Falloff, weighted_sum};
+    use pvector::PVector;
+    
+    macro_rules! assert_float{
+        (
+            $x: expr ,$y: expr
+        ) => {
+            {
+                assert!((($x - $y) / $x).abs() < 1.0e-9);
+            }
+        }
+    }
+    
+    const ALL: [Falloff; 5] = [
+        Falloff::Constant,
+        Falloff::Inverse,
+        Falloff::InverseSquare,
+        Falloff::Linear,
+        Falloff::Gaussian,
+    ];
+    
+    #[test]
+    fn parse_test(){
+        assert_eq!(Falloff::parse("constant"), Ok(Falloff::Constant));
+        assert_eq!(Falloff::parse("inverse"), Ok(Falloff::Inverse));
+        assert_eq!(Falloff::parse("inverse_square"), Ok(Falloff::InverseSquare));
+        assert_eq!(Falloff::parse("linear"), Ok(Falloff::Linear));
+        assert_eq!(Falloff::parse("gaussian"), Ok(Falloff::Gaussian));
+        assert!(Falloff::parse("cubic").is_err());
+    }
+    
+    #[test]
+    fn weight_at_zero_test(){
+        // どの減衰も距離0で1
+        for falloff in ALL.iter() {
+            assert_float!(1.0, falloff.weight(0.0, 10.0));
+        }
+    }
+    
+    #[test]
+    fn weight_monotone_test(){
+        // Constant以外は遠いほど小さくなる
+        for falloff in ALL.iter().skip(1) {
+            let mut last = falloff.weight(0.0, 10.0);
+            for n in 1..10 {
+                let weight = falloff.weight(n as f64, 10.0);
+                assert!(weight < last);
+                assert!(weight >= 0.0);
+                last = weight;
+            }
+        }
+    }
+    
+    #[test]
+    fn constant_weight_test(){
+        assert_eq!(1.0, Falloff::Constant.weight(9.9, 10.0));
+        assert_eq!(1.0, Falloff::Constant.weight(0.1, 10.0));
+    }
+    
+    #[test]
+    fn inverse_weight_test(){
+        assert_float!(0.5, Falloff::Inverse.weight(FALLOFF_SOFTENING, 10.0));
+        // 遠くでは距離に反比例する
+        let ratio = Falloff::Inverse.weight(1000.0, 10.0) / Falloff::Inverse.weight(2000.0, 10.0);
+        assert!((ratio - 2.0).abs() < 1.0e-2);
+        // 半径によらない
+        assert_eq!(Falloff::Inverse.weight(3.0, 10.0), Falloff::Inverse.weight(3.0, 100.0));
+    }
+    
+    #[test]
+    fn inverse_square_weight_test(){
+        assert_float!(0.25, Falloff::InverseSquare.weight(FALLOFF_SOFTENING, 10.0));
+        // 遠くでは距離の二乗に反比例する
+        let ratio = Falloff::InverseSquare.weight(1000.0, 10.0) / Falloff::InverseSquare.weight(2000.0, 10.0);
+        assert!((ratio - 4.0).abs() < 1.0e-2);
+    }
+    
+    #[test]
+    fn linear_weight_test(){
+        assert_float!(0.5, Falloff::Linear.weight(5.0, 10.0));
+        assert_float!(0.25, Falloff::Linear.weight(7.5, 10.0));
+        assert_eq!(0.0, Falloff::Linear.weight(10.0, 10.0));
+        assert_eq!(0.0, Falloff::Linear.weight(20.0, 10.0));
+        // 等間隔で同じだけ減る
+        let d1 = Falloff::Linear.weight(1.0, 10.0) - Falloff::Linear.weight(2.0, 10.0);
+        let d2 = Falloff::Linear.weight(6.0, 10.0) - Falloff::Linear.weight(7.0, 10.0);
+        assert_float!(d1, d2);
+    }
+    
+    #[test]
+    fn gaussian_weight_test(){
+        // 半径の半分が標準偏差
+        assert_float!((-0.5_f64).exp(), Falloff::Gaussian.weight(5.0, 10.0));
+        assert_float!((-2.0_f64).exp(), Falloff::Gaussian.weight(10.0, 10.0));
+        // 原点の近くでは平ら
+        assert!(1.0 - Falloff::Gaussian.weight(0.1, 10.0) < 1.0e-3);
+    }
+    
+    #[test]
+    fn weighted_sum_test(){
+        let near = (PVector::new(0.1, 0.0), 0.1);
+        let far = (PVector::new(9.9, 0.0), 9.9);
+        
+        // 従来通りの場合は近くても遠くても同じ
+        let near_constant = weighted_sum(vec![near.clone()], Falloff::Constant, 10.0);
+        let far_constant = weighted_sum(vec![far.clone()], Falloff::Constant, 10.0);
+        assert_float!(near_constant.len(), far_constant.len());
+        
+        // 減衰させると近い方が強い
+        for falloff in ALL.iter().skip(1) {
+            let near_force = weighted_sum(vec![near.clone()], *falloff, 10.0);
+            let far_force = weighted_sum(vec![far.clone()], *falloff, 10.0);
+            assert!(near_force.len() > far_force.len());
+            assert!(near_force.x > 0.0 && far_force.x >= 0.0);
+            assert!(near_force.len() <= 1.0);
+        }
+        
+        // 重みの和が1を超えたら切り詰める
+        let many = vec![near.clone(); 10];
+        assert_float!(1.0, weighted_sum(many, Falloff::Linear, 10.0).len());
+        
+        // 長さのないベクトルは方向がないので無視する
+        assert_eq!(PVector::zero(), weighted_sum(vec![(PVector::zero(), 0.0)], Falloff::Linear, 10.0));
+        
+        // 近い方の向きに揃う
+        let result = weighted_sum(vec![(PVector::new(0.0, 2.0), 1.0), (PVector::new(0.0, -2.0), 9.0)], Falloff::Linear, 10.0);
+        assert_eq!(0.0, result.x);
+        assert_float!(0.8, result.y);
+    }
+}
@@ -0,0 +1,75 @@
+mod test;
+
+use animal::{Animal, Lineage, Species};
+use std::io;
+use std::io::Write;
+
+// 家系図の辺の一覧の見出し。親がいなければnone、交叉で生まれていなければmateはnone
+pub const HEADER: &str = "id,parent,species,generation,born,mate";
+
+// 家系図に載せる一匹分
+#[derive(Debug, Clone, PartialEq)]
+pub struct Member {
+    pub species: Species,
+    pub id: u64,
+    pub lineage: Lineage,
+}
+
+impl Member {
+    // 個体の種、id、系統を写す
+    pub fn of<A: Animal>(animal: &A) -> Member {
+        Member {
+            species: animal.species(),
+            id: animal.id(),
+            lineage: animal.lineage(),
+        }
+    }
+}
+
+// 「子,親,種,世代,生まれたステップ,もう一方の親」の行で書き出す
+pub fn write_edges<W: Write>(out: &mut W, members: &[Member]) -> io::Result<()> {
+    let id = |id: Option<u64>| id.map_or(String::from("none"), |id| id.to_string());
+    for member in members {
+        writeln!(
+            out,
+            "{},{},{},{},{},{}",
+            member.id,
+            id(member.lineage.parent),
+            member.species.name(),
+            member.lineage.generation,
+            member.lineage.born,
+            id(member.lineage.mate)
+        )?;
+    }
+    Ok(())
+}
+
+// GraphVizのDOT形式で書き出す。猫は四角、ねずみは丸で、親から子へ矢印を引く。交叉のもう一方の親からは点線
+pub fn write_dot<W: Write>(out: &mut W, members: &[Member]) -> io::Result<()> {
+    writeln!(out, "digraph family {{")?;
+    for member in members {
+        let shape = match member.species {
+            Species::Cat => "box",
+            Species::Rat => "ellipse",
+        };
+        writeln!(
+            out,
+            "    n{} [label=\"{} {}\\ngen {} step {}\", shape={}];",
+            member.id,
+            member.species.name(),
+            member.id,
+            member.lineage.generation,
+            member.lineage.born,
+            shape
+        )?;
+    }
+    for member in members {
+        if let Some(parent) = member.lineage.parent {
+            writeln!(out, "    n{} -> n{};", parent, member.id)?;
+        }
+        if let Some(mate) = member.lineage.mate {
+            writeln!(out, "    n{} -> n{} [style=dashed];", mate, member.id)?;
+        }
+    }
+    writeln!(out, "}}")
+}
@@ -0,0 +1,42 @@
+#[cfg(test)]
+mod tests{
+    use animal::{Animal, Cat, Lineage, Rat, Species};
+    use family::{Member, write_dot, write_edges};
+
+    #[test]
+    fn member_test(){
+        let cat = Cat::new();
+        let member = Member::of(&cat.descendant());
+        assert_eq!(Species::Cat, member.species);
+        assert_eq!(Some(cat.id()), member.lineage.parent);
+        assert_eq!(1, member.lineage.generation);
+        assert_eq!(Species::Rat, Member::of(&Rat::new()).species);
+    }
+
+    #[test]
+    fn write_test(){
+        let parent = Cat::new();
+        let mate = Cat::new();
+        let child = parent.descendant();
+        let crossed = parent.descendant();
+        let crossed = crossed.set_lineage(&Lineage { mate: Some(mate.id()), ..crossed.lineage() });
+        let members = vec![Member::of(&parent), Member::of(&child), Member::of(&crossed)];
+
+        let mut out: Vec<u8> = Vec::new();
+        write_edges(&mut out, &members).unwrap();
+        let text = String::from_utf8(out).unwrap();
+        let lines: Vec<&str> = text.lines().collect();
+        assert_eq!(format!("{},none,cat,1,0,none", parent.id()), lines[0]);
+        assert_eq!(format!("{},{},cat,1,0,none", child.id(), parent.id()), lines[1]);
+        assert_eq!(format!("{},{},cat,1,0,{}", crossed.id(), parent.id(), mate.id()), lines[2]);
+
+        let mut out: Vec<u8> = Vec::new();
+        write_dot(&mut out, &members).unwrap();
+        let text = String::from_utf8(out).unwrap();
+        assert!(text.starts_with("digraph family {"));
+        assert!(text.trim_end().ends_with("}"));
+        assert!(text.contains(&format!("n{} -> n{};", parent.id(), child.id())));
+        assert!(text.contains(&format!("n{} -> n{} [style=dashed];", mate.id(), crossed.id())));
+        assert_eq!(3, text.matches("->").count());
+    }
+}
@@ -0,0 +1,233 @@
+mod test;
+
+use animal::{Animal, Cat, age_seconds};
+use config;
+use config::parse_f64;
+use pareto;
+use pareto::Selection;
+
+// 猫の選択に使う適応度。catsは同じ世代の猫全体
+pub trait Fitness {
+    fn score(&self, cat: &Cat, cats: &Vec<Cat>) -> f64;
+}
+
+// 食べたねずみの数
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Kills;
+
+// 使ったエネルギーあたりの食べた数。使ったエネルギーは1を下限にする
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct KillsPerEnergy;
+
+// 1秒生きるあたりの食べた数
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct KillRate;
+
+// 生きた秒数。死んだ猫は死ぬ直前までの秒数
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Survival;
+
+// 世代の終わり、死んだ猫は死ぬ直前に残っていたエネルギー
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FinalEnergy;
+
+// 群れのまとまり。ほかの猫までの平均距離にマイナスをつけたもの
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Cohesion;
+
+// 設定ファイルで選べる適応度
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Measure {
+    Kills(Kills),
+    KillsPerEnergy(KillsPerEnergy),
+    KillRate(KillRate),
+    Survival(Survival),
+    FinalEnergy(FinalEnergy),
+    Cohesion(Cohesion),
+}
+
+// 選択に使う一匹分の値。重みつきの適応度と、paretoで使う目的ごとの値
+#[derive(Debug, Clone, PartialEq)]
+pub struct Score {
+    pub fitness: f64,
+    pub objectives: Vec<f64>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct WeightedFitness {
+    pub weight: f64,
+    pub measure: Measure,
+}
+
+impl Fitness for Kills {
+    fn score(&self, cat: &Cat, _cats: &Vec<Cat>) -> f64 {
+        cat.ate() as f64
+    }
+}
+
+impl Fitness for KillsPerEnergy {
+    fn score(&self, cat: &Cat, _cats: &Vec<Cat>) -> f64 {
+        cat.ate() as f64 / cat.spent().max(1.0)
+    }
+}
+
+impl Fitness for KillRate {
+    fn score(&self, cat: &Cat, _cats: &Vec<Cat>) -> f64 {
+        let age = age_seconds(cat);
+        if age > 0.0 { cat.ate() as f64 / age } else { 0.0 }
+    }
+}
+
+impl Fitness for Survival {
+    fn score(&self, cat: &Cat, _cats: &Vec<Cat>) -> f64 {
+        age_seconds(cat)
+    }
+}
+
+impl Fitness for FinalEnergy {
+    fn score(&self, cat: &Cat, _cats: &Vec<Cat>) -> f64 {
+        cat.energy()
+    }
+}
+
+impl Fitness for Cohesion {
+    fn score(&self, cat: &Cat, cats: &Vec<Cat>) -> f64 {
+        let boundary = config::get().boundary;
+        let distances: Vec<f64> = cats
+            .iter()
+            .filter(|other| !cat.is_same(*other))
+            .map(|other| cat.offset(other, boundary).len())
+            .collect();
+        if distances.is_empty() {
+            return 0.0;
+        }
+        -distances.iter().sum::<f64>() / distances.len() as f64
+    }
+}
+
+// 重みをかけて足し合わせたもの
+impl Fitness for Vec<WeightedFitness> {
+    fn score(&self, cat: &Cat, cats: &Vec<Cat>) -> f64 {
+        self
+            .iter()
+            .map(|weighted| weighted.weight * weighted.measure.fitness().score(cat, cats))
+            .sum()
+    }
+}
+
+impl Measure {
+    // トレイトオブジェクトとして取り出す
+    pub fn fitness(&self) -> &dyn Fitness {
+        match *self {
+            Measure::Kills(ref measure) => measure,
+            Measure::KillsPerEnergy(ref measure) => measure,
+            Measure::KillRate(ref measure) => measure,
+            Measure::Survival(ref measure) => measure,
+            Measure::FinalEnergy(ref measure) => measure,
+            Measure::Cohesion(ref measure) => measure,
+        }
+    }
+    
+    // 設定ファイルや出力で使う名前
+    pub fn name(&self) -> &'static str {
+        match *self {
+            Measure::Kills(_) => "kills",
+            Measure::KillsPerEnergy(_) => "kills_per_energy",
+            Measure::KillRate(_) => "kill_rate",
+            Measure::Survival(_) => "survival",
+            Measure::FinalEnergy(_) => "final_energy",
+            Measure::Cohesion(_) => "cohesion",
+        }
+    }
+
+    // kills、kills_per_energy、kill_rate、survival、final_energy、cohesionのどれかを読む
+    pub fn parse(text: &str) -> Result<Measure, String> {
+        match text.trim() {
+            "kills" => Ok(Measure::Kills(Kills)),
+            "kills_per_energy" => Ok(Measure::KillsPerEnergy(KillsPerEnergy)),
+            "kill_rate" => Ok(Measure::KillRate(KillRate)),
+            "survival" => Ok(Measure::Survival(Survival)),
+            "final_energy" => Ok(Measure::FinalEnergy(FinalEnergy)),
+            "cohesion" => Ok(Measure::Cohesion(Cohesion)),
+            _ => Err(format!("unknown fitness `{}`", text.trim())),
+        }
+    }
+}
+
+impl WeightedFitness {
+    // 「重み 名前」の形式を読む
+    pub fn parse(text: &str) -> Result<WeightedFitness, String> {
+        let text = text.trim();
+        let split = text.find(char::is_whitespace).unwrap_or(text.len());
+        Ok(WeightedFitness {
+            weight: parse_f64(&text[..split])?,
+            measure: Measure::parse(&text[split..])?,
+        })
+    }
+
+    // 「;」で区切られた一覧を読む
+    pub fn parse_list(text: &str) -> Result<Vec<WeightedFitness>, String> {
+        let list = text
+            .split(';')
+            .filter(|item| !item.trim().is_empty())
+            .map(WeightedFitness::parse)
+            .collect::<Result<Vec<WeightedFitness>, String>>()?;
+        if list.is_empty() {
+            return Err(String::from("no fitness"));
+        }
+        Ok(list)
+    }
+}
+
+// 既定の適応度。以前と同じく食べた数だけで選ぶ
+pub fn default_fitness() -> Vec<WeightedFitness> {
+    vec![WeightedFitness { weight: 1.0, measure: Measure::Kills(Kills) }]
+}
+
+// 既定の多目的。狩りの成功、エネルギー効率、群れのまとまり
+pub fn default_objectives() -> Vec<Measure> {
+    vec![
+        Measure::Kills(Kills),
+        Measure::KillsPerEnergy(KillsPerEnergy),
+        Measure::Cohesion(Cohesion),
+    ]
+}
+
+// 設定の適応度と目的で猫ごとの値を求める
+pub fn score_all(cats: &Vec<Cat>) -> Vec<Score> {
+    cats.iter().map(|cat| score(cat, cats)).collect()
+}
+
+// 設定の適応度と目的で一匹分の値を求める。catsは同じ時点の猫全体
+pub fn score(cat: &Cat, cats: &Vec<Cat>) -> Score {
+    let config = config::get();
+    Score {
+        fitness: config.fitness.score(cat, cats),
+        objectives: config.objectives.iter().map(|measure| measure.fitness().score(cat, cats)).collect(),
+    }
+}
+
+impl Score {
+    // 何度か測った値の平均。なければ0
+    pub fn mean(scores: &[Score]) -> Score {
+        let objectives = config::get().objectives.len();
+        let count = scores.len().max(1) as f64;
+        Score {
+            fitness: scores.iter().map(|score| score.fitness).sum::<f64>() / count,
+            objectives: (0..objectives)
+                .map(|m| scores.iter().map(|score| score.objectives[m]).sum::<f64>() / count)
+                .collect(),
+        }
+    }
+}
+
+// 良い順に並べた添字。paretoならフロントの順、同じフロントの中は混雑距離の順
+pub fn rank(scores: &Vec<Score>) -> Vec<usize> {
+    if config::get().selection == Selection::Pareto {
+        let objectives: Vec<Vec<f64>> = scores.iter().map(|score| score.objectives.clone()).collect();
+        return pareto::select(&objectives, scores.len());
+    }
+    let mut ret: Vec<usize> = (0..scores.len()).collect();
+    ret.sort_by(|&a, &b| scores[b].fitness.partial_cmp(&scores[a].fitness).unwrap_or(std::cmp::Ordering::Equal));
+    ret
+}
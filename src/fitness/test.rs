@@ -0,0 +1,104 @@
+#[cfg(test)]
+mod tests{
+    use animal::{Animal, Cat};
+    use config;
+    use config::Config;
+    use fitness::*;
+    use pareto::Selection;
+    use pvector::PVector;
+
+    macro_rules! assert_float{
+        (
+            $x: expr ,$y: expr
+        ) => {
+            {
+                assert!(($x - $y).abs() < 1.0e-9);
+            }
+        }
+    }
+
+    // 2秒生きて3匹食べ、エネルギーを150使った猫
+    fn hunter() -> Cat {
+        let mut cat = Cat::new().feed(0.0, 3).set_energy(500.0);
+        cat = cat.move_self().move_self();
+        cat.tire(150.0 - cat.spent())
+    }
+
+    #[test]
+    fn measure_test(){
+        config::set(Config { dt: 1.0, ..Config::default() });
+        let cat = hunter();
+        assert_float!(3.0, Kills.score(&cat, &vec![]));
+        assert_float!(3.0 / 150.0, KillsPerEnergy.score(&cat, &vec![]));
+        assert_float!(1.5, KillRate.score(&cat, &vec![]));
+        assert_float!(2.0, Survival.score(&cat, &vec![]));
+        assert_float!(cat.energy(), FinalEnergy.score(&cat, &vec![]));
+        // 何もしていない猫で0で割らない
+        let newborn = Cat::new();
+        assert_float!(0.0, KillsPerEnergy.score(&newborn, &vec![]));
+        assert_float!(0.0, KillRate.score(&newborn, &vec![]));
+        config::set(Config::default());
+    }
+
+    #[test]
+    fn combination_test(){
+        config::set(Config { dt: 1.0, ..Config::default() });
+        let cat = hunter();
+        let combined = WeightedFitness::parse_list("1 kills; 0.5 survival").unwrap();
+        assert_float!(3.0 + 0.5 * 2.0, combined.score(&cat, &vec![]));
+        assert_float!(3.0, default_fitness().score(&cat, &vec![]));
+        config::set(Config::default());
+    }
+
+    #[test]
+    fn parse_test(){
+        assert_eq!(Ok(WeightedFitness { weight: 2.0, measure: Measure::KillRate(KillRate) }), WeightedFitness::parse("2 kill_rate"));
+        assert_eq!(2, WeightedFitness::parse_list("1 kills;-0.1 final_energy;").unwrap().len());
+        assert!(WeightedFitness::parse("kills").is_err());
+        assert!(WeightedFitness::parse("1 speed").is_err());
+        assert!(WeightedFitness::parse_list(" ; ").is_err());
+    }
+
+    #[test]
+    fn score_all_test(){
+        // 群れのまとまりはほかの猫までの平均距離の符号を変えたもの
+        let cats = vec![
+            Cat::new().set_position(&PVector::new(100.5, 100.5)).feed(0.0, 2),
+            Cat::new().set_position(&PVector::new(110.5, 100.5)),
+        ];
+        let scores = score_all(&cats);
+        assert_float!(2.0, scores[0].fitness);
+        assert_eq!(vec![Measure::Kills(Kills), Measure::KillsPerEnergy(KillsPerEnergy), Measure::Cohesion(Cohesion)], config::get().objectives);
+        assert_float!(2.0, scores[0].objectives[0]);
+        assert_float!(-10.0, scores[0].objectives[2]);
+        // 平均は目的ごとにとる
+        let mean = Score::mean(&scores);
+        assert_float!(1.0, mean.fitness);
+        assert_float!(-10.0, mean.objectives[2]);
+        assert_float!(0.0, Score::mean(&[]).fitness);
+    }
+
+    #[test]
+    fn rank_test(){
+        config::set(Config {
+            selection: Selection::Pareto,
+            ..Config::default()
+        });
+        let score = |objectives: Vec<f64>| Score { fitness: 0.0, objectives };
+        let scores = vec![
+            score(vec![1.0, 3.0]),
+            score(vec![1.5, 2.5]),
+            score(vec![0.0, 4.0]),
+            score(vec![0.5, 0.5]),
+            score(vec![4.0, 0.0]),
+        ];
+        // 第1フロントが入りきらなくても先頭から切れば端の個体が残る
+        let order = rank(&scores);
+        assert_eq!(5, order.len());
+        let mut top: Vec<usize> = order.iter().take(2).cloned().collect();
+        top.sort();
+        assert_eq!(vec![2, 4], top);
+        assert_eq!(3, order[4]);
+        config::set(Config::default());
+    }
+}
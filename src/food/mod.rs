@@ -0,0 +1,66 @@
+mod test;
+
+use config;
+use consts::*;
+use pvector::PVector;
+
+// CELL_WIDTH四方のマスごとに生える草。ねずみが食べると減り、時間とともに生え直す
+#[derive(Debug, Clone)]
+pub struct Food {
+    pub cells: Vec<f64>,
+    pub columns: usize,
+    pub rows: usize,
+}
+
+impl Food {
+    // どのマスも草が最大まで生えた状態で始める
+    pub fn new() -> Food {
+        let columns = (WIDTH / CELL_WIDTH).ceil() as usize;
+        let rows = (HEIGHT / CELL_WIDTH).ceil() as usize;
+        Food {
+            cells: vec![config::get().food_capacity; columns * rows],
+            columns, rows,
+        }
+    }
+    
+    // 位置のあるマスの番号
+    fn index(&self, position: &PVector) -> usize {
+        let column = ((position.x / CELL_WIDTH).floor().max(0.0) as usize).min(self.columns - 1);
+        let row = ((position.y / CELL_WIDTH).floor().max(0.0) as usize).min(self.rows - 1);
+        row * self.columns + column
+    }
+    
+    // 位置のマスにある草の量
+    pub fn amount(&self, position: &PVector) -> f64 {
+        self.cells[self.index(position)]
+    }
+    
+    // 位置のマスからbiteまで食べ、実際に食べた量を返す
+    pub fn graze(&mut self, position: &PVector, bite: f64) -> f64 {
+        let index = self.index(position);
+        let eaten = self.cells[index].min(bite).max(0.0);
+        self.cells[index] -= eaten;
+        eaten
+    }
+    
+    // dt秒分だけ生え直す。最大量は超えない
+    pub fn regrow(&mut self, dt: f64) {
+        let config = config::get();
+        for cell in &mut self.cells {
+            *cell = (*cell + config.food_regrowth * dt).min(config.food_capacity);
+        }
+    }
+    
+    // 全体の草の量
+    pub fn total(&self) -> f64 {
+        self.cells.iter().sum()
+    }
+    
+    // マスの左上の位置
+    pub fn cell_position(&self, index: usize) -> PVector {
+        PVector::new(
+            (index % self.columns) as f64 * CELL_WIDTH,
+            (index / self.columns) as f64 * CELL_WIDTH,
+        )
+    }
+}
@@ -0,0 +1,57 @@
+#[cfg(test)]
+mod tests{
+    use config;
+    use config::Config;
+    use consts::*;
+    use food::Food;
+    use pvector::PVector;
+    
+    #[test]
+    fn new_test(){
+        let food = Food::new();
+        assert_eq!(10, food.columns);
+        assert_eq!(8, food.rows);
+        assert_eq!(FOOD_CAPACITY, food.amount(&PVector::new(1.0, 1.0)));
+        assert_eq!(FOOD_CAPACITY * 80.0, food.total());
+    }
+    
+    #[test]
+    fn graze_test(){
+        let mut food = Food::new();
+        let position = PVector::new(70.0, 130.0);
+        assert_eq!(50.0, food.graze(&position, 50.0));
+        assert_eq!(FOOD_CAPACITY - 50.0, food.amount(&position));
+        // 同じマスなら同じ草を食べる
+        assert_eq!(FOOD_CAPACITY - 50.0, food.amount(&PVector::new(127.0, 150.0)));
+        assert_eq!(FOOD_CAPACITY, food.amount(&PVector::new(130.0, 150.0)));
+        
+        // 残っている分しか食べられない
+        assert_eq!(FOOD_CAPACITY - 50.0, food.graze(&position, FOOD_CAPACITY * 2.0));
+        assert_eq!(0.0, food.graze(&position, 10.0));
+        
+        // 画面の右下の端も一番端のマスになる
+        assert_eq!(10.0, food.graze(&PVector::new(WIDTH, HEIGHT), 10.0));
+        assert_eq!(FOOD_CAPACITY - 10.0, food.cells[79]);
+    }
+    
+    #[test]
+    fn regrow_test(){
+        config::set(Config { food_capacity: 100.0, food_regrowth: 10.0, ..Config::default() });
+        let mut food = Food::new();
+        let position = PVector::new(300.0, 300.0);
+        food.graze(&position, 100.0);
+        food.regrow(2.0);
+        assert_eq!(20.0, food.amount(&position));
+        // 最大量より多くは生えない
+        food.regrow(100.0);
+        assert_eq!(100.0, food.amount(&position));
+        config::set(Config::default());
+    }
+    
+    #[test]
+    fn cell_position_test(){
+        let food = Food::new();
+        assert_eq!(PVector::new(0.0, 0.0), food.cell_position(0));
+        assert_eq!(PVector::new(3.0 * CELL_WIDTH, 2.0 * CELL_WIDTH), food.cell_position(23));
+    }
+}
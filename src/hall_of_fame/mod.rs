@@ -0,0 +1,139 @@
+mod test;
+
+use animal::{Animal, Cat, Lineage};
+use config::parse_f64;
+use fitness;
+use fitness::Score;
+use neural::Brain;
+use scenario::Scenario;
+use std::fs::File;
+use std::io::Read;
+use vision::Vision;
+use world::World;
+
+// 殿堂入りの記録の見出し。角度は度で書く。brainは操舵のネットワークで、なければnone
+pub const HEADER: &str = "gen,id,parent,fitness,chase,separate,align,cohension,lead_time,view_angle,blind_spot,brain";
+
+// ある世代で上位だった猫と、その時の適応度
+#[derive(Debug, Clone)]
+pub struct Champion {
+    pub generation: i32,
+    pub fitness: f64,
+    pub cat: Cat,
+}
+
+// 読み直した殿堂入りの猫を誰と戦わせるか
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Versus {
+    Champions,  // 殿堂入りの猫どうしで一度だけ狩らせる
+    Population, // 最初の世代に加えて、進化する集団と競わせる
+}
+
+impl Versus {
+    // コマンドラインの名前から読む
+    pub fn parse(text: &str) -> Result<Versus, String> {
+        match text {
+            "champions" => Ok(Versus::Champions),
+            "population" => Ok(Versus::Population),
+            _ => Err(format!("unknown opponent `{}`", text)),
+        }
+    }
+}
+
+impl Champion {
+    // 見出しと同じ順に並べた一行
+    pub fn row(&self) -> String {
+        let cat = &self.cat;
+        format!(
+            "{},{},{},{},{},{},{},{},{},{},{},{}",
+            self.generation,
+            cat.id(),
+            cat.lineage().parent.map_or(String::from("none"), |parent| parent.to_string()),
+            self.fitness,
+            cat.chase_weight,
+            cat.separate_weight,
+            cat.align_weight,
+            cat.cohension_weight,
+            cat.lead_time,
+            cat.vision.view_angle.to_degrees(),
+            cat.vision.blind_spot.to_degrees(),
+            cat.brain.as_ref().map_or(String::from("none"), |brain| brain.to_text()),
+        )
+    }
+
+    // rowで書いた一行を読む
+    pub fn parse(line: &str) -> Result<Champion, String> {
+        let fields: Vec<&str> = line.split(',').map(|field| field.trim()).collect();
+        if fields.len() != HEADER.split(',').count() {
+            return Err(format!("expected {} fields in `{}`", HEADER.split(',').count(), line));
+        }
+        let id = |text: &str| text.parse::<u64>().map_err(|_| format!("`{}` is not an id", text));
+        let parent = match fields[2] {
+            "none" => None,
+            text => Some(id(text)?),
+        };
+        let generation = fields[0].parse::<i32>().map_err(|_| format!("`{}` is not a generation", fields[0]))?;
+        let lineage = Lineage { parent, mate: None, born: 0, generation: generation.max(1) as u32 };
+        let mut cat = Cat::new().with_id(id(fields[1])?).set_lineage(&lineage);
+        cat.chase_weight = parse_f64(fields[4])?;
+        cat.separate_weight = parse_f64(fields[5])?;
+        cat.align_weight = parse_f64(fields[6])?;
+        cat.cohension_weight = parse_f64(fields[7])?;
+        cat.lead_time = parse_f64(fields[8])?;
+        cat.vision = Vision {
+            view_angle: parse_f64(fields[9])?.to_radians(),
+            blind_spot: parse_f64(fields[10])?.to_radians(),
+        };
+        cat.brain = match fields[11] {
+            "none" => None,
+            text => Some(Brain::parse(text)?),
+        };
+        if let Some(ref brain) = cat.brain {
+            brain.check()?;
+        }
+        Ok(Champion {
+            generation,
+            fitness: parse_f64(fields[3])?,
+            cat,
+        })
+    }
+}
+
+// 世代の中で上位count匹を殿堂入りさせる
+pub fn induct(generation: i32, cats: &Vec<Cat>, scores: &Vec<Score>, count: usize) -> Vec<Champion> {
+    fitness::rank(scores)
+        .into_iter()
+        .take(count)
+        .map(|n| Champion { generation, fitness: scores[n].fitness, cat: cats[n].clone() })
+        .collect()
+}
+
+// 書き出した殿堂入りの記録を読む。見出しの行は飛ばす
+pub fn load(path: &str) -> Result<Vec<Champion>, String> {
+    let mut text = String::new();
+    File::open(path)
+        .and_then(|mut file| file.read_to_string(&mut text))
+        .map_err(|e| format!("{}: {}", path, e))?;
+    text
+        .lines()
+        .enumerate()
+        .filter(|&(_, line)| !line.trim().is_empty() && line.trim() != HEADER)
+        .map(|(n, line)| Champion::parse(line).map_err(|e| format!("{}: line {}: {}", path, n + 1, e)))
+        .collect()
+}
+
+// 殿堂入りの猫と同じ遺伝子の猫を同じ場所で世代が終わるまで狩らせる
+// 猫ごとの値を返す。途中で死んだ猫は死ぬ直前に測った値
+pub fn tournament(champions: &Vec<Champion>, scenario: &Scenario) -> Vec<Score> {
+    let entrants: Vec<Cat> = champions.iter().map(|champion| champion.cat.twin()).collect();
+    let cats = scenario.cats.place(&entrants);
+    let ids: Vec<u64> = cats.iter().map(|cat| cat.id()).collect();
+    let mut world = World::with_animals(cats, scenario.rats.spawn());
+    world.run();
+    // 猫は生き残るかfallenに残るので、どの猫も候補の中に見つかる
+    let (cats, scores) = world.candidates();
+    ids
+        .iter()
+        .map(|&id| scores[cats.iter().position(|cat| cat.id() == id).unwrap()].clone())
+        .collect()
+}
@@ -0,0 +1,103 @@
+#[cfg(test)]
+mod tests{
+    use animal::{Animal, Cat};
+    use config;
+    use config::Config;
+    use fitness::Score;
+    use hall_of_fame::{Champion, HEADER, Versus, induct, load, tournament};
+    use consts::*;
+    use neural::{Brain, Network, inputs};
+    use scenario::Scenario;
+    use std::fs::File;
+    use std::io::Write;
+    use termination::Condition;
+
+    fn score(fitness: f64) -> Score {
+        Score { fitness, objectives: Vec::new() }
+    }
+
+    #[test]
+    fn parse_test(){
+        assert_eq!(Ok(Versus::Population), Versus::parse("population"));
+        assert!(Versus::parse("everyone").is_err());
+
+        // 書いた行を読み直すと遺伝子と系統が戻る
+        let mut cat = Cat::new().descendant();
+        cat.brain = Some(Brain::Network(Network::random(inputs(NEURAL_NEAREST), 2, 2)));
+        let champion = Champion { generation: 4, fitness: 2.5, cat: cat.clone() };
+        let read = Champion::parse(&champion.row()).unwrap();
+        assert_eq!(4, read.generation);
+        assert_eq!(2.5, read.fitness);
+        assert_eq!(cat.id(), read.cat.id());
+        assert_eq!(cat.lineage().parent, read.cat.lineage().parent);
+        assert!((cat.chase_weight - read.cat.chase_weight).abs() < 1.0e-9);
+        assert!((cat.lead_time - read.cat.lead_time).abs() < 1.0e-9);
+        assert!((cat.vision.view_angle - read.cat.vision.view_angle).abs() < 1.0e-9);
+        assert_eq!(cat.brain, read.cat.brain);
+        assert!(Champion::parse("1,2,none,0.5").is_err());
+        assert!(Champion::parse(&Champion { generation: 1, fitness: 0.0, cat: Cat::new() }.row().replace(",none,", ",x,")).is_err());
+        // 今の設定と入力や出力の数が合わないネットワークは読まない
+        for &(inputs, outputs) in &[(3, 2), (inputs(NEURAL_NEAREST), 1)] {
+            cat.brain = Some(Brain::Network(Network::random(inputs, 2, outputs)));
+            let champion = Champion { generation: 1, fitness: 0.0, cat: cat.clone() };
+            assert!(Champion::parse(&champion.row()).is_err());
+        }
+    }
+
+    #[test]
+    fn induct_test(){
+        let cats: Vec<Cat> = (0..4).map(|_| Cat::new()).collect();
+        let scores = vec![score(1.0), score(3.0), score(0.0), score(2.0)];
+        let champions = induct(7, &cats, &scores, 2);
+        assert_eq!(2, champions.len());
+        assert_eq!(cats[1].id(), champions[0].cat.id());
+        assert_eq!(cats[3].id(), champions[1].cat.id());
+        assert_eq!(3.0, champions[0].fitness);
+        assert!(champions.iter().all(|champion| champion.generation == 7));
+    }
+
+    #[test]
+    fn load_test(){
+        let path = std::env::temp_dir().join("hall_of_fame_load_test.csv");
+        let path = path.to_str().unwrap();
+        let champions = induct(1, &vec![Cat::new(), Cat::new()], &vec![score(1.0), score(2.0)], 2);
+        {
+            let mut file = File::create(path).unwrap();
+            writeln!(file, "{}", HEADER).unwrap();
+            for champion in &champions {
+                writeln!(file, "{}", champion.row()).unwrap();
+            }
+        }
+        let loaded = load(path).unwrap();
+        assert_eq!(2, loaded.len());
+        assert_eq!(champions[0].cat.id(), loaded[0].cat.id());
+
+        // 壊れた行は行番号つきのエラー
+        writeln!(File::create(path).unwrap(), "{}\n1,2,3", HEADER).unwrap();
+        assert!(load(path).unwrap_err().contains("line 2"));
+        std::fs::remove_file(path).unwrap();
+        assert!(load(path).is_err());
+    }
+
+    #[test]
+    fn tournament_test(){
+        let mut config = Config::default();
+        config.termination.conditions = vec![Condition::Frames(3)];
+        config::set(config);
+        let mut scenario = Scenario::default();
+        scenario.rats.count = 10;
+        let champions = induct(1, &vec![Cat::new(), Cat::new(), Cat::new()], &vec![score(1.0), score(2.0), score(3.0)], 3);
+        let results = tournament(&champions, &scenario);
+        assert_eq!(3, results.len());
+
+        // 途中で寿命を迎えた猫にも死ぬ直前の値がつく
+        let mut config = Config::default();
+        config.termination.conditions = vec![Condition::Frames(5)];
+        config.cat.life.lifespan = Some(config.dt * 2.5);
+        config::set(config);
+        let results = tournament(&champions, &scenario);
+        assert_eq!(3, results.len());
+        assert!(results.iter().all(|result| result.fitness.is_finite()));
+        config::set(Config::default());
+    }
+}
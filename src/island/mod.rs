@@ -0,0 +1,143 @@
+mod test;
+
+use animal::Cat;
+use config;
+use config::Config;
+use evaluation::Evaluation;
+use fitness::Score;
+use rand::SeedableRng;
+use rand::rngs::StdRng;
+use random;
+use scenario::Scenario;
+use world::World;
+
+// 島どうしのつながり方
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Topology {
+    Ring, // 次の島へだけ移る
+    Full, // ほかの全ての島へ移る
+}
+
+// 別々に進化させる集団。島ごとに設定と乱数を持つ
+pub struct Island {
+    pub config: Config,
+    pub world: World,
+    rng: StdRng,
+}
+
+// 島の集まり
+pub struct Archipelago {
+    pub islands: Vec<Island>,
+}
+
+impl Topology {
+    // 設定ファイルの名前から読む
+    pub fn parse(text: &str) -> Result<Topology, String> {
+        match text {
+            "ring" => Ok(Topology::Ring),
+            "full" => Ok(Topology::Full),
+            _ => Err(format!("unknown topology `{}`", text)),
+        }
+    }
+
+    // from番目の島から移る先の島
+    pub fn destinations(&self, from: usize, count: usize) -> Vec<usize> {
+        match *self {
+            Topology::Ring if count > 1 => vec![(from + 1) % count],
+            Topology::Ring => Vec::new(),
+            Topology::Full => (0..count).filter(|&to| to != from).collect(),
+        }
+    }
+}
+
+impl Archipelago {
+    // 今の設定にisland.番号.キーの上書きを重ねて島を作る
+    // island.番号.seedがあればそのシードで、なければ全体の乱数から分けた乱数で島を進める
+    pub fn new(scenario: &Scenario) -> Result<Archipelago, String> {
+        let base = (*config::get()).clone();
+        let islands = &base.islands;
+        if let Some(&(n, _, _)) = islands.overrides.iter().find(|&&(n, _, _)| n >= islands.count) {
+            return Err(format!("island {} is out of {} islands", n, islands.count));
+        }
+        let mut ret = Vec::with_capacity(islands.count);
+        for n in 0..islands.count {
+            let mut config = base.clone();
+            for &(_, ref key, ref value) in islands.overrides.iter().filter(|&&(island, _, _)| island == n) {
+                config.set(key, value).map_err(|e| format!("island {}: {}", n, e))?;
+            }
+            let mut rng = match config.seed {
+                Some(seed) if config.seed != base.seed => StdRng::seed_from_u64(seed),
+                _ => random::fork(),
+            };
+            config::set(config.clone());
+            random::swap(&mut rng);
+            let world = World::new(scenario.clone());
+            random::swap(&mut rng);
+            ret.push(Island { config, world, rng });
+        }
+        config::set(base);
+        Ok(Archipelago { islands: ret })
+    }
+
+    // 全ての島で一世代を終えるまで進める
+    pub fn run(&mut self) {
+        self.each(|world| {
+            if config::get().evaluation.mode == Evaluation::Isolated {
+                world.evaluate();
+            } else {
+                world.run();
+            }
+        });
+    }
+
+    // 島ごとの統計を「島,世代,...」の行で標準出力へ
+    pub fn print_params(&mut self, ord: i32) {
+        let mut n = 0;
+        self.each(|world| {
+            print!("{},", n);
+            world.print_params(ord);
+            n += 1;
+        });
+    }
+
+    // ord世代目がmigration_intervalの倍数なら、各島の良い個体を移す
+    // 移った先では一番悪い個体と入れ替わる
+    pub fn migrate(&mut self, ord: i32) {
+        let base = config::get();
+        let islands = &base.islands;
+        if islands.interval == 0 || ord as usize % islands.interval != 0 {
+            return;
+        }
+        let count = self.islands.len();
+        let mut arrivals: Vec<Vec<(Cat, Score)>> = vec![Vec::new(); count];
+        for (from, island) in self.islands.iter().enumerate() {
+            config::set(island.config.clone());
+            let emigrants = island.world.emigrants(islands.migrants);
+            for to in islands.topology.destinations(from, count) {
+                arrivals[to].extend(emigrants.iter().cloned());
+            }
+        }
+        for (island, migrants) in self.islands.iter_mut().zip(arrivals) {
+            config::set(island.config.clone());
+            island.world.immigrate(migrants);
+        }
+        config::set((*base).clone());
+    }
+
+    // 全ての島で次の世代へ
+    pub fn next_generation(&mut self) {
+        self.each(|world| world.next_generation());
+    }
+
+    // 島ごとの設定と乱数に切り替えながら世界を操作し、最後に元の設定に戻す
+    fn each<F: FnMut(&mut World)>(&mut self, mut f: F) {
+        let base = config::get();
+        for island in self.islands.iter_mut() {
+            config::set(island.config.clone());
+            random::swap(&mut island.rng);
+            f(&mut island.world);
+            random::swap(&mut island.rng);
+        }
+        config::set((*base).clone());
+    }
+}
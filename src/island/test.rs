@@ -0,0 +1,97 @@
+#[cfg(test)]
+mod tests{
+    use animal::Animal;
+    use config;
+    use config::Config;
+    use fitness::Score;
+    use island::{Archipelago, Topology};
+    use scenario::Scenario;
+    use termination::Condition;
+
+    // 数ステップで終わる三つの島
+    fn short_config() -> Config {
+        let mut config = Config::default();
+        config.termination.conditions = vec![Condition::Frames(3)];
+        config.set("islands", "3").unwrap();
+        config.set("migration_interval", "2").unwrap();
+        config
+    }
+
+    fn small_scenario() -> Scenario {
+        let mut scenario = Scenario::default();
+        scenario.cats.count = 5;
+        scenario.cats.offspring = Some(5);
+        scenario.rats.count = 10;
+        scenario
+    }
+
+    #[test]
+    fn topology_test(){
+        assert_eq!(Ok(Topology::Full), Topology::parse("full"));
+        assert!(Topology::parse("star").is_err());
+        assert_eq!(vec![1], Topology::Ring.destinations(0, 3));
+        assert_eq!(vec![0], Topology::Ring.destinations(2, 3));
+        assert!(Topology::Ring.destinations(0, 1).is_empty());
+        assert_eq!(vec![0, 2], Topology::Full.destinations(1, 3));
+    }
+
+    #[test]
+    fn new_test(){
+        let mut config = short_config();
+        config.set("island.1.trials", "7").unwrap();
+        config::set(config.clone());
+        let archipelago = Archipelago::new(&small_scenario()).unwrap();
+        assert_eq!(3, archipelago.islands.len());
+        assert_eq!(7, archipelago.islands[1].config.evaluation.trials);
+        assert_eq!(config.evaluation.trials, archipelago.islands[0].config.evaluation.trials);
+        // 島を作ったあとは元の設定に戻る
+        assert!(config::get().islands.overrides.len() == 1);
+
+        // ない島への上書き
+        config.set("island.3.trials", "2").unwrap();
+        config::set(config);
+        assert!(Archipelago::new(&small_scenario()).is_err());
+        config::set(Config::default());
+    }
+
+    #[test]
+    fn seed_test(){
+        // 同じシードの島は同じ位置から始まり、シードのない島は全体の乱数から分けた別の乱数で始まる
+        let mut config = short_config();
+        config.set("island.0.seed", "5").unwrap();
+        config.set("island.1.seed", "5").unwrap();
+        config::set(config);
+        let archipelago = Archipelago::new(&small_scenario()).unwrap();
+        config::set(Config::default());
+        let positions = |n: usize| -> Vec<(f64, f64)> {
+            archipelago.islands[n].world.cats.iter().map(|cat| (cat.position().x, cat.position().y)).collect()
+        };
+        assert_eq!(positions(0), positions(1));
+        assert_ne!(positions(0), positions(2));
+    }
+
+        #[test]
+    fn migrate_test(){
+        config::set(short_config());
+        let mut archipelago = Archipelago::new(&small_scenario()).unwrap();
+        archipelago.run();
+        // 0番の島の一匹だけが食べたことにする
+        let best = archipelago.islands[0].world.cats[2].feed(0.0, 5);
+        archipelago.islands[0].world.cats[2] = best.clone();
+
+        // 間隔の倍数でない世代では移らない
+        archipelago.migrate(1);
+        assert!(archipelago.islands[1].world.scores.is_none());
+
+        archipelago.migrate(2);
+        let next = &archipelago.islands[1].world;
+        assert!(next.cats.iter().any(|cat| cat.ate() == 5));
+        assert_eq!(next.cats.len(), next.scores().len());
+        assert!(next.scores().iter().any(|score: &Score| score.fitness == 5.0));
+        assert_eq!(small_scenario().cats.count, next.cats.len());
+
+        archipelago.next_generation();
+        assert!(archipelago.islands.iter().all(|island| island.world.cats.len() == 5));
+        config::set(Config::default());
+    }
+}
@@ -9,21 +9,221 @@ mod pvector;
 mod app;
 mod consts;
 mod quad_tree;
+mod config;
+mod vision;
+mod falloff;
+mod steering;
+mod obstacle;
+mod clock;
+mod world;
+mod boundary;
+mod scenario;
+mod food;
+mod predation;
+mod termination;
+mod fitness;
+mod pareto;
+mod evaluation;
+mod island;
+mod hall_of_fame;
+mod family;
+mod diversity;
+mod neural;
+mod neat;
+mod random;
 //mod kd_tree;
 
+use animal::Animal;
 use app::App;
+use config::Config;
+use evaluation::Evaluation;
+use hall_of_fame::Versus;
+use island::Archipelago;
+use scenario::Scenario;
+use std::fs::File;
+use std::io::Write;
+use world::World;
+
+// 「--flag 値」の値を取り出す
+fn flag_value(args: &Vec<String>, flag: &str) -> Option<String> {
+    args.iter().position(|arg| arg == flag).map(|n| {
+        args.get(n + 1).unwrap_or_else(|| exit_with(format!("{} needs a value", flag))).clone()
+    })
+}
+
+// 「--flag FILE」があればファイルを作って見出しを書く
+fn log_file(args: &Vec<String>, flag: &str, header: &str) -> Option<File> {
+    flag_value(args, flag).map(|path| {
+        let mut file = File::create(&path).unwrap_or_else(|e| exit_with(format!("{}: {}", path, e)));
+        writeln!(file, "{}", header).unwrap_or_else(|e| exit_with(format!("{}: {}", path, e)));
+        file
+    })
+}
+
+fn exit_with(message: String) -> ! {
+    eprintln!("{}", message);
+    std::process::exit(1);
+}
 
 fn main(){
-        print!("gen,");
-        print!("chase,");
-        print!("align,");
-        print!("cohension,");
-        print!("separate");
-        println!("");
-    let mut app = App::new();
+    let args: Vec<String> = std::env::args().collect();
+    // シナリオは組み込みの名前かファイルのパスで選ぶ
+    let scenario = match flag_value(&args, "--scenario") {
+        Some(name) => Scenario::find(&name).unwrap_or_else(|e| exit_with(e)),
+        None => Scenario::default(),
+    };
+    // 設定ファイルの上にシナリオによる上書きを重ねる
+    let mut loaded = match flag_value(&args, "--config") {
+        Some(path) => Config::load(&path).unwrap_or_else(|e| exit_with(e)),
+        None => Config::default(),
+    };
+    scenario.apply(&mut loaded).unwrap_or_else(|e| exit_with(e));
+    config::set(loaded);
+    // seedを決めれば同じ設定で同じ結果を繰り返せる
+    if let Some(seed) = config::get().seed {
+        random::seed(seed);
+    }
+
+    // --champions FILEなら殿堂入りの猫を読み直す。--versus championsなら猫どうしで一度だけ狩らせて終わる
+    let champions = flag_value(&args, "--champions").map(|path| hall_of_fame::load(&path).unwrap_or_else(|e| exit_with(e)));
+    // --replay FILEなら--networksで書き出したネットワークを最初の世代の猫とねずみに持たせて動かす
+    let replay = flag_value(&args, "--replay").map(|path| neat::load(&path).unwrap_or_else(|e| exit_with(e)));
+    let versus = flag_value(&args, "--versus").map_or(Ok(Versus::Champions), |text| Versus::parse(&text)).unwrap_or_else(|e| exit_with(e));
+    if let (Some(ref champions), Versus::Champions) = (&champions, versus) {
+        println!("gen,id,fitness");
+        for (champion, score) in champions.iter().zip(hall_of_fame::tournament(champions, &scenario)) {
+            println!("{},{},{}", champion.generation, champion.cat.id(), score.fitness);
+        }
+        return;
+    }
+
+    // 島に分けるなら行の頭に島の番号をつける。島ごとの記録や読み直しにはまだ対応していない
+    let islands = config::get().islands.count > 1;
+    if islands {
+        let unsupported = ["--ages", "--kills", "--fitness", "--pareto", "--hall", "--family", "--family-dot",
+                           "--diversity", "--species", "--networks", "--replay", "--champions"];
+        if let Some(flag) = unsupported.iter().find(|&&flag| args.iter().any(|arg| arg == flag)) {
+            exit_with(format!("{} cannot be used with islands", flag));
+        }
+        print!("island,");
+    }
+    print!("gen,");
+    print!("chase,");
+    print!("align,");
+    print!("cohension,");
+    print!("separate,");
+    print!("view_angle,");
+    print!("blind_spot,");
+    print!("lead_time,");
+    print!("fitness,");
+    print!("misses,");
+    print!("end");
+    println!("");
+    // 島ごとに別々に進化させ、ときどき良い猫を移す。ウィンドウは出さない
+    if islands {
+        let mut archipelago = Archipelago::new(&scenario).unwrap_or_else(|e| exit_with(e));
+        for i in 1..100 {
+            archipelago.run();
+            archipelago.print_params(i);
+            archipelago.migrate(i);
+            archipelago.next_generation();
+        }
+        return;
+    }
+    // --headlessならウィンドウを出さずにステップを数えて進める。isolatedでは見せる場所がない
+    let isolated = config::get().evaluation.mode == Evaluation::Isolated;
+    let mut app = if isolated || args.iter().any(|arg| arg == "--headless") {
+        None
+    } else {
+        Some(App::new())
+    };
+    // --ages FILEなら世代ごとに死んだ時の年齢を、--kills FILEなら誰が誰を食べたかを、
+    // --fitness FILEなら世代の終わりの猫ごとの適応度を書き出す
+    let mut ages = log_file(&args, "--ages", "gen,species,age");
+    let mut kills = log_file(&args, "--kills", "gen,cat,rat,x,y");
+    let mut fitness = log_file(&args, "--fitness", "gen,cat,fitness");
+    // --pareto FILEなら世代ごとに目的の値でパレートフロントにいる猫を書き出す
+    let objectives: Vec<&str> = config::get().objectives.iter().map(|measure| measure.name()).collect();
+    let mut front = log_file(&args, "--pareto", &format!("gen,cat,{}", objectives.join(",")));
+    // --hall FILEなら世代ごとに上位の猫を殿堂入りさせて書き出す
+    let mut hall = log_file(&args, "--hall", hall_of_fame::HEADER);
+    // --family FILEなら生まれた個体と親を辺の一覧として、--family-dot FILEなら家系図全体をDOTで書き出す
+    let mut edges = log_file(&args, "--family", family::HEADER);
+    let dot = flag_value(&args, "--family-dot");
+    let mut members = Vec::new();
+    // --diversity FILEなら世代ごとの遺伝的な多様性を書き出す。plateauを設定すれば収束したところで止める
+    let mut spread = log_file(&args, "--diversity", diversity::HEADER);
+    let mut history = Vec::new();
+    // --species FILEならNEATの種分けの様子を、--networks FILEなら種ごとに一番良いネットワークを書き出す
+    let mut niches = log_file(&args, "--species", neat::SPECIES_HEADER);
+    let mut networks = log_file(&args, "--networks", neat::NETWORKS_HEADER);
+    let mut world = World::new(scenario);
+    if let Some(ref brains) = replay {
+        world.replay(brains);
+    }
+    // --versus populationなら殿堂入りの猫を最初の世代に加えて競わせる
+    if let Some(ref champions) = champions {
+        world.admit(&champions.iter().map(|champion| champion.cat.twin()).collect());
+    }
     for i in 1..100 {
-        app.show_window();
-        app.print_params(i);
-        app.next_generation();
+        // isolatedなら猫を一匹ずつ別の場所で試す
+        if isolated {
+            world.evaluate();
+        } else {
+            match app {
+                Some(ref mut app) => app.show_window(&mut world),
+                None => world.run(),
+            }
+        }
+        world.print_params(i);
+        if let Some(ref mut file) = ages {
+            world.write_deaths(file, i).unwrap_or_else(|e| exit_with(e.to_string()));
+        }
+        if let Some(ref mut file) = kills {
+            world.write_kills(file, i).unwrap_or_else(|e| exit_with(e.to_string()));
+        }
+        if let Some(ref mut file) = fitness {
+            world.write_fitness(file, i).unwrap_or_else(|e| exit_with(e.to_string()));
+        }
+        if let Some(ref mut file) = front {
+            world.write_front(file, i).unwrap_or_else(|e| exit_with(e.to_string()));
+        }
+        if let Some(ref mut file) = hall {
+            // 世代の途中で死んだ猫も殿堂入りの候補にする
+            let (cats, scores) = world.candidates();
+            for champion in hall_of_fame::induct(i, &cats, &scores, config::get().hall_of_fame) {
+                writeln!(file, "{}", champion.row()).unwrap_or_else(|e| exit_with(e.to_string()));
+            }
+        }
+        if let Some(ref mut file) = edges {
+            family::write_edges(file, &world.births).unwrap_or_else(|e| exit_with(e.to_string()));
+        }
+        // DOTは閉じた形で書くので、全員を貯めておいて最後に一度だけ書く
+        if dot.is_some() {
+            members.extend(world.births.iter().cloned());
+        }
+        world.speciate();
+        if let Some(ref mut file) = niches {
+            world.write_species(file, i).unwrap_or_else(|e| exit_with(e.to_string()));
+        }
+        if let Some(ref mut file) = networks {
+            world.write_networks(file, i).unwrap_or_else(|e| exit_with(e.to_string()));
+        }
+        let measured = diversity::measure(&world.cats);
+        let mean_fitness = world.mean_fitness();
+        if let Some(ref mut file) = spread {
+            measured.write(file, i, mean_fitness).unwrap_or_else(|e| exit_with(e.to_string()));
+        }
+        history.push((measured.distance, mean_fitness));
+        if diversity::has_converged(&history) {
+            eprintln!("converged at generation {}", i);
+            break;
+        }
+        world.next_generation();
+    }
+    if let Some(ref path) = dot {
+        File::create(path)
+            .and_then(|mut file| family::write_dot(&mut file, &members))
+            .unwrap_or_else(|e| exit_with(format!("{}: {}", path, e)));
     }
 }
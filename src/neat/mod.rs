@@ -0,0 +1,557 @@
+mod test;
+
+use animal::{Animal, Lineage, Species};
+use config;
+use config::parse_f64;
+use consts::*;
+use neural;
+use neural::Brain;
+use rand::prelude::*;
+use random;
+use std::collections::{HashMap, HashSet};
+use std::fs::File;
+use std::io;
+use std::io::{Read, Write};
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+// 種分けの統計の見出し。ニューロンと結合の数は種の中の平均で、結合は有効なものだけ数える
+pub const SPECIES_HEADER: &str = "gen,species,niche,size,mean_nodes,mean_connections,best_fitness";
+
+// 種ごとの一番良いネットワークの見出し。brainはBrain::to_textの文字列
+pub const NETWORKS_HEADER: &str = "gen,species,niche,id,brain";
+
+// ニューロンの種類
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum NodeKind {
+    Input,
+    Bias,   // 常に1を出す
+    Output,
+    Hidden,
+}
+
+// ニューロン。idはどの個体でも同じ位置のニューロンなら同じ
+#[derive(Debug, Clone, PartialEq)]
+pub struct Node {
+    pub id: usize,
+    pub kind: NodeKind,
+}
+
+// ニューロンの間の結合。innovationは同じ構造の結合に全員で同じ番号をつけた歴史的な印
+#[derive(Debug, Clone, PartialEq)]
+pub struct Connection {
+    pub from: usize,
+    pub to: usize,
+    pub weight: f64,
+    pub enabled: bool,
+    pub innovation: u64,
+}
+
+// 構造ごと進化するネットワークの遺伝子。結合はinnovationの順に並べる
+// idは入力が0から、その次がバイアス、続いて出力で、隠れ層はHIDDEN_NODE_BASEから
+#[derive(Debug, Clone, PartialEq)]
+pub struct Genome {
+    pub inputs: usize,
+    pub outputs: usize,
+    pub nodes: Vec<Node>,
+    pub connections: Vec<Connection>,
+    order: Vec<(usize, Vec<usize>)>, // 計算する順の隠れ層と出力のidと、そこへ入る有効な結合の添字
+}
+
+// 似た構造の遺伝子の集まり。代表との距離で所属を決める
+#[derive(Debug, Clone, PartialEq)]
+pub struct Niche {
+    pub id: u64,
+    pub representative: Genome,
+    pub members: Vec<usize>, // 個体の添字
+}
+
+// どの結合にどのinnovationをつけたか、どの結合を分けてどのニューロンを作ったか
+struct Registry {
+    connections: HashMap<(usize, usize), u64>,
+    splits: HashMap<u64, usize>,
+    innovation: u64,
+    node: usize,
+}
+
+// 全ての個体、全てのスレッドで共有する印の台帳
+static REGISTRY: Mutex<Option<Registry>> = Mutex::new(None);
+
+// 次に作る種のid
+static NEXT_NICHE: AtomicU64 = AtomicU64::new(1);
+
+// 隠れ層のニューロンのidの始まり。入力や出力のidと重ならないようにする
+const HIDDEN_NODE_BASE: usize = 1 << 20;
+
+impl Registry {
+    fn new() -> Registry {
+        Registry {
+            connections: HashMap::new(),
+            splits: HashMap::new(),
+            innovation: 0,
+            node: HIDDEN_NODE_BASE,
+        }
+    }
+}
+
+// 台帳を使う。初めて使う時に作る
+fn with_registry<T, F: FnOnce(&mut Registry) -> T>(f: F) -> T {
+    let mut guard = REGISTRY.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+    f(guard.get_or_insert_with(Registry::new))
+}
+
+// fromからtoへの結合のinnovation。初めての結合なら新しい番号をつける
+pub fn innovation(from: usize, to: usize) -> u64 {
+    with_registry(|registry| {
+        let next = registry.innovation;
+        let ret = *registry.connections.entry((from, to)).or_insert(next);
+        if ret == next {
+            registry.innovation += 1;
+        }
+        ret
+    })
+}
+
+// innovationの結合を分けた時にできるニューロンのid。同じ結合を分ければ同じid
+pub fn split_node(innovation: u64) -> usize {
+    with_registry(|registry| {
+        let next = registry.node;
+        let ret = *registry.splits.entry(innovation).or_insert(next);
+        if ret == next {
+            registry.node += 1;
+        }
+        ret
+    })
+}
+
+// 読み直した遺伝子の印を台帳に載せ、これから作る番号と重ならないようにする
+fn observe(genome: &Genome) {
+    with_registry(|registry| {
+        for connection in &genome.connections {
+            registry.connections.entry((connection.from, connection.to)).or_insert(connection.innovation);
+            registry.innovation = registry.innovation.max(connection.innovation + 1);
+        }
+        for node in genome.nodes.iter().filter(|node| node.kind == NodeKind::Hidden) {
+            registry.node = registry.node.max(node.id + 1);
+        }
+    });
+}
+
+impl Genome {
+    // 隠れ層がなく、入力とバイアスの全てが出力の全てにつながった遺伝子。重みは-1から1の一様乱数
+    pub fn minimal(inputs: usize, outputs: usize) -> Genome {
+        let mut rng = random::rng();
+        let nodes = Genome::io_nodes(inputs, outputs);
+        let mut connections = Vec::new();
+        for from in 0..(inputs + 1) {
+            for to in (inputs + 1)..(inputs + 1 + outputs) {
+                connections.push(Connection {
+                    from,
+                    to,
+                    weight: rng.gen::<f64>() * 2.0 - 1.0,
+                    enabled: true,
+                    innovation: innovation(from, to),
+                });
+            }
+        }
+        connections.sort_by_key(|connection| connection.innovation);
+        Genome { inputs, outputs, nodes, connections, order: Vec::new() }.sorted()
+    }
+
+    // 入力、バイアス、出力のニューロン
+    fn io_nodes(inputs: usize, outputs: usize) -> Vec<Node> {
+        let mut ret: Vec<Node> = (0..inputs).map(|id| Node { id, kind: NodeKind::Input }).collect();
+        ret.push(Node { id: inputs, kind: NodeKind::Bias });
+        ret.extend((0..outputs).map(|n| Node { id: inputs + 1 + n, kind: NodeKind::Output }));
+        ret
+    }
+
+    // 計算する順を決め直す。ニューロンや結合の有無を変えたら呼ぶ
+    // 入ってくる結合の元が全て決まったニューロンから順に並べる。決まらなければ残りの先頭から並べる
+    fn sorted(mut self) -> Genome {
+        let enabled: Vec<usize> = (0..self.connections.len()).filter(|&n| self.connections[n].enabled).collect();
+        let mut known: HashSet<usize> = self
+            .nodes
+            .iter()
+            .filter(|node| node.kind == NodeKind::Input || node.kind == NodeKind::Bias)
+            .map(|node| node.id)
+            .collect();
+        let mut remaining: Vec<usize> = self
+            .nodes
+            .iter()
+            .filter(|node| node.kind == NodeKind::Hidden || node.kind == NodeKind::Output)
+            .map(|node| node.id)
+            .collect();
+        let mut order = Vec::with_capacity(remaining.len());
+        while !remaining.is_empty() {
+            let ready = remaining
+                .iter()
+                .position(|&id| enabled.iter().filter(|&&n| self.connections[n].to == id).all(|&n| known.contains(&self.connections[n].from)))
+                .unwrap_or(0);
+            let id = remaining.remove(ready);
+            known.insert(id);
+            order.push((id, enabled.iter().cloned().filter(|&n| self.connections[n].to == id).collect()));
+        }
+        self.order = order;
+        self
+    }
+
+    // 入力から出力を求める。隠れ層と出力の活性化関数はtanh
+    // sortedで決めた順に計算し、まだ決まっていない元の値は0とみなす
+    pub fn activate(&self, input: &[f64]) -> Vec<f64> {
+        let mut values: HashMap<usize, f64> = HashMap::new();
+        for node in &self.nodes {
+            match node.kind {
+                NodeKind::Input => { values.insert(node.id, input.get(node.id).cloned().unwrap_or(0.0)); }
+                NodeKind::Bias => { values.insert(node.id, 1.0); }
+                _ => {}
+            }
+        }
+        for &(id, ref incoming) in &self.order {
+            let sum: f64 = incoming
+                .iter()
+                .map(|&n| &self.connections[n])
+                .map(|c| c.weight * values.get(&c.from).cloned().unwrap_or(0.0))
+                .sum();
+            values.insert(id, sum.tanh());
+        }
+        (0..self.outputs).map(|n| values[&(self.inputs + 1 + n)]).collect()
+    }
+
+    // 重みを変え、確率で結合かニューロンを足す
+    pub fn mutate(&self) -> Genome {
+        let config = config::get();
+        let mut rng = random::rng();
+        let mut ret = self.clone();
+        let abs = config.controller.mutation;
+        for connection in ret.connections.iter_mut() {
+            if rng.gen::<f64>() < NEAT_REPLACE_RATE {
+                connection.weight = rng.gen::<f64>() * 2.0 - 1.0;
+            } else {
+                connection.weight += rng.gen::<f64>() * abs * 2.0 - abs;
+            }
+        }
+        if rng.gen::<f64>() < config.neat.add_connection {
+            ret = ret.add_connection(&mut rng);
+        }
+        if rng.gen::<f64>() < config.neat.add_node {
+            ret = ret.add_node(&mut rng);
+        }
+        ret
+    }
+
+    // まだない結合を一つ足す。出力から入力へ戻る向きや、輪になる結合は作らない
+    pub fn add_connection<R: Rng>(&self, rng: &mut R) -> Genome {
+        let sources: Vec<usize> = self.nodes.iter().filter(|node| node.kind != NodeKind::Output).map(|node| node.id).collect();
+        let targets: Vec<usize> = self
+            .nodes
+            .iter()
+            .filter(|node| node.kind == NodeKind::Hidden || node.kind == NodeKind::Output)
+            .map(|node| node.id)
+            .collect();
+        let mut ret = self.clone();
+        for _ in 0..NEAT_ATTEMPTS {
+            let from = sources[rng.gen_range(0, sources.len())];
+            let to = targets[rng.gen_range(0, targets.len())];
+            if from == to || self.connects(from, to) || self.reaches(to, from) {
+                continue;
+            }
+            ret.connections.push(Connection { from, to, weight: rng.gen::<f64>() * 2.0 - 1.0, enabled: true, innovation: innovation(from, to) });
+            ret.connections.sort_by_key(|connection| connection.innovation);
+            return ret.sorted();
+        }
+        ret
+    }
+
+    // 有効な結合を一つ選んで間にニューロンを挟む。元の結合は無効にし、入る側の重みを1、出る側を元の重みにする
+    pub fn add_node<R: Rng>(&self, rng: &mut R) -> Genome {
+        let enabled: Vec<usize> = (0..self.connections.len()).filter(|&n| self.connections[n].enabled).collect();
+        if enabled.is_empty() {
+            return self.clone();
+        }
+        let split = self.connections[enabled[rng.gen_range(0, enabled.len())]].clone();
+        let id = split_node(split.innovation);
+        if self.nodes.iter().any(|node| node.id == id) {
+            return self.clone();
+        }
+        let mut ret = self.clone();
+        for connection in ret.connections.iter_mut().filter(|connection| connection.innovation == split.innovation) {
+            connection.enabled = false;
+        }
+        ret.nodes.push(Node { id, kind: NodeKind::Hidden });
+        ret.connections.push(Connection { from: split.from, to: id, weight: 1.0, enabled: true, innovation: innovation(split.from, id) });
+        ret.connections.push(Connection { from: id, to: split.to, weight: split.weight, enabled: true, innovation: innovation(id, split.to) });
+        ret.connections.sort_by_key(|connection| connection.innovation);
+        ret.sorted()
+    }
+
+    // fromからtoへの結合があるか
+    fn connects(&self, from: usize, to: usize) -> bool {
+        self.connections.iter().any(|connection| connection.from == from && connection.to == to)
+    }
+
+    // fromから結合をたどってtoに着けるか
+    fn reaches(&self, from: usize, to: usize) -> bool {
+        let mut stack = vec![from];
+        let mut visited = HashSet::new();
+        while let Some(id) = stack.pop() {
+            if id == to {
+                return true;
+            }
+            if visited.insert(id) {
+                stack.extend(self.connections.iter().filter(|c| c.from == id).map(|c| c.to));
+            }
+        }
+        false
+    }
+
+    // 適応度の高い親fitterともう一方の親の子。同じinnovationの結合はどちらかから選び、
+    // それ以外はfitterから受け継ぐ。どちらかで無効なら確率で無効のまま
+    pub fn crossover(fitter: &Genome, other: &Genome) -> Genome {
+        let mut rng = random::rng();
+        let others: HashMap<u64, &Connection> = other.connections.iter().map(|c| (c.innovation, c)).collect();
+        let mut ret = fitter.clone();
+        for connection in ret.connections.iter_mut() {
+            if let Some(matching) = others.get(&connection.innovation) {
+                if rng.gen::<bool>() {
+                    connection.weight = matching.weight;
+                }
+                if !connection.enabled || !matching.enabled {
+                    connection.enabled = rng.gen::<f64>() >= NEAT_DISABLE_RATE;
+                }
+            }
+        }
+        ret.sorted()
+    }
+
+    // 種を分けるための距離。c1・余剰 + c2・離散を遺伝子の数で割り、c3・一致した重みの差の平均を足す
+    pub fn distance(&self, other: &Genome) -> f64 {
+        let mine: HashMap<u64, f64> = self.connections.iter().map(|c| (c.innovation, c.weight)).collect();
+        let theirs: HashMap<u64, f64> = other.connections.iter().map(|c| (c.innovation, c.weight)).collect();
+        let my_max = mine.keys().cloned().max().unwrap_or(0);
+        let their_max = theirs.keys().cloned().max().unwrap_or(0);
+        let (mut excess, mut disjoint, mut matching, mut difference) = (0, 0, 0, 0.0);
+        for (innovation, weight) in &mine {
+            match theirs.get(innovation) {
+                Some(theirs) => {
+                    matching += 1;
+                    difference += (weight - theirs).abs();
+                }
+                None if *innovation > their_max => excess += 1,
+                None => disjoint += 1,
+            }
+        }
+        for innovation in theirs.keys().filter(|innovation| !mine.contains_key(innovation)) {
+            if *innovation > my_max { excess += 1 } else { disjoint += 1 }
+        }
+        let size = mine.len().max(theirs.len()).max(1) as f64;
+        let mean = if matching > 0 { difference / matching as f64 } else { 0.0 };
+        NEAT_EXCESS * excess as f64 / size + NEAT_DISJOINT * disjoint as f64 / size + NEAT_WEIGHT * mean
+    }
+
+    // 「入力数 出力数 h隠れ層のid... 元>先:重み:有効:innovation...」を空白で区切った文字列
+    pub fn to_text(&self) -> String {
+        let mut words = vec![self.inputs.to_string(), self.outputs.to_string()];
+        words.extend(self.nodes.iter().filter(|node| node.kind == NodeKind::Hidden).map(|node| format!("h{}", node.id)));
+        words.extend(self.connections.iter().map(|c| {
+            format!("{}>{}:{}:{}:{}", c.from, c.to, c.weight, if c.enabled { 1 } else { 0 }, c.innovation)
+        }));
+        words.join(" ")
+    }
+
+    // to_textで書いた文字列を読む。読んだ印は台帳に載せる
+    pub fn parse(text: &str) -> Result<Genome, String> {
+        let words: Vec<&str> = text.split_whitespace().collect();
+        if words.len() < 2 {
+            return Err(format!("expected `inputs outputs nodes... connections...` in `{}`", text));
+        }
+        let number = |word: &str| word.parse::<usize>().map_err(|_| format!("`{}` is not a node", word));
+        let (inputs, outputs) = (number(words[0])?, number(words[1])?);
+        let mut ret = Genome { inputs, outputs, nodes: Genome::io_nodes(inputs, outputs), connections: Vec::new(), order: Vec::new() };
+        for word in &words[2..] {
+            if word.starts_with('h') {
+                ret.nodes.push(Node { id: number(&word[1..])?, kind: NodeKind::Hidden });
+                continue;
+            }
+            let fields: Vec<&str> = word.split(|c| c == '>' || c == ':').collect();
+            if fields.len() != 5 {
+                return Err(format!("expected `from>to:weight:enabled:innovation` in `{}`", word));
+            }
+            let (from, to) = (number(fields[0])?, number(fields[1])?);
+            if !ret.nodes.iter().any(|node| node.id == from) || !ret.nodes.iter().any(|node| node.id == to) {
+                return Err(format!("unknown node in `{}`", word));
+            }
+            ret.connections.push(Connection {
+                from,
+                to,
+                weight: parse_f64(fields[2])?,
+                enabled: fields[3] == "1",
+                innovation: fields[4].parse::<u64>().map_err(|_| format!("`{}` is not an innovation", fields[4]))?,
+            });
+        }
+        ret.connections.sort_by_key(|connection| connection.innovation);
+        observe(&ret);
+        Ok(ret.sorted())
+    }
+}
+
+// 前の世代の種の代表と比べて種に分ける。どの代表とも離れていれば新しい種を作る
+// 次の世代の代表は種の最初の個体にする
+pub fn speciate(genomes: &[(usize, Genome)], previous: &[Niche]) -> Vec<Niche> {
+    let threshold = config::get().neat.compatibility;
+    let mut ret: Vec<Niche> = previous
+        .iter()
+        .map(|niche| Niche { id: niche.id, representative: niche.representative.clone(), members: Vec::new() })
+        .collect();
+    for &(n, ref genome) in genomes {
+        match ret.iter().position(|niche| niche.representative.distance(genome) < threshold) {
+            Some(found) => ret[found].members.push(n),
+            None => ret.push(Niche {
+                id: NEXT_NICHE.fetch_add(1, Ordering::Relaxed),
+                representative: genome.clone(),
+                members: vec![n],
+            }),
+        }
+    }
+    ret.retain(|niche| !niche.members.is_empty());
+    for niche in ret.iter_mut() {
+        let first = niche.members[0];
+        niche.representative = genomes.iter().find(|&&(n, _)| n == first).map(|&(_, ref genome)| genome.clone()).unwrap();
+    }
+    ret
+}
+
+// 種の大きさで割った適応度の合計に比例して、size匹の子を種に割り振る
+pub fn allot(niches: &[Niche], fitness: &[f64], size: usize) -> Vec<usize> {
+    let low = fitness.iter().cloned().fold(std::f64::INFINITY, f64::min);
+    // 負の適応度があっても比べられるように、一番低い値が少しだけ正になるようにずらす
+    let adjusted: Vec<f64> = niches
+        .iter()
+        .map(|niche| niche.members.iter().map(|&n| fitness[n] - low + 1.0e-3).sum::<f64>() / niche.members.len() as f64)
+        .collect();
+    let total: f64 = adjusted.iter().sum();
+    if total <= 0.0 || niches.is_empty() {
+        return vec![0; niches.len()];
+    }
+    let shares: Vec<f64> = adjusted.iter().map(|a| a / total * size as f64).collect();
+    let mut ret: Vec<usize> = shares.iter().map(|share| share.floor() as usize).collect();
+    // 端数の大きい種から残りを一匹ずつ足す
+    let mut order: Vec<usize> = (0..niches.len()).collect();
+    order.sort_by(|&a, &b| {
+        (shares[b] - shares[b].floor()).partial_cmp(&(shares[a] - shares[a].floor())).unwrap_or(std::cmp::Ordering::Equal)
+    });
+    let given: usize = ret.iter().sum();
+    for &n in order.iter().cycle().take(size - given) {
+        ret[n] += 1;
+    }
+    ret
+}
+
+// NEATの世代交代。種ごとに割り振った数の子を、種の上位の個体から交叉と突然変異で作る
+// 大きな種では一番良い個体をそのまま残す。子の脳以外の遺伝子は適応度の高い方の親から継ぎ、交叉した子はもう一方の親も系統に残す
+pub fn next_generation<A: Animal>(animals: &Vec<A>, fitness: &[f64], niches: &[Niche], size: usize) -> Vec<A> {
+    let config = config::get();
+    let mut rng = random::rng();
+    if animals.is_empty() || niches.is_empty() {
+        return (0..size).map(|_| A::new()).collect();
+    }
+    // NEAT以外の脳を持つ個体が混ざっていれば最小の遺伝子から始める
+    let genome = |n: usize| match animals[n].brain() {
+        Some(Brain::Neat(genome)) => genome,
+        _ => Genome::minimal(neural::inputs(config.controller.nearest), 2),
+    };
+    let mut ret = Vec::with_capacity(size);
+    for (niche, count) in niches.iter().zip(allot(niches, fitness, size)) {
+        let mut members = niche.members.clone();
+        members.sort_by(|&a, &b| fitness[b].partial_cmp(&fitness[a]).unwrap_or(std::cmp::Ordering::Equal));
+        let survivors = ((members.len() as f64 * config.neat.survival).ceil() as usize).max(1);
+        members.truncate(survivors);
+        for k in 0..count {
+            if k == 0 && niche.members.len() >= NEAT_ELITE_SIZE {
+                ret.push(animals[members[0]].descendant().set_brain(Some(Brain::Neat(genome(members[0])))));
+                continue;
+            }
+            let a = members[rng.gen_range(0, members.len())];
+            let b = members[rng.gen_range(0, members.len())];
+            let (fitter, other) = if fitness[a] >= fitness[b] { (a, b) } else { (b, a) };
+            let child = animals[fitter].descendant();
+            if a != b && rng.gen::<f64>() < config.neat.crossover {
+                let brain = Genome::crossover(&genome(fitter), &genome(other)).mutate();
+                let lineage = Lineage { mate: Some(animals[other].id()), ..child.lineage() };
+                ret.push(child.set_lineage(&lineage).set_brain(Some(Brain::Neat(brain))));
+            } else {
+                ret.push(child.set_brain(Some(Brain::Neat(genome(fitter).mutate()))));
+            }
+        }
+    }
+    ret
+}
+
+// 種の中で一番適応度の高い個体の添字
+fn best(niche: &Niche, fitness: &[f64]) -> usize {
+    niche
+        .members
+        .iter()
+        .cloned()
+        .max_by(|&a, &b| fitness[a].partial_cmp(&fitness[b]).unwrap_or(std::cmp::Ordering::Equal))
+        .unwrap()
+}
+
+// 「世代,種,NEATの種,大きさ,ニューロン数の平均,結合数の平均,一番高い適応度」の行で書き出す
+pub fn write_species<W: Write, A: Animal>(out: &mut W, ord: i32, animals: &Vec<A>, fitness: &[f64], niches: &[Niche]) -> io::Result<()> {
+    for niche in niches {
+        let genomes: Vec<Genome> = niche
+            .members
+            .iter()
+            .filter_map(|&n| match animals[n].brain() {
+                Some(Brain::Neat(genome)) => Some(genome),
+                _ => None,
+            })
+            .collect();
+        let size = genomes.len() as f64;
+        let nodes = genomes.iter().map(|genome| genome.nodes.len()).sum::<usize>() as f64 / size;
+        let connections = genomes
+            .iter()
+            .map(|genome| genome.connections.iter().filter(|connection| connection.enabled).count())
+            .sum::<usize>() as f64
+            / size;
+        let species = animals[niche.members[0]].species();
+        writeln!(out, "{},{},{},{},{},{},{}", ord, species.name(), niche.id, niche.members.len(), nodes, connections, fitness[best(niche, fitness)])?;
+    }
+    Ok(())
+}
+
+// 種ごとに一番良い個体のネットワークを「世代,種,NEATの種,id,ネットワーク」の行で書き出す
+pub fn write_networks<W: Write, A: Animal>(out: &mut W, ord: i32, animals: &Vec<A>, fitness: &[f64], niches: &[Niche]) -> io::Result<()> {
+    for niche in niches {
+        let animal = &animals[best(niche, fitness)];
+        if let Some(brain) = animal.brain() {
+            writeln!(out, "{},{},{},{},{}", ord, animal.species().name(), niche.id, animal.id(), brain.to_text())?;
+        }
+    }
+    Ok(())
+}
+
+// write_networksで書き出したネットワークを種と一緒に読む。見出しの行は飛ばす
+// 入力と出力の数が今の設定に合わないネットワークはエラーにする
+pub fn load(path: &str) -> Result<Vec<(Species, Brain)>, String> {
+    let mut text = String::new();
+    File::open(path)
+        .and_then(|mut file| file.read_to_string(&mut text))
+        .map_err(|e| format!("{}: {}", path, e))?;
+    let parse = |line: &str| -> Result<(Species, Brain), String> {
+        let fields: Vec<&str> = line.split(',').map(|field| field.trim()).collect();
+        if fields.len() != NETWORKS_HEADER.split(',').count() {
+            return Err(format!("expected {} fields in `{}`", NETWORKS_HEADER.split(',').count(), line));
+        }
+        let brain = Brain::parse(fields[4])?;
+        brain.check()?;
+        Ok((Species::parse(fields[1])?, brain))
+    };
+    text
+        .lines()
+        .enumerate()
+        .filter(|&(_, line)| !line.trim().is_empty() && line.trim() != NETWORKS_HEADER)
+        .map(|(n, line)| parse(line).map_err(|e| format!("{}: line {}: {}", path, n + 1, e)))
+        .collect()
+}
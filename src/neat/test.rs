@@ -0,0 +1,197 @@
+#[cfg(test)]
+mod tests{
+    use animal::{Animal, Cat, Species};
+    use config;
+    use config::Config;
+    use consts::*;
+    use neat::{Genome, NETWORKS_HEADER, Niche, NodeKind, allot, load, next_generation, speciate, write_networks, write_species};
+    use neural::{Brain, Controller, inputs};
+    use rand::prelude::*;
+    use std::fs::File;
+    use std::io::Write;
+
+    #[test]
+    fn minimal_test(){
+        let genome = Genome::minimal(2, 1);
+        // 入力2つとバイアスが出力につながる
+        assert_eq!(4, genome.nodes.len());
+        assert_eq!(3, genome.connections.len());
+        assert_eq!(NodeKind::Bias, genome.nodes[2].kind);
+        let weights: Vec<f64> = genome.connections.iter().map(|c| c.weight).collect();
+        let output = genome.activate(&[0.5, -1.0]);
+        assert_eq!(1, output.len());
+        assert!((output[0] - (weights[0] * 0.5 - weights[1] + weights[2]).tanh()).abs() < 1.0e-12);
+        // 同じ結合には同じinnovationがつく
+        let other = Genome::minimal(2, 1);
+        let innovations = |genome: &Genome| -> Vec<u64> { genome.connections.iter().map(|c| c.innovation).collect() };
+        assert_eq!(innovations(&genome), innovations(&other));
+    }
+
+    #[test]
+    fn add_node_test(){
+        let mut rng = thread_rng();
+        let genome = Genome::minimal(1, 1);
+        let grown = genome.add_node(&mut rng);
+        assert_eq!(genome.nodes.len() + 1, grown.nodes.len());
+        assert_eq!(genome.connections.len() + 2, grown.connections.len());
+        assert_eq!(1, grown.connections.iter().filter(|c| !c.enabled).count());
+        // 挟んだニューロンは元の結合と同じ働きから始まる
+        let hidden = grown.nodes.iter().find(|node| node.kind == NodeKind::Hidden).unwrap().id;
+        assert!(grown.connections.iter().any(|c| c.to == hidden && c.weight == 1.0));
+        // 後から足した隠れ層も出力より先に計算する
+        let value = |id: usize| -> f64 { if id == 0 { 0.5 } else { 1.0 } };
+        let split = grown.connections.iter().find(|c| !c.enabled).unwrap();
+        let sum: f64 = grown
+            .connections
+            .iter()
+            .filter(|c| c.enabled && c.to == split.to)
+            .map(|c| c.weight * if c.from == hidden { value(split.from).tanh() } else { value(c.from) })
+            .sum();
+        assert!((grown.activate(&[0.5])[0] - sum.tanh()).abs() < 1.0e-12);
+        // 同じ結合を分ければ、別の個体でも同じニューロンと印になる
+        let mut again = genome.add_node(&mut rng);
+        while again.nodes.last().unwrap().id != hidden {
+            again = genome.add_node(&mut rng);
+        }
+        assert_eq!(grown.connections.iter().map(|c| c.innovation).collect::<Vec<u64>>(),
+                   again.connections.iter().map(|c| c.innovation).collect::<Vec<u64>>());
+    }
+
+    #[test]
+    fn add_connection_test(){
+        let mut rng = thread_rng();
+        let genome = Genome::minimal(2, 1).add_node(&mut rng);
+        let grown = (0..50).fold(genome.clone(), |genome, _| genome.add_connection(&mut rng));
+        // 同じ結合は二度作らず、出力からは出ない
+        for (n, a) in grown.connections.iter().enumerate() {
+            assert!(grown.connections[n + 1..].iter().all(|b| (a.from, a.to) != (b.from, b.to)));
+            assert!(grown.nodes.iter().any(|node| node.id == a.from && node.kind != NodeKind::Output));
+        }
+        assert_eq!(1, grown.activate(&[0.1, 0.2]).len());
+    }
+
+    #[test]
+    fn distance_test(){
+        let mut rng = thread_rng();
+        let genome = Genome::minimal(2, 2);
+        assert_eq!(0.0, genome.distance(&genome));
+        let grown = genome.add_node(&mut rng);
+        assert!(genome.distance(&grown) > 0.0);
+        assert!((genome.distance(&grown) - grown.distance(&genome)).abs() < 1.0e-12);
+    }
+
+    #[test]
+    fn crossover_test(){
+        let mut rng = thread_rng();
+        let fitter = Genome::minimal(2, 1).add_node(&mut rng);
+        let other = Genome::minimal(2, 1);
+        // 構造は適応度の高い親から受け継ぐ
+        let child = Genome::crossover(&fitter, &other);
+        assert_eq!(fitter.nodes, child.nodes);
+        assert_eq!(fitter.connections.len(), child.connections.len());
+        for connection in &child.connections {
+            let weights: Vec<f64> = fitter.connections.iter().chain(&other.connections)
+                .filter(|c| c.innovation == connection.innovation)
+                .map(|c| c.weight)
+                .collect();
+            assert!(weights.contains(&connection.weight));
+        }
+    }
+
+    #[test]
+    fn text_test(){
+        let mut rng = thread_rng();
+        let genome = Genome::minimal(3, 2).add_node(&mut rng).add_connection(&mut rng);
+        assert_eq!(Ok(genome.clone()), Genome::parse(&genome.to_text()));
+        let brain = Brain::Neat(genome);
+        assert_eq!(Ok(brain.clone()), Brain::parse(&brain.to_text()));
+        assert!(Genome::parse("3").is_err());
+        assert!(Genome::parse("1 1 0>9:0.5:1:0").is_err());
+        assert!(Genome::parse("1 1 0>2:0.5").is_err());
+    }
+
+    #[test]
+    fn load_test(){
+        let path = std::env::temp_dir().join("neat_load_test.csv");
+        let path = path.to_str().unwrap();
+        let fits = Brain::Neat(Genome::minimal(inputs(NEURAL_NEAREST), 2));
+        let line = |brain: &Brain| format!("1,cat,1,1,{}", brain.to_text());
+        writeln!(File::create(path).unwrap(), "{}\n{}", NETWORKS_HEADER, line(&fits)).unwrap();
+        assert_eq!(Ok(vec![(Species::Cat, fits.clone())]), load(path));
+
+        // 出力が1つしかないと操舵できないので、読む時に行番号つきで断る
+        let one = Brain::Neat(Genome::minimal(inputs(NEURAL_NEAREST), 1));
+        writeln!(File::create(path).unwrap(), "{}\n{}\n{}", NETWORKS_HEADER, line(&fits), line(&one)).unwrap();
+        assert!(load(path).unwrap_err().contains("line 3"));
+        let other = Brain::Neat(Genome::minimal(inputs(NEURAL_NEAREST + 1), 2));
+        writeln!(File::create(path).unwrap(), "{}", line(&other)).unwrap();
+        assert!(load(path).is_err());
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn speciate_test(){
+        let mut rng = thread_rng();
+        let mut config = Config::default();
+        config.neat.compatibility = 0.1;
+        config::set(config);
+        let genome = Genome::minimal(2, 1);
+        let far = genome.add_node(&mut rng);
+        let genomes = vec![(0, genome.clone()), (1, far.clone()), (2, genome.clone())];
+        let niches = speciate(&genomes, &[]);
+        assert_eq!(2, niches.len());
+        assert_eq!(vec![0, 2], niches[0].members);
+        assert_eq!(vec![1], niches[1].members);
+        // 前の世代の種はidを引き継ぎ、いなくなった種は消える
+        let again = speciate(&vec![(0, genome.clone())], &niches);
+        assert_eq!(1, again.len());
+        assert_eq!(niches[0].id, again[0].id);
+        config::set(Config::default());
+    }
+
+    #[test]
+    fn allot_test(){
+        let genome = Genome::minimal(1, 1);
+        let niche = |id: u64, members: Vec<usize>| Niche { id, representative: genome.clone(), members };
+        let niches = vec![niche(1, vec![0, 1]), niche(2, vec![2])];
+        let counts = allot(&niches, &[1.0, 1.0, 3.0], 10);
+        assert_eq!(10, counts.iter().sum::<usize>());
+        assert!(counts[1] > counts[0]);
+    }
+
+    #[test]
+    fn next_generation_test(){
+        let mut config = Config::default();
+        config.cat.controller = Controller::Neat;
+        config.controller.nearest = 1;
+        config::set(config);
+        let cats: Vec<Cat> = (0..6).map(|_| Cat::new()).collect();
+        let fitness = vec![1.0, 2.0, 3.0, 4.0, 5.0, 6.0];
+        let genomes: Vec<(usize, Genome)> = cats.iter().enumerate().map(|(n, cat)| match cat.brain() {
+            Some(Brain::Neat(genome)) => (n, genome),
+            _ => panic!("expected a NEAT brain"),
+        }).collect();
+        let niches = speciate(&genomes, &[]);
+        let next = next_generation(&cats, &fitness, &niches, 8);
+        assert_eq!(8, next.len());
+        let ids: Vec<u64> = cats.iter().map(|cat| cat.id()).collect();
+        assert!(next.iter().all(|cat| ids.contains(&cat.lineage().parent.unwrap())));
+        // 交叉した子はもう一方の親も系統に残す
+        assert!(next.iter().filter_map(|cat| cat.lineage().mate).all(|mate| ids.contains(&mate)));
+        assert!(next.iter().all(|cat| cat.lineage().mate != cat.lineage().parent));
+        assert!(next.iter().all(|cat| match cat.brain() { Some(Brain::Neat(_)) => true, _ => false }));
+
+        // 統計とネットワークは種ごとに一行
+        let mut out: Vec<u8> = Vec::new();
+        write_species(&mut out, 3, &cats, &fitness, &niches).unwrap();
+        let text = String::from_utf8(out).unwrap();
+        assert_eq!(niches.len(), text.lines().count());
+        assert!(text.starts_with(&format!("3,{},{},", Species::Cat.name(), niches[0].id)));
+        let mut out: Vec<u8> = Vec::new();
+        write_networks(&mut out, 3, &cats, &fitness, &niches).unwrap();
+        let text = String::from_utf8(out).unwrap();
+        let brain = text.lines().next().unwrap().split(',').nth(4).unwrap();
+        assert!(Brain::parse(brain).is_ok());
+        config::set(Config::default());
+    }
+}
@@ -0,0 +1,243 @@
+mod test;
+
+use animal::{Animal, Species};
+use config;
+use config::parse_f64;
+use consts::*;
+use neat::Genome;
+use pvector::PVector;
+use rand::prelude::*;
+use random;
+use steering::Neighbors;
+
+// 操舵のしかた
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Controller {
+    Boids,  // 設定の振る舞いを遺伝子の重みで足し合わせる
+    Neural, // 近くの個体を入力にしたニューラルネットワークの出力で進む
+    Neat,   // 入力は同じで、ネットワークの構造ごとNEATで進化させる
+}
+
+// 操舵に使うネットワーク。重みだけを進化させるものと、構造ごと進化させるもの
+#[derive(Debug, Clone, PartialEq)]
+pub enum Brain {
+    Network(Network),
+    Neat(Genome),
+}
+
+// 隠れ層が一つの全結合ネットワーク。重みがそのまま遺伝子になる
+// 重みは隠れ層のニューロンごとに入力の重みとバイアス、続いて出力ごとに隠れ層の重みとバイアスの順
+#[derive(Debug, Clone, PartialEq)]
+pub struct Network {
+    pub inputs: usize,
+    pub hidden: usize,
+    pub outputs: usize,
+    pub weights: Vec<f64>,
+}
+
+impl Controller {
+    // 設定ファイルの名前から読む
+    pub fn parse(text: &str) -> Result<Controller, String> {
+        match text {
+            "boids" => Ok(Controller::Boids),
+            "neural" => Ok(Controller::Neural),
+            "neat" => Ok(Controller::Neat),
+            _ => Err(format!("unknown controller `{}`", text)),
+        }
+    }
+}
+
+impl Network {
+    // 重みを-1から1の一様乱数で初期化
+    pub fn random(inputs: usize, hidden: usize, outputs: usize) -> Network {
+        let mut rng = random::rng();
+        Network {
+            inputs,
+            hidden,
+            outputs,
+            weights: (0..Network::weight_count(inputs, hidden, outputs))
+                .map(|_| rng.gen::<f64>() * 2.0 - 1.0)
+                .collect(),
+        }
+    }
+
+    // バイアスを含めた重みの数
+    pub fn weight_count(inputs: usize, hidden: usize, outputs: usize) -> usize {
+        (inputs + 1) * hidden + (hidden + 1) * outputs
+    }
+
+    // 入力から出力を求める。活性化関数はどちらの層もtanh
+    pub fn forward(&self, input: &[f64]) -> Vec<f64> {
+        let layer = |weights: &[f64], input: &[f64], count: usize| -> Vec<f64> {
+            weights
+                .chunks(input.len() + 1)
+                .take(count)
+                .map(|neuron| {
+                    let bias = neuron[input.len()];
+                    (neuron.iter().zip(input).map(|(w, x)| w * x).sum::<f64>() + bias).tanh()
+                })
+                .collect()
+        };
+        let split = (self.inputs + 1) * self.hidden;
+        let hidden = layer(&self.weights[..split], input, self.hidden);
+        layer(&self.weights[split..], &hidden, self.outputs)
+    }
+
+    // 「入力数 隠れ層 出力数 重み...」を空白で区切った文字列
+    pub fn to_text(&self) -> String {
+        let mut words = vec![self.inputs.to_string(), self.hidden.to_string(), self.outputs.to_string()];
+        words.extend(self.weights.iter().map(|weight| weight.to_string()));
+        words.join(" ")
+    }
+
+    // to_textで書いた文字列を読む
+    pub fn parse(text: &str) -> Result<Network, String> {
+        let words: Vec<&str> = text.split_whitespace().collect();
+        if words.len() < 3 {
+            return Err(format!("expected `inputs hidden outputs weights...` in `{}`", text));
+        }
+        let size = |word: &str| word.parse::<usize>().map_err(|_| format!("`{}` is not a size", word));
+        let (inputs, hidden, outputs) = (size(words[0])?, size(words[1])?, size(words[2])?);
+        let weights = words[3..]
+            .iter()
+            .map(|word| parse_f64(word))
+            .collect::<Result<Vec<f64>, String>>()?;
+        if weights.len() != Network::weight_count(inputs, hidden, outputs) {
+            return Err(format!("expected {} weights but got {}", Network::weight_count(inputs, hidden, outputs), weights.len()));
+        }
+        Ok(Network { inputs, hidden, outputs, weights })
+    }
+
+    // 子孫を残す時に重みを±absの範囲で変化させる
+    pub fn mutate(&self, abs: f64) -> Network {
+        let mut rng = random::rng();
+        let mut ret = self.clone();
+        for weight in ret.weights.iter_mut() {
+            *weight += rng.gen::<f64>() * abs * 2.0 - abs;
+        }
+        ret
+    }
+}
+
+impl Brain {
+    // 種の設定に合わせた新しいネットワーク。出力は進みたい向きの2つ。boidsならNone
+    pub fn new(species: Species) -> Option<Brain> {
+        let config = config::get();
+        let inputs = inputs(config.controller.nearest);
+        match config.species(species).controller {
+            Controller::Boids => None,
+            Controller::Neural => Some(Brain::Network(Network::random(inputs, config.controller.hidden, 2))),
+            Controller::Neat => Some(Brain::Neat(Genome::minimal(inputs, 2))),
+        }
+    }
+
+    // 入力から出力を求める
+    pub fn forward(&self, input: &[f64]) -> Vec<f64> {
+        match *self {
+            Brain::Network(ref network) => network.forward(input),
+            Brain::Neat(ref genome) => genome.activate(input),
+        }
+    }
+
+    // 子孫を残す時の突然変異
+    pub fn mutate(&self) -> Brain {
+        match *self {
+            Brain::Network(ref network) => Brain::Network(network.mutate(config::get().controller.mutation)),
+            Brain::Neat(ref genome) => Brain::Neat(genome.mutate()),
+        }
+    }
+
+    // 種類の名前に続けて中身を書いた文字列
+    pub fn to_text(&self) -> String {
+        match *self {
+            Brain::Network(ref network) => format!("network {}", network.to_text()),
+            Brain::Neat(ref genome) => format!("neat {}", genome.to_text()),
+        }
+    }
+
+    // 読み直したネットワークが今の設定で操舵に使えるか。入力と出力の数が合わなければ操舵の途中で落ちる
+    pub fn check(&self) -> Result<(), String> {
+        let (inputs, outputs) = match *self {
+            Brain::Network(ref network) => (network.inputs, network.outputs),
+            Brain::Neat(ref genome) => (genome.inputs, genome.outputs),
+        };
+        let expected = self::inputs(config::get().controller.nearest);
+        if inputs != expected {
+            return Err(format!("expected {} inputs for nearest = {} but got {}", expected, config::get().controller.nearest, inputs));
+        }
+        if outputs != 2 {
+            return Err(format!("expected 2 outputs but got {}", outputs));
+        }
+        Ok(())
+    }
+
+    // to_textで書いた文字列を読む
+    pub fn parse(text: &str) -> Result<Brain, String> {
+        let text = text.trim();
+        let split = text.find(char::is_whitespace).unwrap_or(text.len());
+        match &text[..split] {
+            "network" => Ok(Brain::Network(Network::parse(&text[split..])?)),
+            "neat" => Ok(Brain::Neat(Genome::parse(&text[split..])?)),
+            kind => Err(format!("unknown brain `{}`", kind)),
+        }
+    }
+}
+
+// 入力の数。自分の速さと、猫とねずみそれぞれ近いnearest匹の相対位置と相対速度
+pub fn inputs(nearest: usize) -> usize {
+    1 + 2 * nearest * 4
+}
+
+// ネットワークの入力。向きを基準にした座標で、距離は感じ取る範囲、速さは最高速で割る
+// 見えている数がnearestに足りなければ0で埋める
+pub fn sense<A: Animal>(animal: &A, neighbors: &Neighbors) -> Vec<f64> {
+    let config = config::get();
+    let controller = &config.controller;
+    let velocity = animal.as_velocity();
+    let heading = velocity.y.atan2(velocity.x);
+    let max_speed = config.species(animal.species()).motion.max_speed;
+    let boundary = neighbors.boundary();
+    let mut ret = vec![velocity.len() / max_speed];
+    for &species in &[Species::Rat, Species::Cat] {
+        let mut seen: Vec<(PVector, PVector)> = match species {
+            Species::Cat => animal
+                .collect_near_pvectors(neighbors.cats, neighbors, controller.radious)
+                .into_iter()
+                .map(|other| (animal.offset(&other, boundary), other.as_velocity()))
+                .collect(),
+            Species::Rat => animal
+                .collect_near_pvectors(neighbors.rats, neighbors, controller.radious)
+                .into_iter()
+                .map(|other| (animal.offset(&other, boundary), other.as_velocity()))
+                .collect(),
+        };
+        seen.sort_by(|a, b| a.0.len().partial_cmp(&b.0.len()).unwrap_or(std::cmp::Ordering::Equal));
+        for n in 0..controller.nearest {
+            match seen.get(n) {
+                Some(&(ref offset, ref other)) => {
+                    let offset = offset.rotate(-heading).mult(1.0 / controller.radious);
+                    let relative = other.add(velocity.mult(-1.0)).rotate(-heading).mult(1.0 / max_speed);
+                    ret.extend_from_slice(&[offset.x, offset.y, relative.x, relative.y]);
+                }
+                None => ret.extend_from_slice(&[0.0; 4]),
+            }
+        }
+    }
+    ret
+}
+
+// ネットワークの出力を向きを基準にした力として世界の座標に戻す。柔らかい壁の力も足す
+pub fn steer<A: Animal>(animal: &A, brain: &Brain, neighbors: &Neighbors) -> PVector {
+    let config = config::get();
+    let velocity = animal.as_velocity();
+    let heading = velocity.y.atan2(velocity.x);
+    let output = brain.forward(&sense(animal, neighbors));
+    let wall = config
+        .boundary
+        .wall_force(&animal.position(), config.wall_margin)
+        .mult(config.wall_weight);
+    PVector::new(output[0], output[1])
+        .rotate(heading)
+        .mult(NEURAL_FORCE)
+        .add(wall)
+}
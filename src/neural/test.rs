@@ -0,0 +1,115 @@
+#[cfg(test)]
+mod tests{
+    use animal::{Animal, Cat, Rat};
+    use config;
+    use config::Config;
+    use neural::{Brain, Controller, Network, inputs, sense, steer};
+    use pvector::PVector;
+    use quad_tree::QuadTree;
+    use steering::Neighbors;
+
+    fn neural_config() -> Config {
+        let mut config = Config::default();
+        config.cat.controller = Controller::Neural;
+        config.controller.nearest = 1;
+        config.controller.hidden = 2;
+        config
+    }
+
+    #[test]
+    fn parse_test(){
+        assert_eq!(Ok(Controller::Neural), Controller::parse("neural"));
+        assert_eq!(Ok(Controller::Neat), Controller::parse("neat"));
+        assert!(Controller::parse("fuzzy").is_err());
+
+        let network = Network::random(3, 2, 2);
+        assert_eq!(Ok(network.clone()), Network::parse(&network.to_text()));
+        assert!(Network::parse("3 2").is_err());
+        assert!(Network::parse("1 1 1 0.5").is_err());
+        let brain = Brain::Network(network);
+        assert_eq!(Ok(brain.clone()), Brain::parse(&brain.to_text()));
+        assert!(Brain::parse("fuzzy 3 2 2").is_err());
+    }
+
+    #[test]
+    fn check_test(){
+        config::set(neural_config());
+        // 隠れ層の大きさは違ってもよいが、入力と出力の数は設定に合わせる
+        assert!(Brain::Network(Network::random(inputs(1), 5, 2)).check().is_ok());
+        assert!(Brain::Network(Network::random(inputs(2), 2, 2)).check().is_err());
+        assert!(Brain::Network(Network::random(inputs(1), 2, 1)).check().is_err());
+        config::set(Config::default());
+    }
+
+    #[test]
+    fn forward_test(){
+        let network = Network::random(4, 3, 2);
+        assert_eq!(Network::weight_count(4, 3, 2), network.weights.len());
+        assert!(network.weights.iter().all(|&weight| -1.0 <= weight && weight <= 1.0));
+
+        // 入力1つ、隠れ層1つ、出力1つ。重みは[入力→隠れ, バイアス, 隠れ→出力, バイアス]
+        let network = Network { inputs: 1, hidden: 1, outputs: 1, weights: vec![2.0, 0.5, -1.0, 0.25] };
+        let hidden = (2.0 * 0.3 + 0.5_f64).tanh();
+        let output = network.forward(&[0.3]);
+        assert_eq!(1, output.len());
+        assert!((output[0] - (-hidden + 0.25).tanh()).abs() < 1.0e-12);
+
+        let mutated = network.mutate(0.1);
+        assert!(mutated.weights.iter().zip(&network.weights).all(|(a, b)| (a - b).abs() <= 0.1));
+    }
+
+    #[test]
+    fn cat_brain_test(){
+        // boidsなら猫はネットワークを持たない
+        assert!(Cat::new().brain.is_none());
+        config::set(neural_config());
+        let cat = Cat::new();
+        let brain = cat.brain.clone().unwrap();
+        match brain {
+            Brain::Network(ref network) => {
+                assert_eq!(inputs(1), network.inputs);
+                assert_eq!(Network::weight_count(inputs(1), 2, 2), network.weights.len());
+            }
+            Brain::Neat(_) => panic!("expected a fixed network"),
+        }
+        // 子孫は少し変わったネットワークを、同じ遺伝子の猫は同じネットワークを持つ
+        assert_ne!(Some(brain.clone()), cat.descendant().brain);
+        assert_eq!(Some(brain), cat.twin().brain);
+        // 猫だけneuralにしたのでねずみは持たない
+        assert!(Rat::new().brain.is_none());
+        config::set(Config::default());
+    }
+
+    #[test]
+    fn sense_test(){
+        config::set(neural_config());
+        let cat = Cat::new()
+            .set_position(&PVector::new(100.5, 100.5))
+            .apply_velocity(&PVector::new(0.0, 10.0));
+        let rat = Rat::new()
+            .set_position(&PVector::new(100.5, 120.5))
+            .apply_velocity(&PVector::new(0.0, 0.0));
+        let cats_tree = QuadTree::new(&vec![cat.clone()]);
+        let rats_tree = QuadTree::new(&vec![rat]);
+        let neighbors = Neighbors { cats: &cats_tree, rats: &rats_tree };
+        let input = sense(&cat, &neighbors);
+        assert_eq!(inputs(1), input.len());
+        // 真正面のねずみは向きを基準にするとx軸の上にいる
+        let radious = config::get().controller.radious;
+        assert!((input[1].abs() - 20.0 / radious).abs() < 1.0e-9);
+        assert!(input[2].abs() < 1.0e-9);
+        // 見えている猫がいなければ0で埋める
+        assert!(input[5..].iter().all(|&value| value == 0.0));
+
+        // 前へ進もうとする出力は猫の向きに合わせて回る。重みは0で、1つ目の出力のバイアスだけ1
+        let mut weights = vec![0.0; Network::weight_count(inputs(1), 1, 2)];
+        let bias = weights.len() - 3;
+        weights[bias] = 1.0;
+        let mut cat = cat;
+        cat.brain = Some(Brain::Network(Network { inputs: inputs(1), hidden: 1, outputs: 2, weights }));
+        let force = steer(&cat, cat.brain.as_ref().unwrap(), &neighbors);
+        assert!(force.x.abs() < 1.0e-9);
+        assert!(force.y > 0.0);
+        config::set(Config::default());
+    }
+}
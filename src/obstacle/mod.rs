@@ -0,0 +1,233 @@
+mod test;
+
+use animal::Species;
+use boundary::Boundary;
+use config;
+use config::Config;
+use config::parse_f64;
+use pvector::PVector;
+
+// 動物が避けて通る障害物。隠れ家も同じ形で表す
+#[derive(Debug, Clone)]
+pub enum Obstacle {
+    Circle {
+        center: PVector,
+        radious: f64,
+    },
+    // 頂点を順に並べた多角形
+    Polygon {
+        points: Vec<PVector>,
+    },
+}
+
+impl Obstacle {
+    // 「circle x y 半径」か「polygon x1 y1 x2 y2 x3 y3 ...」の形式を読む
+    pub fn parse(text: &str) -> Result<Obstacle, String> {
+        let words: Vec<&str> = text.split_whitespace().collect();
+        match words.first() {
+            Some(&"circle") if words.len() == 4 => {
+                let radious = parse_f64(words[3])?;
+                if radious <= 0.0 {
+                    return Err(format!("radious `{}` must be positive", words[3]));
+                }
+                Ok(Obstacle::Circle {
+                    center: PVector::new(parse_f64(words[1])?, parse_f64(words[2])?),
+                    radious,
+                })
+            },
+            Some(&"circle") => Err(String::from("expected `circle x y radious`")),
+            Some(&"polygon") if words.len() >= 7 && words.len() % 2 == 1 => {
+                let mut points = Vec::with_capacity(words.len() / 2);
+                for pair in words[1..].chunks(2) {
+                    points.push(PVector::new(parse_f64(pair[0])?, parse_f64(pair[1])?));
+                }
+                Ok(Obstacle::Polygon { points })
+            },
+            Some(&"polygon") => Err(String::from("expected `polygon x1 y1 x2 y2 x3 y3 ...`")),
+            _ => Err(format!("unknown obstacle `{}`", text)),
+        }
+    }
+    
+    // 表面までの距離。内側なら負になる
+    pub fn distance(&self, point: &PVector, boundary: Boundary) -> f64 {
+        match *self {
+            Obstacle::Circle { ref center, radious } => center.offset(point, boundary).len() - radious,
+            Obstacle::Polygon { ref points } => {
+                let local = Obstacle::local(points, point, boundary);
+                let dist = Obstacle::closest_edge(points, &local).offset(&local, boundary).len();
+                if Obstacle::contains(points, &local) { -dist } else { dist }
+            },
+        }
+    }
+    
+    // 表面から点へ向かう外向きの単位ベクトル
+    pub fn normal(&self, point: &PVector, boundary: Boundary) -> PVector {
+        let outward = match *self {
+            Obstacle::Circle { ref center, .. } => center.offset(point, boundary),
+            Obstacle::Polygon { ref points } => {
+                let local = Obstacle::local(points, point, boundary);
+                let to_point = Obstacle::closest_edge(points, &local).offset(&local, boundary);
+                if Obstacle::contains(points, &local) { to_point.mult(-1.0) } else { to_point }
+            },
+        };
+        if outward.len() == 0.0 {
+            PVector::new(1.0, 0.0)
+        } else {
+            outward.normalize()
+        }
+    }
+    
+    // 多角形の最初の頂点に近い側の点の位置。画面の端をまたぐ多角形でも普通に計算できる
+    fn local(points: &Vec<PVector>, point: &PVector, boundary: Boundary) -> PVector {
+        points[0].add(points[0].offset(point, boundary))
+    }
+    
+    // 多角形の辺の上で一番近い点
+    fn closest_edge(points: &Vec<PVector>, point: &PVector) -> PVector {
+        let mut nearest = points[0].clone();
+        let mut nearest_dist = std::f64::MAX;
+        for n in 0..points.len() {
+            let a = &points[n];
+            let b = &points[(n + 1) % points.len()];
+            let edge = PVector::new(b.x - a.x, b.y - a.y);
+            let to_point = PVector::new(point.x - a.x, point.y - a.y);
+            let t = if edge.len() == 0.0 {
+                0.0
+            } else {
+                (to_point.dot(&edge) / edge.dot(&edge)).max(0.0).min(1.0)
+            };
+            let candidate = a.add(edge.mult(t));
+            let dist = PVector::new(point.x - candidate.x, point.y - candidate.y).len();
+            if dist < nearest_dist {
+                nearest = candidate;
+                nearest_dist = dist;
+            }
+        }
+        nearest
+    }
+    
+    // 点から右へ伸ばした半直線が辺と交わる回数で内側かどうかを判定する
+    fn contains(points: &Vec<PVector>, point: &PVector) -> bool {
+        let mut inside = false;
+        for n in 0..points.len() {
+            let a = &points[n];
+            let b = &points[(n + 1) % points.len()];
+            if (a.y > point.y) != (b.y > point.y) {
+                let cross_x = a.x + (point.y - a.y) / (b.y - a.y) * (b.x - a.x);
+                if point.x < cross_x {
+                    inside = !inside;
+                }
+            }
+        }
+        inside
+    }
+    
+    // fromからoffsetだけ進む線分が障害物を横切るかどうか
+    pub fn crosses(&self, from: &PVector, offset: &PVector, boundary: Boundary) -> bool {
+        match *self {
+            Obstacle::Circle { ref center, radious } => {
+                let to_center = from.offset(center, boundary);
+                let t = if offset.len() == 0.0 {
+                    0.0
+                } else {
+                    (to_center.dot(offset) / offset.dot(offset)).max(0.0).min(1.0)
+                };
+                PVector::new(to_center.x - offset.x * t, to_center.y - offset.y * t).len() < radious
+            },
+            Obstacle::Polygon { ref points } => {
+                let start = Obstacle::local(points, from, boundary);
+                let end = start.add(offset.clone());
+                Obstacle::contains(points, &start) || (0..points.len()).any(|n| {
+                    Obstacle::segments_cross(&start, &end, &points[n], &points[(n + 1) % points.len()])
+                })
+            },
+        }
+    }
+    
+    // 線分abと線分cdが交わるかどうか
+    fn segments_cross(a: &PVector, b: &PVector, c: &PVector, d: &PVector) -> bool {
+        let side = |p: &PVector, q: &PVector, r: &PVector| PVector::new(q.x - p.x, q.y - p.y).cross(&PVector::new(r.x - p.x, r.y - p.y));
+        let (d1, d2) = (side(a, b, c), side(a, b, d));
+        let (d3, d4) = (side(c, d, a), side(c, d, b));
+        (d1 > 0.0) != (d2 > 0.0) && (d3 > 0.0) != (d4 > 0.0)
+    }
+    
+    // 表面の外側へ押し戻した位置と、障害物へ向かう成分を除いた速度
+    pub fn push_out(&self, position: &PVector, velocity: &PVector, boundary: Boundary) -> (PVector, PVector) {
+        let depth = self.distance(position, boundary);
+        if depth >= 0.0 {
+            return (position.clone(), velocity.clone());
+        }
+        let normal = self.normal(position, boundary);
+        let into = velocity.dot(&normal).min(0.0);
+        (position.add(normal.mult(-depth)), velocity.add(normal.mult(-into)))
+    }
+}
+
+// 多角形を耳を一つずつ切り落として三角形に分け、頂点の添字の組を返す。凹んだ多角形にも使える
+// 辺が交わっていて耳が見つからなければ、残りは最初の頂点から扇形に分ける
+pub fn triangulate(points: &Vec<PVector>) -> Vec<[usize; 3]> {
+    let count = points.len();
+    let area: f64 = (0..count).map(|n| points[n].cross(&points[(n + 1) % count])).sum();
+    let turn = |a: usize, b: usize, c: usize| -> f64 {
+        let ab = PVector::new(points[b].x - points[a].x, points[b].y - points[a].y);
+        let bc = PVector::new(points[c].x - points[b].x, points[c].y - points[b].y);
+        ab.cross(&bc) * area.signum()
+    };
+    let mut rest: Vec<usize> = (0..count).collect();
+    let mut ret = Vec::with_capacity(count.saturating_sub(2));
+    while rest.len() > 3 {
+        let size = rest.len();
+        let ear = (0..size).find(|&n| {
+            let (a, b, c) = (rest[(n + size - 1) % size], rest[n], rest[(n + 1) % size]);
+            turn(a, b, c) > 0.0 && rest
+                .iter()
+                .filter(|&&p| p != a && p != b && p != c)
+                .all(|&p| turn(a, b, p) < 0.0 || turn(b, c, p) < 0.0 || turn(c, a, p) < 0.0)
+        });
+        match ear {
+            Some(n) => {
+                ret.push([rest[(n + size - 1) % size], rest[n], rest[(n + 1) % size]]);
+                rest.remove(n);
+            },
+            None => break,
+        }
+    }
+    for n in 1..rest.len().saturating_sub(1) {
+        ret.push([rest[0], rest[n], rest[n + 1]]);
+    }
+    ret
+}
+
+// 種が通り抜けられない障害物の一覧。猫には隠れ家も障害物になる
+pub fn blocking<'a>(config: &'a Config, species: Species) -> impl Iterator<Item = &'a Obstacle> + 'a {
+    let refuges = if species == Species::Cat { config.refuges.len() } else { 0 };
+    config.obstacles.iter().chain(config.refuges.iter().take(refuges))
+}
+
+// 障害物にめり込んだ位置と速度を直す
+pub fn collide(species: Species, position: &PVector, velocity: &PVector) -> (PVector, PVector) {
+    let config = config::get();
+    blocking(&config, species)
+        .fold((position.clone(), velocity.clone()), |(position, velocity), obstacle| {
+            obstacle.push_out(&position, &velocity, config.boundary)
+        })
+}
+
+// 障害物が視線を遮るかどうか。隠れ家は入れる場所なので遮らない
+pub fn blocks_sight(from: &PVector, offset: &PVector) -> bool {
+    let config = config::get();
+    config
+        .obstacles
+        .iter()
+        .any(|obstacle| obstacle.crosses(from, offset, config.boundary))
+}
+
+// 隠れ家の中にいるかどうか。隠れ家の中のねずみは食べられない
+pub fn in_refuge(position: &PVector) -> bool {
+    let config = config::get();
+    config
+        .refuges
+        .iter()
+        .any(|refuge| refuge.distance(position, config.boundary) < 0.0)
+}
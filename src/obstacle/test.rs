@@ -0,0 +1,182 @@
+#[cfg(test)]
+mod tests{
+    use boundary::Boundary;
+    use animal::{Animal, Cat, Rat, Species};
+    use config;
+    use config::Config;
+    use consts::*;
+    use obstacle::{Obstacle, blocking, collide, in_refuge, triangulate};
+    use predation;
+    use quad_tree::QuadTree;
+    use pvector::PVector;
+    
+    macro_rules! assert_float{
+        (
+            $x: expr ,$y: expr
+        ) => {
+            {
+                assert!((($x - $y) / $x).abs() < 1.0e-9);
+            }
+        }
+    }
+    
+    fn circle(x: f64, y: f64, radious: f64) -> Obstacle {
+        Obstacle::Circle { center: PVector::new(x, y), radious }
+    }
+    
+    #[test]
+    fn parse_test(){
+        match Obstacle::parse("circle 10 20.5 3").unwrap() {
+            Obstacle::Circle { center, radious } => {
+                assert_eq!(center, PVector::new(10.0, 20.5));
+                assert_eq!(radious, 3.0);
+            },
+            _ => panic!("not a circle"),
+        }
+        assert!(Obstacle::parse("circle 10 20").is_err());
+        assert!(Obstacle::parse("circle 10 20 -1").is_err());
+        match Obstacle::parse("polygon 0 0 10 0 10 10").unwrap() {
+            Obstacle::Polygon { points } => assert_eq!(points, vec![
+                PVector::new(0.0, 0.0), PVector::new(10.0, 0.0), PVector::new(10.0, 10.0),
+            ]),
+            _ => panic!("not a polygon"),
+        }
+        assert!(Obstacle::parse("polygon 0 0 10 0").is_err());
+        assert!(Obstacle::parse("polygon 0 0 10 0 10").is_err());
+        assert!(Obstacle::parse("polygon 0 0 10 0 10 x").is_err());
+        assert!(Obstacle::parse("square 10 20 3").is_err());
+    }
+    
+    #[test]
+    fn circle_distance_test(){
+        let obstacle = circle(100.0, 100.0, 10.0);
+        assert_float!(5.0, obstacle.distance(&PVector::new(115.0, 100.0), Boundary::Torus));
+        assert_float!(-10.0, obstacle.distance(&PVector::new(100.0, 100.0), Boundary::Torus));
+        assert_float!(-6.0, obstacle.distance(&PVector::new(100.0, 104.0), Boundary::Torus));
+        // 画面の端をまたいで測る
+        let edge = circle(2.0, 100.0, 5.0);
+        assert_float!(2.0, edge.distance(&PVector::new(WIDTH - 5.0, 100.0), Boundary::Torus));
+    }
+    
+    #[test]
+    fn circle_normal_test(){
+        let obstacle = circle(100.0, 100.0, 10.0);
+        assert_eq!(PVector::new(0.0, -1.0), obstacle.normal(&PVector::new(100.0, 80.0), Boundary::Torus));
+        assert_eq!(PVector::new(1.0, 0.0), obstacle.normal(&PVector::new(105.0, 100.0), Boundary::Torus));
+        // 中心では向きが決まらないので右向きにする
+        assert_eq!(PVector::new(1.0, 0.0), obstacle.normal(&PVector::new(100.0, 100.0), Boundary::Torus));
+    }
+    
+    fn square(x: f64, y: f64, size: f64) -> Obstacle {
+        Obstacle::Polygon { points: vec![
+            PVector::new(x, y),
+            PVector::new(x + size, y),
+            PVector::new(x + size, y + size),
+            PVector::new(x, y + size),
+        ] }
+    }
+    
+    #[test]
+    fn polygon_distance_test(){
+        let obstacle = square(100.0, 100.0, 20.0);
+        assert_float!(5.0, obstacle.distance(&PVector::new(125.0, 110.0), Boundary::Torus));
+        assert_float!(-4.0, obstacle.distance(&PVector::new(104.0, 110.0), Boundary::Torus));
+        // 角の外側は頂点までの距離
+        assert_float!(5.0, obstacle.distance(&PVector::new(97.0, 96.0), Boundary::Torus));
+        // 画面の端をまたぐ多角形
+        let edge = square(WIDTH - 5.0, 100.0, 10.0);
+        assert_float!(-2.0, edge.distance(&PVector::new(3.0, 105.0), Boundary::Torus));
+        assert_float!(3.0, edge.distance(&PVector::new(8.0, 105.0), Boundary::Torus));
+    }
+    
+    #[test]
+    fn polygon_normal_test(){
+        let obstacle = square(100.0, 100.0, 20.0);
+        assert_eq!(PVector::new(1.0, 0.0), obstacle.normal(&PVector::new(125.0, 110.0), Boundary::Torus));
+        // 内側でも一番近い辺から外へ向く
+        assert_eq!(PVector::new(0.0, -1.0), obstacle.normal(&PVector::new(110.0, 102.0), Boundary::Torus));
+    }
+    
+    #[test]
+    fn push_out_test(){
+        let obstacle = circle(100.0, 100.0, 10.0);
+        let (position, velocity) = obstacle.push_out(&PVector::new(107.0, 100.0), &PVector::new(-2.0, 1.0), Boundary::Torus);
+        assert_float!(110.0, position.x);
+        assert_float!(100.0, position.y);
+        // 表面に沿った速度だけ残る
+        assert_eq!(PVector::new(0.0, 1.0), velocity);
+        
+        // 外にいれば何もしない
+        let (position, velocity) = obstacle.push_out(&PVector::new(120.0, 100.0), &PVector::new(-2.0, 1.0), Boundary::Torus);
+        assert_eq!(PVector::new(120.0, 100.0), position);
+        assert_eq!(PVector::new(-2.0, 1.0), velocity);
+    }
+    
+    #[test]
+    fn refuge_test(){
+        let mut refuge = Config::default();
+        refuge.refuges.push(circle(100.0, 100.0, 10.0));
+        config::set(refuge);
+        
+        assert!(in_refuge(&PVector::new(105.0, 100.0)));
+        assert!(!in_refuge(&PVector::new(115.0, 100.0)));
+        // 隠れ家は猫だけを通さない
+        assert_eq!(1, blocking(&config::get(), Species::Cat).count());
+        assert_eq!(0, blocking(&config::get(), Species::Rat).count());
+        let (cat_position, _) = collide(Species::Cat, &PVector::new(105.0, 100.0), &PVector::new(-1.0, 0.0));
+        let (rat_position, _) = collide(Species::Rat, &PVector::new(105.0, 100.0), &PVector::new(-1.0, 0.0));
+        assert_float!(110.0, cat_position.x);
+        assert_eq!(105.0, rat_position.x);
+        
+        // 隠れ家の中のねずみは食べられない
+        let rat = Rat::new().set_position(&PVector::new(105.5, 100.5));
+        let cat = Cat::new().set_position(&PVector::new(105.8, 100.5));
+        let cats_tree = QuadTree::new(&vec![cat.clone()]);
+        let rats_tree = QuadTree::new(&vec![rat.clone()]);
+        let predation = predation::resolve(&vec![cat], &vec![rat], &cats_tree, &rats_tree);
+        assert_eq!(1, predation.rats.len());
+        assert_eq!(0, predation.cats[0].ate());
+        assert_eq!(0, predation.kills.len());
+        config::set(Config::default());
+    }
+    
+    #[test]
+    fn triangulate_test(){
+        // L字の凹んだ多角形。どちらの向きに頂点を並べても、中に収まる4つの三角形で面積が合う
+        // 最初の頂点から扇形に分けると、はみ出す三角形ができる並び
+        let text = "polygon 130 110 110 110 110 130 100 130 100 100 130 100";
+        let mut points = match Obstacle::parse(text).unwrap() {
+            Obstacle::Polygon { points } => points,
+            _ => panic!("not a polygon"),
+        };
+        for _ in 0..2 {
+            let obstacle = Obstacle::Polygon { points: points.clone() };
+            let triangles = triangulate(&points);
+            assert_eq!(4, triangles.len());
+            let mut area = 0.0;
+            for triangle in &triangles {
+                let (a, b, c) = (&points[triangle[0]], &points[triangle[1]], &points[triangle[2]]);
+                let ab = PVector::new(b.x - a.x, b.y - a.y);
+                let ac = PVector::new(c.x - a.x, c.y - a.y);
+                area += ab.cross(&ac).abs() / 2.0;
+                let center = PVector::new((a.x + b.x + c.x) / 3.0, (a.y + b.y + c.y) / 3.0);
+                assert!(obstacle.distance(&center, Boundary::Torus) < 0.0);
+            }
+            assert_float!(500.0, area);
+            points.reverse();
+        }
+    }
+    
+    #[test]
+    fn crosses_test(){
+        let from = PVector::new(100.0, 100.0);
+        let round = circle(105.0, 101.0, 2.0);
+        assert!(round.crosses(&from, &PVector::new(10.0, 0.0), Boundary::Torus));
+        assert!(!round.crosses(&from, &PVector::new(2.0, 0.0), Boundary::Torus));
+        assert!(!round.crosses(&from, &PVector::new(0.0, 10.0), Boundary::Torus));
+        let square = Obstacle::parse("polygon 104 98 106 98 106 102 104 102").unwrap();
+        assert!(square.crosses(&from, &PVector::new(10.0, 0.0), Boundary::Torus));
+        assert!(!square.crosses(&from, &PVector::new(3.0, 0.0), Boundary::Torus));
+        assert!(!square.crosses(&from, &PVector::new(10.0, 10.0), Boundary::Torus));
+    }
+}
@@ -0,0 +1,116 @@
+mod test;
+
+use fitness::Measure;
+
+// 猫の選び方
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Selection {
+    Weighted, // 適応度を重みつきで足した値の高い順
+    Pareto,   // 複数の目的でNSGA-IIのように選ぶ
+}
+
+impl Selection {
+    // 設定ファイルの名前から読む
+    pub fn parse(text: &str) -> Result<Selection, String> {
+        match text {
+            "weighted" => Ok(Selection::Weighted),
+            "pareto" => Ok(Selection::Pareto),
+            _ => Err(format!("unknown selection `{}`", text)),
+        }
+    }
+}
+
+// 「;」で区切られた目的の一覧を読む
+pub fn parse_objectives(text: &str) -> Result<Vec<Measure>, String> {
+    let list = text
+        .split(';')
+        .filter(|item| !item.trim().is_empty())
+        .map(Measure::parse)
+        .collect::<Result<Vec<Measure>, String>>()?;
+    if list.is_empty() {
+        return Err(String::from("no objective"));
+    }
+    Ok(list)
+}
+
+// aがbを支配しているか。どの目的でも劣らず、どれかで勝っている
+pub fn dominates(a: &Vec<f64>, b: &Vec<f64>) -> bool {
+    a.iter().zip(b).all(|(x, y)| x >= y) && a.iter().zip(b).any(|(x, y)| x > y)
+}
+
+// 非優越ソート。支配されていない個体の添字から順にフロントに分ける
+pub fn fronts(scores: &Vec<Vec<f64>>) -> Vec<Vec<usize>> {
+    let n = scores.len();
+    let mut dominated_by: Vec<Vec<usize>> = vec![Vec::new(); n]; // 自分が支配している個体
+    let mut counts: Vec<usize> = vec![0; n]; // 自分を支配している個体の数
+    for i in 0..n {
+        for j in 0..n {
+            if dominates(&scores[i], &scores[j]) {
+                dominated_by[i].push(j);
+            } else if dominates(&scores[j], &scores[i]) {
+                counts[i] += 1;
+            }
+        }
+    }
+    let mut ret = Vec::new();
+    let mut front: Vec<usize> = (0..n).filter(|&i| counts[i] == 0).collect();
+    while !front.is_empty() {
+        let mut next = Vec::new();
+        for &i in &front {
+            for &j in &dominated_by[i] {
+                counts[j] -= 1;
+                if counts[j] == 0 {
+                    next.push(j);
+                }
+            }
+        }
+        ret.push(front);
+        front = next;
+    }
+    ret
+}
+
+// フロントの中での混雑距離。目的ごとに両隣との差を値の幅で割って足し、端の個体は無限大
+pub fn crowding_distance(scores: &Vec<Vec<f64>>, front: &Vec<usize>) -> Vec<f64> {
+    let mut distance = vec![0.0; front.len()];
+    let objectives = match front.first() {
+        Some(&first) => scores[first].len(),
+        None => return distance,
+    };
+    for m in 0..objectives {
+        let mut order: Vec<usize> = (0..front.len()).collect();
+        order.sort_by(|&a, &b| {
+            scores[front[a]][m]
+                .partial_cmp(&scores[front[b]][m])
+                .unwrap_or(std::cmp::Ordering::Equal)
+        });
+        let low = scores[front[order[0]]][m];
+        let high = scores[front[order[order.len() - 1]]][m];
+        distance[order[0]] = std::f64::INFINITY;
+        distance[order[order.len() - 1]] = std::f64::INFINITY;
+        if high - low <= 0.0 {
+            continue;
+        }
+        for k in 1..order.len().saturating_sub(1) {
+            let gap = scores[front[order[k + 1]]][m] - scores[front[order[k - 1]]][m];
+            distance[order[k]] += gap / (high - low);
+        }
+    }
+    distance
+}
+
+// NSGA-IIの選択。前のフロントから詰め、各フロントの中は混雑距離の大きい順に並べるので先頭から切り詰めてよい
+pub fn select(scores: &Vec<Vec<f64>>, count: usize) -> Vec<usize> {
+    let mut ret = Vec::with_capacity(count);
+    for front in fronts(scores) {
+        if ret.len() >= count {
+            break;
+        }
+        let distance = crowding_distance(scores, &front);
+        let mut order: Vec<usize> = (0..front.len()).collect();
+        order.sort_by(|&a, &b| distance[b].partial_cmp(&distance[a]).unwrap_or(std::cmp::Ordering::Equal));
+        let room = count - ret.len();
+        ret.extend(order.into_iter().take(room).map(|k| front[k]));
+    }
+    ret
+}
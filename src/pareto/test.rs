@@ -0,0 +1,78 @@
+#[cfg(test)]
+mod tests{
+    use fitness::{Kills, Measure};
+    use pareto::*;
+
+    #[test]
+    fn dominates_test(){
+        assert!(dominates(&vec![2.0, 1.0], &vec![1.0, 1.0]));
+        assert!(!dominates(&vec![1.0, 1.0], &vec![1.0, 1.0]));
+        assert!(!dominates(&vec![2.0, 0.0], &vec![1.0, 1.0]));
+    }
+
+    #[test]
+    fn fronts_test(){
+        // 0と1と2は互いに支配せず、3は1に、4は3に支配される
+        let scores = vec![
+            vec![3.0, 1.0],
+            vec![2.0, 2.0],
+            vec![1.0, 3.0],
+            vec![1.5, 1.5],
+            vec![1.0, 1.0],
+        ];
+        let fronts = fronts(&scores);
+        assert_eq!(3, fronts.len());
+        let mut first = fronts[0].clone();
+        first.sort();
+        assert_eq!(vec![0, 1, 2], first);
+        assert_eq!(vec![3], fronts[1]);
+        assert_eq!(vec![4], fronts[2]);
+    }
+
+    #[test]
+    fn crowding_distance_test(){
+        let scores = vec![
+            vec![0.0, 4.0],
+            vec![1.0, 3.0],
+            vec![3.0, 1.0],
+            vec![4.0, 0.0],
+        ];
+        let distance = crowding_distance(&scores, &vec![0, 1, 2, 3]);
+        // 端は無限大、内側は両隣の差を幅で割った和
+        assert!(distance[0].is_infinite());
+        assert!(distance[3].is_infinite());
+        assert!((distance[1] - 1.5).abs() < 1.0e-9);
+        assert!((distance[2] - 1.5).abs() < 1.0e-9);
+        assert_eq!(0, crowding_distance(&scores, &vec![]).len());
+    }
+
+    #[test]
+    fn select_test(){
+        let scores = vec![
+            vec![0.0, 4.0],
+            vec![1.0, 3.0],
+            vec![1.5, 2.5],
+            vec![4.0, 0.0],
+            vec![0.5, 0.5],
+        ];
+        // 第1フロントだけで足りなければ端から選ぶ
+        let mut selected = select(&scores, 2);
+        selected.sort();
+        assert_eq!(vec![0, 3], selected);
+        // 第1フロントを全部入れてから次のフロントへ
+        let mut selected = select(&scores, 5);
+        selected.sort();
+        assert_eq!(vec![0, 1, 2, 3, 4], selected);
+        assert_eq!(4, select(&scores, 4).len());
+        assert!(!select(&scores, 4).contains(&4));
+    }
+
+    #[test]
+    fn parse_test(){
+        assert_eq!(Ok(Selection::Pareto), Selection::parse("pareto"));
+        assert!(Selection::parse("tournament").is_err());
+        assert_eq!(Ok(vec![Measure::Kills(Kills)]), parse_objectives("kills;"));
+        assert!(parse_objectives("").is_err());
+        assert!(parse_objectives("kills; luck").is_err());
+    }
+}
@@ -0,0 +1,152 @@
+mod test;
+
+use animal::{Animal, Cat, Rat};
+use boundary::Boundary;
+use config;
+use consts::*;
+use obstacle;
+use pvector::PVector;
+use quad_tree::QuadTree;
+use rand::prelude::*;
+use random;
+use std::collections::HashMap;
+
+// 捕まえたねずみを誰の手柄にするか
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Credit {
+    Nearest, // 一番近い猫
+    Random,  // 届く猫のうちランダムに一匹
+}
+
+// 誰が誰を食べたか
+#[derive(Debug, Clone)]
+pub struct Kill {
+    pub cat: u64,
+    pub rat: u64,
+    pub position: PVector, // 食べられた場所
+}
+
+// 1ステップの捕食の結果
+pub struct Predation {
+    pub cats: Vec<Cat>, // 食べた分のエネルギーを受け取った猫
+    pub rats: Vec<Rat>, // 食べられなかったねずみ
+    pub kills: Vec<Kill>,
+    pub misses: usize, // 襲ったが逃げられた回数
+}
+
+impl Credit {
+    // 設定ファイルの名前から読む
+    pub fn parse(text: &str) -> Result<Credit, String> {
+        match text {
+            "nearest" => Ok(Credit::Nearest),
+            "random" => Ok(Credit::Random),
+            _ => Err(format!("unknown credit `{}`", text)),
+        }
+    }
+
+    // 届く猫の中から食べる猫を選ぶ
+    fn choose<R: Rng>(&self, rat: &Rat, contenders: &Vec<Cat>, boundary: Boundary, rng: &mut R) -> Option<Cat> {
+        match *self {
+            Credit::Nearest => contenders
+                .iter()
+                .min_by(|a, b| {
+                    rat.offset(*a, boundary)
+                        .len()
+                        .partial_cmp(&rat.offset(*b, boundary).len())
+                        .unwrap_or(std::cmp::Ordering::Equal)
+                })
+                .cloned(),
+            Credit::Random => contenders.choose(rng).cloned(),
+        }
+    }
+}
+
+// 捕食をまとめて解決する。ねずみは届く猫のうち一匹だけに襲われ、隠れ家の中なら襲われない
+// 襲った猫はcapture_chanceの確率で捕まえ、失敗するとmiss_costだけエネルギーを失う
+// 群れの半径が正なら、食べた場所からその半径以内にいる猫でエネルギーを等分する
+pub fn resolve(cats: &Vec<Cat>, rats: &Vec<Rat>, cats_tree: &QuadTree<Cat>, rats_tree: &QuadTree<Rat>) -> Predation {
+    resolve_with(cats, rats, cats_tree, rats_tree, &mut random::rng())
+}
+
+// 乱数を指定して捕食を解決する。シードを固定して確かめるのに使う
+pub fn resolve_with<R: Rng>(cats: &Vec<Cat>, rats: &Vec<Rat>, cats_tree: &QuadTree<Cat>, rats_tree: &QuadTree<Rat>, rng: &mut R) -> Predation {
+    let config = config::get();
+    let predation = &config.predation;
+    let mut gains: HashMap<u64, (f64, u32, f64)> = HashMap::new();
+    let mut kills = Vec::new();
+    let mut misses = 0;
+    let mut survivors = Vec::with_capacity(rats.len());
+    for rat in rats {
+        let contenders: Vec<Cat> = if obstacle::in_refuge(&rat.position()) {
+            Vec::new()
+        } else {
+            cats_tree.search(rat, predation.radious).into_iter().collect()
+        };
+        let killer = match predation.credit.choose(rat, &contenders, cats_tree.boundary(), rng) {
+            Some(killer) => killer,
+            None => {
+                survivors.push(rat.clone());
+                continue;
+            }
+        };
+        let crowd = if predation.capture.confusion > 0.0 {
+            rats_tree.search(rat, predation.capture.confusion_radious).len().max(1)
+        } else {
+            1
+        };
+        if rng.gen::<f64>() >= capture_chance(&killer, rat, crowd) {
+            gains.entry(killer.id()).or_insert((0.0, 0, 0.0)).2 += predation.capture.miss_cost;
+            misses += 1;
+            survivors.push(rat.clone());
+            continue;
+        }
+        let mut pack: Vec<u64> = if predation.pack_radious > 0.0 {
+            cats_tree
+                .search(rat, predation.pack_radious)
+                .into_iter()
+                .map(|cat| cat.id())
+                .filter(|&id| id != killer.id())
+                .collect()
+        } else {
+            Vec::new()
+        };
+        pack.push(killer.id());
+        let share = EAT_ENERGY / pack.len() as f64;
+        for id in pack {
+            gains.entry(id).or_insert((0.0, 0, 0.0)).0 += share;
+        }
+        gains.entry(killer.id()).or_insert((0.0, 0, 0.0)).1 += 1;
+        kills.push(Kill { cat: killer.id(), rat: rat.id(), position: rat.position() });
+    }
+    let cats = cats
+        .iter()
+        .map(|cat| match gains.get(&cat.id()) {
+            Some(&(energy, ate, cost)) => cat.feed(energy, ate).tire(cost),
+            None => cat.clone(),
+        })
+        .collect();
+    Predation { cats, rats: survivors, kills, misses }
+}
+
+// 襲ったねずみを捕まえられる確率
+// 近づく速さが猫の最高速に近いほど、ねずみが正面にいるほど高く、周りにねずみが多いほど目移りして低い
+pub fn capture_chance(cat: &Cat, rat: &Rat, crowd: usize) -> f64 {
+    let config = config::get();
+    let capture = &config.predation.capture;
+    let direction = cat.offset(rat, config.boundary);
+    let closing = if direction.len() == 0.0 {
+        1.0
+    } else {
+        let speed = cat.as_velocity().add(rat.as_velocity().mult(-1.0)).dot(&direction.normalize());
+        (speed / config.cat.motion.max_speed).max(-1.0).min(1.0)
+    };
+    let facing = if direction.len() == 0.0 || cat.as_velocity().len() == 0.0 {
+        1.0
+    } else {
+        cat.as_velocity().angle_to(&direction).cos()
+    };
+    let speed_term = ((1.0 + closing) / 2.0).powf(capture.speed_weight);
+    let angle_term = ((1.0 + facing) / 2.0).powf(capture.angle_weight);
+    let confusion = 1.0 + capture.confusion * (crowd.max(1) - 1) as f64;
+    (capture.chance * speed_term * angle_term / confusion).max(0.0).min(1.0)
+}
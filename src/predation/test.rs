@@ -0,0 +1,187 @@
+#[cfg(test)]
+mod tests{
+    use animal::{Animal, Cat, Rat};
+    use config;
+    use config::Config;
+    use consts::*;
+    use predation::{Credit, capture_chance, resolve, resolve_with};
+    use pvector::PVector;
+    use quad_tree::QuadTree;
+    use rand::prelude::*;
+
+    macro_rules! assert_float{
+        (
+            $x: expr ,$y: expr
+        ) => {
+            {
+                assert!(($x - $y).abs() < 1.0e-9);
+            }
+        }
+    }
+
+    fn cat_at(x: f64, y: f64) -> Cat {
+        Cat::new().set_position(&PVector::new(x, y))
+    }
+
+    fn rat_at(x: f64, y: f64) -> Rat {
+        Rat::new().set_position(&PVector::new(x, y))
+    }
+
+    fn no_rats() -> QuadTree<Rat> {
+        QuadTree::new(&Vec::new())
+    }
+
+    #[test]
+    fn parse_test(){
+        assert_eq!(Ok(Credit::Nearest), Credit::parse("nearest"));
+        assert_eq!(Ok(Credit::Random), Credit::parse("random"));
+        assert!(Credit::parse("first").is_err());
+    }
+
+    #[test]
+    fn resolve_test(){
+        // 半径以内のねずみだけが食べられる
+        let cat = cat_at(100.5, 100.5);
+        let near = rat_at(100.5 + EATEN_RADIOUS / 2.0, 100.5 + EATEN_RADIOUS / 2.0);
+        let far = rat_at(100.5 + EATEN_RADIOUS, 100.5 + EATEN_RADIOUS);
+        let cats = vec![cat.clone()];
+        let predation = resolve(&cats, &vec![near.clone(), far.clone()], &QuadTree::new(&cats), &no_rats());
+        assert_eq!(1, predation.rats.len());
+        assert_eq!(far.id(), predation.rats[0].id());
+        assert_eq!(cat.energy() + EAT_ENERGY, predation.cats[0].energy());
+        assert_eq!(1, predation.cats[0].ate());
+        assert_eq!(1, predation.kills.len());
+        assert_eq!(cat.id(), predation.kills[0].cat);
+        assert_eq!(near.id(), predation.kills[0].rat);
+
+        // 一匹の猫が一度に何匹食べてもそれぞれ数える
+        let rats: Vec<Rat> = (0..5).map(|_| near.clone()).collect();
+        let predation = resolve(&cats, &rats, &QuadTree::new(&cats), &no_rats());
+        assert_eq!(0, predation.rats.len());
+        assert_eq!(5, predation.cats[0].ate());
+    }
+
+    #[test]
+    fn credit_test(){
+        // 二匹とも届いても手柄は一番近い猫だけのもの
+        let rat = rat_at(100.5, 100.5);
+        let nearest = cat_at(100.6, 100.5);
+        let other = cat_at(100.5, 101.2);
+        let cats = vec![nearest.clone(), other.clone()];
+        let predation = resolve(&cats, &vec![rat.clone()], &QuadTree::new(&cats), &no_rats());
+        assert_eq!(1, predation.kills.len());
+        assert_eq!(nearest.id(), predation.kills[0].cat);
+        let ate: u32 = predation.cats.iter().map(|cat| cat.ate()).sum();
+        assert_eq!(1, ate);
+        let fed = predation.cats.iter().find(|cat| cat.id() == other.id()).unwrap();
+        assert_eq!(other.energy(), fed.energy());
+
+        // ランダムならどちらの猫も選ばれうるが、食べるのはいつも一匹
+        config::set(Config { predation: config::PredationConfig { credit: Credit::Random, ..Config::default().predation }, ..Config::default() });
+        let mut winners = std::collections::HashSet::new();
+        for _ in 0..100 {
+            let predation = resolve(&cats, &vec![rat.clone()], &QuadTree::new(&cats), &no_rats());
+            assert_eq!(1, predation.kills.len());
+            winners.insert(predation.kills[0].cat);
+        }
+        config::set(Config::default());
+        assert_eq!(2, winners.len());
+    }
+
+    #[test]
+    fn pack_test(){
+        let mut config = Config::default();
+        config.predation.pack_radious = 10.0;
+        config::set(config);
+
+        // 食べた場所の近くの猫でエネルギーを等分し、食べた数は食べた猫だけに数える
+        let rat = rat_at(100.5, 100.5);
+        let killer = cat_at(100.6, 100.5);
+        let helper = cat_at(105.5, 100.5);
+        let stranger = cat_at(150.5, 100.5);
+        let cats = vec![killer.clone(), helper.clone(), stranger.clone()];
+        let predation = resolve(&cats, &vec![rat], &QuadTree::new(&cats), &no_rats());
+        config::set(Config::default());
+        let find = |id: u64| predation.cats.iter().find(|cat| cat.id() == id).unwrap().clone();
+        assert_eq!(killer.energy() + EAT_ENERGY / 2.0, find(killer.id()).energy());
+        assert_eq!(helper.energy() + EAT_ENERGY / 2.0, find(helper.id()).energy());
+        assert_eq!(stranger.energy(), find(stranger.id()).energy());
+        assert_eq!(1, find(killer.id()).ate());
+        assert_eq!(0, find(helper.id()).ate());
+    }
+
+    #[test]
+    fn capture_chance_test(){
+        let max_speed = config::get().cat.motion.max_speed;
+        let rat = rat_at(100.5, 100.5).apply_velocity(&PVector::zero());
+        let chasing = cat_at(99.5, 100.5).apply_velocity(&PVector::new(max_speed, 0.0));
+        let resting = cat_at(99.5, 100.5).apply_velocity(&PVector::zero());
+        let sideways = cat_at(99.5, 100.5).apply_velocity(&PVector::new(0.0, max_speed));
+        // 既定では必ず捕まえる
+        assert_float!(1.0, capture_chance(&resting, &rat, 10));
+
+        // 最高速で近づけば確実、同じ速さで逃げられたら捕まらない
+        let mut config = Config::default();
+        config.predation.capture.speed_weight = 1.0;
+        config::set(config);
+        assert_float!(1.0, capture_chance(&chasing, &rat, 1));
+        let fleeing = rat.apply_velocity(&PVector::new(max_speed, 0.0));
+        assert_float!(0.0, capture_chance(&resting, &fleeing, 1));
+        assert_float!(0.5, capture_chance(&resting, &rat, 1));
+
+        // 正面なら確実、横向きなら半分
+        let mut config = Config::default();
+        config.predation.capture.angle_weight = 1.0;
+        config::set(config);
+        assert_float!(1.0, capture_chance(&chasing, &rat, 1));
+        assert_float!(0.5, capture_chance(&sideways, &rat, 1));
+
+        // 周りのねずみが多いほど目移りする
+        let mut config = Config::default();
+        config.predation.capture.confusion = 1.0;
+        config::set(config);
+        assert_float!(1.0, capture_chance(&chasing, &rat, 1));
+        assert_float!(1.0 / 3.0, capture_chance(&chasing, &rat, 3));
+        config::set(Config::default());
+    }
+
+    #[test]
+    fn capture_statistics_test(){
+        let mut config = Config::default();
+        config.predation.capture.chance = 0.3;
+        config.predation.capture.miss_cost = 10.0;
+        config::set(config);
+
+        // 一匹ずつ襲わせて、捕まえた割合が確率に近いことを確かめる
+        let cat = cat_at(100.5, 100.5);
+        let cats = vec![cat.clone()];
+        let cats_tree = QuadTree::new(&cats);
+        let rats: Vec<Rat> = (0..2000).map(|_| rat_at(100.7, 100.5)).collect();
+        let predation = resolve_with(&cats, &rats, &cats_tree, &no_rats(), &mut StdRng::seed_from_u64(42));
+        let kills = predation.kills.len();
+        assert_eq!(2000, kills + predation.misses);
+        assert_eq!(predation.misses, predation.rats.len());
+        assert!((kills as f64 / 2000.0 - 0.3).abs() < 0.05);
+        // 逃げられた分だけエネルギーを失う
+        let expect = cat.energy() + EAT_ENERGY * kills as f64 - 10.0 * predation.misses as f64;
+        assert_float!(expect, predation.cats[0].energy());
+
+        // シードが同じなら結果も同じ
+        let again = resolve_with(&cats, &rats, &cats_tree, &no_rats(), &mut StdRng::seed_from_u64(42));
+        assert_eq!(kills, again.kills.len());
+
+        // 周りのねずみで目移りすると捕まえにくい
+        let mut config = Config::default();
+        config.predation.capture.confusion = 1.0;
+        config::set(config);
+        let herd: Vec<Rat> = (0..4).map(|_| rat_at(100.7, 100.5)).collect();
+        let herd_tree = QuadTree::new(&herd);
+        let mut rng = StdRng::seed_from_u64(7);
+        let mut caught = 0;
+        for _ in 0..1000 {
+            caught += resolve_with(&cats, &herd[0..1].to_vec(), &cats_tree, &herd_tree, &mut rng).kills.len();
+        }
+        config::set(Config::default());
+        assert!((caught as f64 / 1000.0 - 0.25).abs() < 0.05);
+    }
+}
@@ -1,5 +1,7 @@
 mod test;
 
+use boundary::Boundary;
+
 const WIDTH: f64 = 640.0;
 const HEIGHT: f64 = 480.0;
 
@@ -50,8 +52,11 @@ impl PVector {
         }
     }
     
-    // 点と点の距離を計算
-    pub fn offset(&self, other: &PVector) -> PVector {
+    // 点と点の距離を計算。画面がつながっていなければ端をまたがない
+    pub fn offset(&self, other: &PVector, boundary: Boundary) -> PVector {
+        if !boundary.wraps() {
+            return PVector::new(other.x - self.x, other.y - self.y);
+        }
         PVector {
             x: PVector::offset_x(self.x, other.x), 
             y: PVector::offset_y(self.y, other.y) 
@@ -83,6 +88,42 @@ impl PVector {
         }
     }
     
+    // 内積
+    pub fn dot(&self, other: &PVector) -> f64 {
+        self.x * other.x + self.y * other.y
+    }
+    
+    // 二つのベクトルのなす角(0からπ)
+    pub fn angle_to(&self, other: &PVector) -> f64 {
+        let size = self.len() * other.len();
+        if size == 0.0 {
+            return 0.0;
+        }
+        (self.dot(other) / size).max(-1.0).min(1.0).acos()
+    }
+    
+    // 外積のz成分。otherが左回り(画面では下回り)にあれば正
+    pub fn cross(&self, other: &PVector) -> f64 {
+        self.x * other.y - self.y * other.x
+    }
+    
+    // thetaだけ回転させる
+    pub fn rotate(&self, theta: f64) -> PVector {
+        PVector {
+            x: self.x * theta.cos() - self.y * theta.sin(),
+            y: self.x * theta.sin() + self.y * theta.cos(),
+        }
+    }
+    
+    // 長さをmaxまでに切り詰める
+    pub fn truncate(&self, max: f64) -> PVector {
+        if self.len() > max {
+            self.normalize().mult(max)
+        } else {
+            self.clone()
+        }
+    }
+    
     // 零ベクトル
     pub fn zero() -> PVector {
         PVector {
@@ -1,5 +1,6 @@
 #[cfg(test)]
 mod tests{
+    use boundary::Boundary;
     use pvector::PVector;
     use consts::*;
     
@@ -38,29 +39,29 @@ mod tests{
         // 画面を超えて判定しなくてもいい場合
         let vec1 = PVector::new(1.0, 2.0);
         let vec2 = PVector::new(10.0, 20.0);
-        assert_eq!(vec1.offset(&vec2), PVector::new(9.0, 18.0));
-        assert_eq!(vec2.offset(&vec1), PVector::new(-9.0, -18.0));
+        assert_eq!(vec1.offset(&vec2, Boundary::Torus), PVector::new(9.0, 18.0));
+        assert_eq!(vec2.offset(&vec1, Boundary::Torus), PVector::new(-9.0, -18.0));
         
         let over_width1 = PVector::new(WIDTH - 1.0, 10.0);
         let over_width2 = PVector::new(2.0, 10.0);
         // 1画面で見たら相対的に左だが、画面を飛び越えると相対的に右の場合
-        assert_eq!(over_width1.offset(&over_width2), PVector::new(3.0, 0.0));
+        assert_eq!(over_width1.offset(&over_width2, Boundary::Torus), PVector::new(3.0, 0.0));
         // 1画面で見たら相対的に右だが、画面を飛び越えると相対的に左の場合
-        assert_eq!(over_width2.offset(&over_width1), PVector::new(-3.0, 0.0));
+        assert_eq!(over_width2.offset(&over_width1, Boundary::Torus), PVector::new(-3.0, 0.0));
         
         let over_height1 = PVector::new(10.0 , HEIGHT - 1.0);
         let over_height2 = PVector::new(10.0 , 2.0);
          //1画面で見たら相対的に下だが、画面を飛び越えると相対的に上の場合
-        assert_eq!(over_height1.offset(&over_height2), PVector::new(0.0, 3.0));
+        assert_eq!(over_height1.offset(&over_height2, Boundary::Torus), PVector::new(0.0, 3.0));
          //1画面で見たら相対的に上だが、画面を飛び越えると相対的に下の場合
-        assert_eq!(over_height2.offset(&over_height1), PVector::new(0.0, -3.0));
+        assert_eq!(over_height2.offset(&over_height1, Boundary::Torus), PVector::new(0.0, -3.0));
         
         let over_both1 = PVector::new(WIDTH - 1.0, HEIGHT - 2.0);
         let over_both2 = PVector::new(3.0, 4.0);
         // 1画面で見たら右下だが、画面を飛び越えると左上の場合
-        assert_eq!(over_both1.offset(&over_both2), PVector::new(4.0, 6.0));
+        assert_eq!(over_both1.offset(&over_both2, Boundary::Torus), PVector::new(4.0, 6.0));
         // 1画面で見たら右下だが、画面を飛び越えると左上の場合
-        assert_eq!(over_both2.offset(&over_both1), PVector::new(-4.0, -6.0));
+        assert_eq!(over_both2.offset(&over_both1, Boundary::Torus), PVector::new(-4.0, -6.0));
     }
     
     #[test]
@@ -91,6 +92,51 @@ mod tests{
         assert_float!(0.75_f64.sqrt(), v.y);
     }
     
+    #[test]
+    fn dot_test(){
+        let a = PVector::new(1.0, 2.0);
+        let b = PVector::new(3.0, 4.0);
+        assert_float!(11.0, a.dot(&b));
+        // 直交するベクトル
+        assert_eq!(0.0, PVector::new(1.0, 0.0).dot(&PVector::new(0.0, 5.0)));
+    }
+    
+    #[test]
+    fn angle_to_test(){
+        let right = PVector::new(2.0, 0.0);
+        assert_float!(std::f64::consts::PI / 2.0, right.angle_to(&PVector::new(0.0, 3.0)));
+        assert_float!(std::f64::consts::PI / 4.0, right.angle_to(&PVector::new(1.0, -1.0)));
+        assert_float!(std::f64::consts::PI, right.angle_to(&PVector::new(-1.0, 0.0)));
+        assert_eq!(0.0, right.angle_to(&PVector::new(5.0, 0.0)));
+        // 零ベクトルとの角度は0とする
+        assert_eq!(0.0, right.angle_to(&PVector::zero()));
+    }
+    
+    #[test]
+    fn cross_test(){
+        assert_float!(1.0, PVector::new(1.0, 0.0).cross(&PVector::new(0.0, 1.0)));
+        assert_float!(-1.0, PVector::new(0.0, 1.0).cross(&PVector::new(1.0, 0.0)));
+        assert_eq!(0.0, PVector::new(2.0, 1.0).cross(&PVector::new(4.0, 2.0)));
+    }
+    
+    #[test]
+    fn rotate_test(){
+        let v = PVector::new(2.0, 0.0).rotate(std::f64::consts::PI / 2.0);
+        assert!(v.x.abs() < 1.0e-12);
+        assert_float!(2.0, v.y);
+        let v = PVector::new(1.0, 1.0).rotate(-std::f64::consts::PI / 4.0);
+        assert_float!(2.0_f64.sqrt(), v.x);
+        assert!(v.y.abs() < 1.0e-12);
+    }
+    
+    #[test]
+    fn truncate_test(){
+        assert_eq!(PVector::new(3.0, 4.0), PVector::new(3.0, 4.0).truncate(5.0));
+        let v = PVector::new(3.0, 4.0).truncate(1.0);
+        assert_float!(0.6, v.x);
+        assert_float!(0.8, v.y);
+    }
+    
     #[test]
     fn zero_test() {
         let zero = PVector::zero();
@@ -1,6 +1,8 @@
 mod test;
 
 use animal::Animal;
+use boundary::Boundary;
+use config;
 use pvector::PVector;
 use consts::*;
 use std::collections::LinkedList;
@@ -30,31 +32,34 @@ pub struct QuadTree<T: Animal> {
     rectangle:  Rectangle,
     children:   Option<Vec<Rc<RefCell<QuadTree<T>>>>>,
     animals:    Option<LinkedList<T>>,
+    boundary:   Boundary, // 距離を測る時の画面の端の扱い。木を作る時に一度だけ設定から読む
 }
 
 impl<T: Animal> QuadTree<T> {
-    fn new_tree(rect: &Rectangle) -> QuadTree<T> {
+    fn new_tree(rect: &Rectangle, boundary: Boundary) -> QuadTree<T> {
         if rect.width >= WIDTH_LIMIT {
             let mut children: Vec<Rc<RefCell<QuadTree<T>>>> = Vec::with_capacity(4);
             for n in 0..4 {
-                children.push(QuadTree::optimize(rect.child(n)));
+                children.push(QuadTree::optimize(rect.child(n), boundary));
             }
             QuadTree {
                 rectangle:  rect.clone(),
                 animals:    None,
                 children:   Some(children),
+                boundary,
             }
         } else {
             QuadTree {
                 rectangle:  rect.clone(),
                 animals:    Some(LinkedList::new()),
                 children:   None,
+                boundary,
             }
         }
     }
     
-    fn optimize(rect: Rectangle) -> Rc<RefCell<QuadTree<T>>> {
-        Rc::new(RefCell::new(QuadTree::new_tree(&rect)))
+    fn optimize(rect: Rectangle, boundary: Boundary) -> Rc<RefCell<QuadTree<T>>> {
+        Rc::new(RefCell::new(QuadTree::new_tree(&rect, boundary)))
     }
     
     fn append(&mut self, animal: &T) {
@@ -93,20 +98,30 @@ impl<T: Animal> QuadTree<T> {
     }
     
     pub fn new(animals: &Vec<T>) -> QuadTree<T> {
-        let mut tree = QuadTree::new_tree(&Rectangle::whole_screen());
+        let mut tree = QuadTree::new_tree(&Rectangle::whole_screen(), config::get().boundary);
         for animal in animals {
             tree.append(animal);
         }
         tree
     }
 
+    // 木を作った時の画面の端の扱い
+    pub fn boundary(&self) -> Boundary {
+        self.boundary
+    }
+
     pub fn search<S: Animal>(&self, animal: &S, radious: f64) -> LinkedList<T> {
-        if self.rectangle.min_dist(animal) > radious {
+        self.search_around(&animal.position(), radious)
+    }
+
+    // 点を中心とした円の中にいる個体を集める
+    pub fn search_around(&self, center: &PVector, radious: f64) -> LinkedList<T> {
+        if self.rectangle.min_dist(center, self.boundary) > radious {
             return LinkedList::new();
         } else if let Some(ref animals) = self.animals {
             return animals
                 .into_iter()
-                .filter(|other| other.is_within(animal, radious))
+                .filter(|other| center.offset(&other.position(), self.boundary).len() < radious)
                 .map(|animal| animal.clone())
                 .collect()
         }
@@ -115,8 +130,8 @@ impl<T: Animal> QuadTree<T> {
             let mut ret = LinkedList::new();
             for child in children {
                 let tree = child.borrow();
-                if tree.rectangle.min_dist(animal) < radious {
-                    let mut animals = tree.search(animal, radious);
+                if tree.rectangle.min_dist(center, self.boundary) < radious {
+                    let mut animals = tree.search_around(center, radious);
                     ret.append(&mut animals);
                 }
             }
@@ -125,6 +140,73 @@ impl<T: Animal> QuadTree<T> {
             panic!("both none");
         }
     }
+
+    // 長方形の中にいる個体を集める。画面の端をまたぐ長方形は反対側につながる
+    pub fn search_rect(&self, rect: &Rectangle) -> LinkedList<T> {
+        let mut ret = LinkedList::new();
+        for part in rect.wrapped(self.boundary) {
+            let mut animals = self.search_inside(&part);
+            ret.append(&mut animals);
+        }
+        ret
+    }
+
+    fn search_inside(&self, rect: &Rectangle) -> LinkedList<T> {
+        if !self.rectangle.intersects(rect) {
+            return LinkedList::new();
+        } else if let Some(ref animals) = self.animals {
+            return animals
+                .into_iter()
+                .filter(|animal| rect.contains(&animal.position()))
+                .map(|animal| animal.clone())
+                .collect()
+        }
+
+        if let Some(ref children) = self.children {
+            let mut ret = LinkedList::new();
+            for child in children {
+                let mut animals = child.borrow().search_inside(rect);
+                ret.append(&mut animals);
+            }
+            ret
+        } else {
+            panic!("both none");
+        }
+    }
+
+    // 半直線と交わる個体を原点から近い順に集める。距離と個体の組を返す
+    // lengthは画面の半分より短いことを想定している
+    pub fn raycast(&self, origin: &PVector, direction: &PVector, length: f64, hit_radious: f64) -> Vec<(f64, T)> {
+        let direction = direction.normalize();
+        let mut ret: Vec<(f64, T)> = self
+            .search_around(origin, length + hit_radious)
+            .into_iter()
+            .filter_map(|animal| {
+                let offset = origin.offset(&animal.position(), self.boundary);
+                let along = offset.dot(&direction);
+                let across = offset.add(direction.mult(-along)).len();
+                if 0.0 <= along && along <= length && across < hit_radious {
+                    Some((along, animal))
+                } else {
+                    None
+                }
+            })
+            .collect();
+        ret.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap_or(std::cmp::Ordering::Equal));
+        ret
+    }
+
+    // 扇形の視野の中にいる個体を集める。half_angleは向きから左右それぞれの角度
+    pub fn search_cone(&self, origin: &PVector, direction: &PVector, half_angle: f64, radious: f64) -> LinkedList<T> {
+        self
+            .search_around(origin, radious)
+            .into_iter()
+            .filter(|animal| {
+                let offset = origin.offset(&animal.position(), self.boundary);
+                offset.len() == 0.0 || direction.angle_to(&offset) <= half_angle
+            })
+            .collect()
+    }
     
     pub fn is_move_tree(&self, animal: &T) -> bool {
         self.rectangle.get_index((0, 0), &animal.position()) !=
@@ -133,6 +215,12 @@ impl<T: Animal> QuadTree<T> {
 }
     
 impl Rectangle {
+    pub fn new(x: f64, y: f64, width: f64, height: f64) -> Rectangle {
+        Rectangle {
+            x, y, width, height,
+        }
+    }
+    
     fn child(&self, num: u8) -> Rectangle{
         let x = if num % 2 == 0 {
             self.x
@@ -165,7 +253,7 @@ impl Rectangle {
         } else {
             self.x + self.width
         };
-        let y = if n % 2 == 0 {
+        let y = if n / 2 == 0 {
             self.y
         } else {
             self.y + self.height
@@ -176,30 +264,79 @@ impl Rectangle {
         }
     }
 
-    fn min_dist<T: Animal>(&self, animal: &T) -> f64 {
-        let position = animal.position();
-        let PVector{x, y} = position;
+    fn min_dist(&self, position: &PVector, boundary: Boundary) -> f64 {
+        let PVector{x, y} = *position;
         let x_contain = self.x < x && x < self.x + self.width;
         let y_contain = self.y < y && y < self.y + self.height;
         if x_contain && y_contain {
             0.0
         } else if x_contain {
             min(
-                PVector{x, y: self.y}.offset(&position).len(),
-                PVector{x, y: self.y + self.height}.offset(&position).len()
+                PVector{x, y: self.y}.offset(position, boundary).len(),
+                PVector{x, y: self.y + self.height}.offset(position, boundary).len()
             )
         } else if y_contain {
             min(
-                PVector{x: self.x, y}.offset(&position).len(),
-                PVector{x: self.x + self.width, y}.offset(&position).len()
+                PVector{x: self.x, y}.offset(position, boundary).len(),
+                PVector{x: self.x + self.width, y}.offset(position, boundary).len()
             )
         }else {
             (0..4)
-                .map(|n| self.point(n).offset(&animal.position()).len())
+                .map(|n| self.point(n).offset(position, boundary).len())
                 .fold(WIDTH + 100.0, |a, b| if a < b { a } else { b })
         }
     }
     
+    // 左上の辺を含み、右下の辺を含まない判定
+    pub fn contains(&self, vector: &PVector) -> bool {
+        self.x <= vector.x
+            && vector.x < self.x + self.width
+            && self.y <= vector.y
+            && vector.y < self.y + self.height
+    }
+    
+    // 二つの長方形が重なるかどうか
+    fn intersects(&self, other: &Rectangle) -> bool {
+        self.x <= other.x + other.width
+            && other.x <= self.x + self.width
+            && self.y <= other.y + other.height
+            && other.y <= self.y + self.height
+    }
+    
+    // 画面の端をまたぐ長方形を画面内の長方形に分割する
+    // 画面がつながっていなければはみ出た部分を切り捨てる
+    pub fn wrapped(&self, boundary: Boundary) -> Vec<Rectangle> {
+        if !boundary.wraps() {
+            let x = self.x.max(0.0);
+            let y = self.y.max(0.0);
+            let width = (self.x + self.width).min(WIDTH) - x;
+            let height = (self.y + self.height).min(HEIGHT) - y;
+            if width < 0.0 || height < 0.0 {
+                return Vec::new();
+            }
+            return vec![Rectangle{ x, y, width, height }];
+        }
+        let mut ret = Vec::with_capacity(4);
+        for &(x, width) in &Rectangle::wrap_range(self.x, self.width, WIDTH) {
+            for &(y, height) in &Rectangle::wrap_range(self.y, self.height, HEIGHT) {
+                ret.push(Rectangle{ x, y, width, height });
+            }
+        }
+        ret
+    }
+    
+    fn wrap_range(start: f64, size: f64, max: f64) -> Vec<(f64, f64)> {
+        if size >= max {
+            return vec![(0.0, max)];
+        }
+        let start = ((start % max) + max) % max;
+        if start + size <= max {
+            vec![(start, size)]
+        } else {
+            vec![(start, max - start), (0.0, start + size - max)]
+        }
+    }
+    
     pub fn whole_screen() -> Rectangle {
         Rectangle{
             x: 0.0,
@@ -1,10 +1,13 @@
 #[cfg(test)]
 mod rectangle_tests{
+    use boundary::Boundary;
     use quad_tree::{Rectangle, QuadTree};
+    use config;
     use consts::*;
     use animal::{Cat, Animal};
     use pvector::PVector;
     use std::collections::LinkedList;
+    use rand::prelude::*;
     
     macro_rules! assert_float{
         (
@@ -98,7 +101,7 @@ mod rectangle_tests{
     #[test]
     fn new_tree_test(){
         let rect = sized_rect(1000.0, 2000.0);
-        let tree = QuadTree::new_tree(&rect);
+        let tree = QuadTree::new_tree(&rect, Boundary::Torus);
         assert_eq!(tree_depth(&tree), 7);
         let Rectangle{width, height, .. } = tree_minsq(&tree);
         assert_float!(7.8125, width);
@@ -107,13 +110,13 @@ mod rectangle_tests{
     
     #[test]
     fn new_tree_child_test(){
-        tree_parse(&QuadTree::new_tree(&sized_rect(1000.0, 2000.0)));
+        tree_parse(&QuadTree::new_tree(&sized_rect(1000.0, 2000.0), Boundary::Torus));
     }
     
     #[test]
     fn append_test_left_up(){
         let rect = sized_rect(1000.0, 2000.0);
-        let mut tree = QuadTree::new_tree(&rect);
+        let mut tree = QuadTree::new_tree(&rect, Boundary::Torus);
         let cat = positioned_cat(5.0, 10.0);
         
         for _ in 0..10 {
@@ -128,7 +131,7 @@ mod rectangle_tests{
     #[test]
     fn append_test_right_up(){
         let rect = sized_rect(1000.0, 2000.0);
-        let mut tree = QuadTree::new_tree(&rect);
+        let mut tree = QuadTree::new_tree(&rect, Boundary::Torus);
         let cat = positioned_cat(995.0, 10.0);
         
         for _ in 0..10 {
@@ -143,7 +146,7 @@ mod rectangle_tests{
     #[test]
     fn append_test_left_down(){
         let rect = sized_rect(1000.0, 2000.0);
-        let mut tree = QuadTree::new_tree(&rect);
+        let mut tree = QuadTree::new_tree(&rect, Boundary::Torus);
         let cat = positioned_cat(5.0, 1990.0);
         
         for _ in 0..10 {
@@ -158,7 +161,7 @@ mod rectangle_tests{
     #[test]
     fn append_test_right_down(){
         let rect = sized_rect(1000.0, 2000.0);
-        let mut tree = QuadTree::new_tree(&rect);
+        let mut tree = QuadTree::new_tree(&rect, Boundary::Torus);
         let cat = positioned_cat(995.0, 1990.0);
         
         for _ in 0..10 {
@@ -296,9 +299,200 @@ mod rectangle_tests{
         let origin_cat = positioned_cat((x as f64 + 0.5) * 5.0, (y as f64 + 0.5) * 3.75);
         let tree = QuadTree::new(&Vec::new());
         assert!(!tree.is_move_tree(&origin_cat.apply_velocity(&PVector{ x: 0.0, y: 0.0 })));
+        // 1ステップでちょうど1マス分動く速さ
+        let per_step = 1.0 / config::get().dt;
         for i in 0..8 {
-            let vec = PVector {x: (i as f64 * 45.0).cos() * 5.0, y: (i as f64 * 45.0).sin() * 3.75 };
+            let vec = PVector {x: (i as f64 * 45.0).cos() * 5.0, y: (i as f64 * 45.0).sin() * 3.75 }.mult(per_step);
             assert!(tree.is_move_tree(&origin_cat.apply_velocity(&vec)));
         }
     }
+    
+    fn random_cats(n: usize) -> Vec<Cat> {
+        let mut rng = rand::thread_rng();
+        (0..n)
+            .map(|_| positioned_cat(rng.gen::<f64>() * WIDTH, rng.gen::<f64>() * HEIGHT))
+            .collect()
+    }
+    
+    fn sorted_ids(animals: Vec<Cat>) -> Vec<u64> {
+        let mut ids: Vec<u64> = animals.into_iter().map(|cat| cat.id()).collect();
+        ids.sort();
+        ids
+    }
+    
+    // 画面を上下左右に並べた9通りの位置の中で原点からの相対位置を全て返す
+    fn images(origin: &PVector, position: &PVector) -> Vec<PVector> {
+        let mut ret = Vec::with_capacity(9);
+        for i in -1..2 {
+            for j in -1..2 {
+                ret.push(PVector::new(
+                    position.x + i as f64 * WIDTH - origin.x,
+                    position.y + j as f64 * HEIGHT - origin.y,
+                ));
+            }
+        }
+        ret
+    }
+    
+    #[test]
+    fn search_around_brute_force_test(){
+        let mut rng = rand::thread_rng();
+        let cats = random_cats(2000);
+        let tree = QuadTree::new(&cats);
+        for _ in 0..50 {
+            let center = PVector::new(rng.gen::<f64>() * WIDTH, rng.gen::<f64>() * HEIGHT);
+            let radious = rng.gen::<f64>() * 60.0;
+            let expect: Vec<Cat> = cats
+                .iter()
+                .filter(|cat| images(&center, &cat.position()).iter().any(|v| v.len() < radious))
+                .cloned()
+                .collect();
+            let result = tree.search_around(&center, radious).into_iter().collect();
+            assert_eq!(sorted_ids(expect), sorted_ids(result));
+        }
+    }
+    
+    #[test]
+    fn search_rect_brute_force_test(){
+        let mut rng = rand::thread_rng();
+        let cats = random_cats(2000);
+        let tree = QuadTree::new(&cats);
+        for _ in 0..50 {
+            // 画面の端をまたぐ長方形も含める
+            let rect = Rectangle::new(
+                rng.gen::<f64>() * WIDTH * 2.0 - WIDTH / 2.0,
+                rng.gen::<f64>() * HEIGHT * 2.0 - HEIGHT / 2.0,
+                rng.gen::<f64>() * WIDTH / 2.0,
+                rng.gen::<f64>() * HEIGHT / 2.0,
+            );
+            let expect: Vec<Cat> = cats
+                .iter()
+                .filter(|cat| {
+                    let PVector{x, y} = cat.position();
+                    let dx = ((x - rect.x) % WIDTH + WIDTH) % WIDTH;
+                    let dy = ((y - rect.y) % HEIGHT + HEIGHT) % HEIGHT;
+                    dx < rect.width && dy < rect.height
+                })
+                .cloned()
+                .collect();
+            let result = tree.search_rect(&rect).into_iter().collect();
+            assert_eq!(sorted_ids(expect), sorted_ids(result));
+        }
+    }
+    
+    #[test]
+    fn search_rect_whole_screen_test(){
+        let cats = random_cats(500);
+        let tree = QuadTree::new(&cats);
+        assert_eq!(tree.search_rect(&Rectangle::whole_screen()).len(), 500);
+        // 画面より大きい長方形でも重複しない
+        let larger = Rectangle::new(-10.0, -10.0, WIDTH * 2.0, HEIGHT * 2.0);
+        assert_eq!(tree.search_rect(&larger).len(), 500);
+    }
+    
+    #[test]
+    fn wrapped_test(){
+        let parts = Rectangle::new(WIDTH - 10.0, -5.0, 20.0, 10.0).wrapped(Boundary::Torus);
+        assert_eq!(parts.len(), 4);
+        let area = parts.iter().fold(0.0, |a, rect| a + rect.width * rect.height);
+        assert_float!(200.0, area);
+        assert_eq!(Rectangle::new(10.0, 10.0, 20.0, 10.0).wrapped(Boundary::Torus).len(), 1);
+    }
+    
+    #[test]
+    fn raycast_brute_force_test(){
+        let mut rng = rand::thread_rng();
+        let cats = random_cats(2000);
+        let tree = QuadTree::new(&cats);
+        let hit_radious = 3.0;
+        for _ in 0..50 {
+            let origin = PVector::new(rng.gen::<f64>() * WIDTH, rng.gen::<f64>() * HEIGHT);
+            let theta = rng.gen::<f64>() * 2.0 * std::f64::consts::PI;
+            let direction = PVector::new(theta.cos(), theta.sin());
+            let length = rng.gen::<f64>() * 150.0;
+            let mut expect: Vec<(f64, u64)> = cats
+                .iter()
+                .filter_map(|cat| {
+                    images(&origin, &cat.position())
+                        .into_iter()
+                        .filter_map(|v| {
+                            let along = v.dot(&direction);
+                            let across = (v.x * direction.y - v.y * direction.x).abs();
+                            if 0.0 <= along && along <= length && across < hit_radious {
+                                Some(along)
+                            } else {
+                                None
+                            }
+                        })
+                        .fold(None, |a: Option<f64>, b| Some(a.map_or(b, |a| a.min(b))))
+                        .map(|along| (along, cat.id()))
+                })
+                .collect();
+            expect.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap());
+            let result = tree.raycast(&origin, &direction.mult(5.0), length, hit_radious);
+            assert_eq!(expect.len(), result.len());
+            for (e, r) in expect.iter().zip(result.iter()) {
+                assert!((e.0 - r.0).abs() < 1.0e-9);
+            }
+            let mut expect_ids: Vec<u64> = expect.iter().map(|e| e.1).collect();
+            let mut result_ids: Vec<u64> = result.iter().map(|r| r.1.id()).collect();
+            expect_ids.sort();
+            result_ids.sort();
+            assert_eq!(expect_ids, result_ids);
+        }
+    }
+    
+    #[test]
+    fn raycast_wrap_test(){
+        // 画面の右端から右へ飛ばすと左端の個体に当たる
+        let near = positioned_cat(6.0, 101.0);
+        let far = positioned_cat(21.0, 101.0);
+        let tree = QuadTree::new(&vec![far.clone(), near.clone()]);
+        let result = tree.raycast(&PVector::new(WIDTH - 4.0, 101.0), &PVector::new(1.0, 0.0), 50.0, 1.0);
+        assert_eq!(result.len(), 2);
+        assert_float!(10.0, result[0].0);
+        assert_eq!(result[0].1.id(), near.id());
+        assert_float!(25.0, result[1].0);
+        assert_eq!(result[1].1.id(), far.id());
+        // 逆向きには当たらない
+        let behind = tree.raycast(&PVector::new(WIDTH - 4.0, 101.0), &PVector::new(-1.0, 0.0), 50.0, 1.0);
+        assert_eq!(behind.len(), 0);
+    }
+    
+    #[test]
+    fn search_cone_brute_force_test(){
+        let mut rng = rand::thread_rng();
+        let cats = random_cats(2000);
+        let tree = QuadTree::new(&cats);
+        for _ in 0..50 {
+            let origin = PVector::new(rng.gen::<f64>() * WIDTH, rng.gen::<f64>() * HEIGHT);
+            let theta = rng.gen::<f64>() * 2.0 * std::f64::consts::PI;
+            let direction = PVector::new(theta.cos(), theta.sin());
+            let half_angle = rng.gen::<f64>() * std::f64::consts::PI;
+            let radious = rng.gen::<f64>() * 80.0;
+            let expect: Vec<Cat> = cats
+                .iter()
+                .filter(|cat| {
+                    images(&origin, &cat.position()).iter().any(|v| {
+                        let cos = v.dot(&direction) / v.len();
+                        v.len() < radious && cos >= half_angle.cos()
+                    })
+                })
+                .cloned()
+                .collect();
+            let result = tree.search_cone(&origin, &direction, half_angle, radious).into_iter().collect();
+            assert_eq!(sorted_ids(expect), sorted_ids(result));
+        }
+    }
+    
+    #[test]
+    fn search_cone_wrap_test(){
+        // 画面の下端から下向きの視野に上端の個体が入る
+        let ahead = positioned_cat(101.0, 3.0);
+        let behind = positioned_cat(101.0, HEIGHT - 10.0);
+        let tree = QuadTree::new(&vec![ahead.clone(), behind.clone()]);
+        let result = tree.search_cone(&PVector::new(101.0, HEIGHT - 3.0), &PVector::new(0.0, 1.0), 0.5, 20.0);
+        assert_eq!(result.len(), 1);
+        assert_eq!(result.front().unwrap().id(), ahead.id());
+    }
 }
@@ -0,0 +1,53 @@
+mod test;
+
+use rand::{Error, FromEntropy, RngCore, SeedableRng};
+use rand::rngs::StdRng;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+// シミュレーションで使う乱数。スレッドごとに一つ持ち、シードを決めれば同じ動きを繰り返せる
+thread_local! {
+    static RNG: Rc<RefCell<StdRng>> = Rc::new(RefCell::new(StdRng::from_entropy()));
+}
+
+// 今のスレッドの乱数を指す。thread_rngと同じように使う
+#[derive(Clone)]
+pub struct SharedRng(Rc<RefCell<StdRng>>);
+
+impl RngCore for SharedRng {
+    fn next_u32(&mut self) -> u32 {
+        self.0.borrow_mut().next_u32()
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.0.borrow_mut().next_u64()
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        self.0.borrow_mut().fill_bytes(dest)
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), Error> {
+        self.0.borrow_mut().try_fill_bytes(dest)
+    }
+}
+
+// 今のスレッドの乱数
+pub fn rng() -> SharedRng {
+    RNG.with(|rng| SharedRng(rng.clone()))
+}
+
+// 今のスレッドの乱数をシードから作り直す
+pub fn seed(seed: u64) {
+    RNG.with(|rng| *rng.borrow_mut() = StdRng::seed_from_u64(seed));
+}
+
+// 今のスレッドの乱数から別の乱数を分ける。分けた乱数も元のシードで決まる
+pub fn fork() -> StdRng {
+    StdRng::from_rng(rng()).expect("failed to fork the random number generator")
+}
+
+// 今のスレッドの乱数とotherを入れ替える。島ごとに別の乱数で進めるのに使う
+pub fn swap(other: &mut StdRng) {
+    RNG.with(|rng| std::mem::swap(&mut *rng.borrow_mut(), other));
+}
@@ -0,0 +1,32 @@
+#[cfg(test)]
+mod tests{
+    use rand::prelude::*;
+    use random;
+
+    #[test]
+    fn seed_test(){
+        // 同じシードなら同じ並びになる
+        random::seed(42);
+        let first: Vec<f64> = (0..5).map(|_| random::rng().gen()).collect();
+        random::seed(42);
+        let again: Vec<f64> = (0..5).map(|_| random::rng().gen()).collect();
+        assert_eq!(first, again);
+        random::seed(43);
+        let other: Vec<f64> = (0..5).map(|_| random::rng().gen()).collect();
+        assert_ne!(first, other);
+    }
+
+    #[test]
+    fn swap_test(){
+        // 入れ替えている間だけ別の乱数で進み、戻すと続きから引ける
+        random::seed(7);
+        let mut forked = random::fork();
+        let expected: f64 = random::rng().gen();
+        random::seed(7);
+        let _ = random::fork();
+        random::swap(&mut forked);
+        let _: f64 = random::rng().gen();
+        random::swap(&mut forked);
+        assert_eq!(expected, random::rng().gen::<f64>());
+    }
+}
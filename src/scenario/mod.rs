@@ -0,0 +1,286 @@
+mod test;
+
+use animal::Animal;
+use config;
+use config::{Config, parse_count, parse_f64};
+use consts::*;
+use pvector::PVector;
+use rand::prelude::*;
+use random;
+use std::fs::File;
+use std::io::Read;
+
+// 世代の初めの個体の置き方
+#[derive(Debug, Clone, PartialEq)]
+pub enum Placement {
+    Uniform,                        // 画面全体にばらまく
+    Clusters(Vec<(PVector, f64)>),  // 中心と半径の円のどれかに順に入れる
+    Ring { center: PVector, radious: f64 }, // 円周上に等間隔に並べる
+    Points(Vec<PVector>),           // 決めた位置に順に置く
+}
+
+// 世代の初めの速さの決め方。向きは常にばらばら
+#[derive(Debug, Clone, PartialEq)]
+pub enum Speed {
+    Max,               // 種の最高速
+    Fixed(f64),
+    Uniform(f64, f64), // 下限と上限の間の一様乱数
+}
+
+// 一つの種の数と置き方
+#[derive(Debug, Clone, PartialEq)]
+pub struct Population {
+    pub count: usize,
+    pub offspring: Option<usize>, // 二世代目からの数。なければcountと同じ
+    pub placement: Placement,
+    pub speed: Speed,
+}
+
+// 初期条件と、それに合わせて上書きする設定
+#[derive(Debug, Clone)]
+pub struct Scenario {
+    pub cats: Population,
+    pub rats: Population,
+    pub overrides: Vec<(String, String)>,
+}
+
+// 組み込みのシナリオ。名前と、シナリオファイルと同じ形式の中身
+const BUILT_IN: &[(&str, &str)] = &[
+    ("uniform", ""),
+    ("clumped", "
+        rat.placement = cluster 160 120 30
+        rat.placement = cluster 480 120 30
+        rat.placement = cluster 160 360 30
+        rat.placement = cluster 480 360 30
+    "),
+    ("single_hunter", "
+        cat.count = 1
+        cat.offspring = 1
+    "),
+    ("ring", "
+        cat.count = 1
+        cat.offspring = 1
+        cat.placement = point 322 242
+        rat.placement = ring 322 242 150
+    "),
+];
+
+impl Default for Scenario {
+    fn default() -> Scenario {
+        Scenario {
+            cats: Population { count: CAT_COUNT, offspring: Some(CAT_OFFSPRING), placement: Placement::Uniform, speed: Speed::Max },
+            rats: Population { count: RAT_COUNT, offspring: None, placement: Placement::Uniform, speed: Speed::Max },
+            overrides: Vec::new(),
+        }
+    }
+}
+
+impl Scenario {
+    // 組み込みの名前か、シナリオファイルのパスから読む
+    pub fn find(name: &str) -> Result<Scenario, String> {
+        match BUILT_IN.iter().find(|&&(built_in, _)| built_in == name) {
+            Some(&(_, text)) => Scenario::parse(text),
+            None => Scenario::load(name).map_err(|e| {
+                format!("{} (built-in scenarios: {})", e, Scenario::names().join(", "))
+            }),
+        }
+    }
+    
+    // 組み込みのシナリオの名前の一覧
+    pub fn names() -> Vec<&'static str> {
+        BUILT_IN.iter().map(|&(name, _)| name).collect()
+    }
+    
+    // シナリオファイルを読み込む
+    pub fn load(path: &str) -> Result<Scenario, String> {
+        let mut text = String::new();
+        File::open(path)
+            .and_then(|mut file| file.read_to_string(&mut text))
+            .map_err(|e| format!("{}: {}", path, e))?;
+        Scenario::parse(&text).map_err(|e| format!("{}: {}", path, e))
+    }
+    
+    // 「key = value」の行を読む。count、offspring、placement、speed以外は設定の上書きになる
+    pub fn parse(text: &str) -> Result<Scenario, String> {
+        let mut scenario = Scenario::default();
+        // 上書きが正しいかは読んだ時点で確かめる
+        let mut checked = Config::default();
+        for (n, line) in text.lines().enumerate() {
+            let line = line.split('#').next().unwrap_or("").trim();
+            if line.is_empty() {
+                continue;
+            }
+            let mut pair = line.splitn(2, '=');
+            let key = pair.next().unwrap_or("").trim();
+            let value = pair
+                .next()
+                .ok_or_else(|| format!("line {}: expected `key = value`", n + 1))?
+                .trim();
+            scenario.set(key, value, &mut checked).map_err(|e| format!("line {}: {}", n + 1, e))?;
+        }
+        Ok(scenario)
+    }
+    
+    fn set(&mut self, key: &str, value: &str, checked: &mut Config) -> Result<(), String> {
+        let mut path = key.splitn(2, '.');
+        let population = match path.next() {
+            Some("cat") => Some(&mut self.cats),
+            Some("rat") => Some(&mut self.rats),
+            _ => None,
+        };
+        match (population, path.next()) {
+            (Some(population), Some("count")) => population.count = parse_count(value)?,
+            (Some(population), Some("offspring")) => population.offspring = Some(parse_count(value)?),
+            (Some(population), Some("placement")) => {
+                population.placement = Placement::parse(value, &population.placement)?
+            },
+            (Some(population), Some("speed")) => population.speed = Speed::parse(value)?,
+            _ => {
+                checked.set(key, value)?;
+                self.overrides.push((String::from(key), String::from(value)));
+            }
+        }
+        Ok(())
+    }
+    
+    // 上書きを設定に反映する
+    pub fn apply(&self, config: &mut Config) -> Result<(), String> {
+        for &(ref key, ref value) in &self.overrides {
+            config.set(key, value)?;
+        }
+        Ok(())
+    }
+}
+
+impl Population {
+    // 二世代目からの数
+    pub fn next_count(&self) -> usize {
+        self.offspring.unwrap_or(self.count)
+    }
+    
+    // 新しい個体をcount匹置く
+    pub fn spawn<A: Animal>(&self) -> Vec<A> {
+        let animals = (0..self.count).map(|_| A::new()).collect();
+        self.place(&animals)
+    }
+    
+    // 個体の遺伝子はそのままに、位置と速度を置き直す
+    pub fn place<A: Animal>(&self, animals: &Vec<A>) -> Vec<A> {
+        let mut rng = random::rng();
+        let count = animals.len();
+        animals
+            .iter()
+            .enumerate()
+            .map(|(n, animal)| {
+                let position = self.placement.position(n, count, &mut rng);
+                let max_speed = config::get().species(animal.species()).motion.max_speed;
+                let theta: f64 = rng.gen::<f64>() * 2.0 * std::f64::consts::PI;
+                let velocity = PVector::new(theta.cos(), theta.sin()).mult(self.speed.sample(max_speed, &mut rng));
+                animal.set_position(&position).apply_velocity(&velocity)
+            })
+            .collect()
+    }
+}
+
+impl Placement {
+    // 「uniform」「cluster x y 半径」「ring x y 半径」「point x y」を読む
+    // clusterとpointは続けて書くと前のものに足される
+    pub fn parse(text: &str, current: &Placement) -> Result<Placement, String> {
+        let words: Vec<&str> = text.split_whitespace().collect();
+        let numbers = words
+            .get(1..)
+            .unwrap_or(&[])
+            .iter()
+            .map(|word| parse_f64(word))
+            .collect::<Result<Vec<f64>, String>>();
+        match (words.first(), numbers?.as_slice()) {
+            (Some(&"uniform"), &[]) => Ok(Placement::Uniform),
+            (Some(&"cluster"), &[x, y, radious]) if radious >= 0.0 => {
+                let mut clusters = match *current {
+                    Placement::Clusters(ref clusters) => clusters.clone(),
+                    _ => Vec::new(),
+                };
+                clusters.push((PVector::new(x, y), radious));
+                Ok(Placement::Clusters(clusters))
+            },
+            (Some(&"ring"), &[x, y, radious]) if radious >= 0.0 => {
+                Ok(Placement::Ring { center: PVector::new(x, y), radious })
+            },
+            (Some(&"point"), &[x, y]) => {
+                let mut points = match *current {
+                    Placement::Points(ref points) => points.clone(),
+                    _ => Vec::new(),
+                };
+                points.push(PVector::new(x, y));
+                Ok(Placement::Points(points))
+            },
+            _ => Err(format!("unknown placement `{}`", text)),
+        }
+    }
+    
+    // count匹中n匹目の位置
+    pub fn position<R: Rng>(&self, n: usize, count: usize, rng: &mut R) -> PVector {
+        let position = match *self {
+            Placement::Uniform => PVector::new(rng.gen::<f64>() * WIDTH, rng.gen::<f64>() * HEIGHT),
+            Placement::Clusters(ref clusters) => {
+                let (ref center, radious) = clusters[n % clusters.len()];
+                // 円の中に一様に散らばるように半径は平方根をとる
+                let theta: f64 = rng.gen::<f64>() * 2.0 * std::f64::consts::PI;
+                let r = radious * rng.gen::<f64>().sqrt();
+                center.add(PVector::new(theta.cos(), theta.sin()).mult(r))
+            },
+            Placement::Ring { ref center, radious } => {
+                let theta = 2.0 * std::f64::consts::PI * n as f64 / count as f64;
+                center.add(PVector::new(theta.cos(), theta.sin()).mult(radious))
+            },
+            Placement::Points(ref points) => points[n % points.len()].clone(),
+        };
+        inside_screen(&position)
+    }
+}
+
+impl Speed {
+    // 「max」「fixed 速さ」「uniform 下限 上限」を読む。速さは0以上の有限な数に限る
+    pub fn parse(text: &str) -> Result<Speed, String> {
+        let words: Vec<&str> = text.split_whitespace().collect();
+        let speed = |word: &str| -> Result<f64, String> {
+            let speed = parse_f64(word)?;
+            if !speed.is_finite() || speed < 0.0 {
+                return Err(format!("`{}` is not a speed", word));
+            }
+            Ok(speed)
+        };
+        match words.as_slice() {
+            &["max"] => Ok(Speed::Max),
+            &["fixed", value] => Ok(Speed::Fixed(speed(value)?)),
+            &["uniform", low, high] => {
+                let (low, high) = (speed(low)?, speed(high)?);
+                if low > high {
+                    return Err(format!("`{}` has low above high", text));
+                }
+                Ok(Speed::Uniform(low, high))
+            },
+            _ => Err(format!("unknown speed `{}`", text)),
+        }
+    }
+    
+    pub fn sample<R: Rng>(&self, max_speed: f64, rng: &mut R) -> f64 {
+        match *self {
+            Speed::Max => max_speed,
+            Speed::Fixed(speed) => speed,
+            Speed::Uniform(low, high) => low + rng.gen::<f64>() * (high - low),
+        }
+    }
+}
+
+// 画面の外に出た位置を、画面がつながっていれば反対側へ、そうでなければ端へ寄せる
+fn inside_screen(position: &PVector) -> PVector {
+    let fit = |value: f64, max: f64| {
+        if config::get().boundary.wraps() {
+            ((value % max) + max) % max
+        } else {
+            value.max(0.0).min(max)
+        }
+    };
+    PVector::new(fit(position.x, WIDTH), fit(position.y, HEIGHT))
+}
@@ -0,0 +1,145 @@
+#[cfg(test)]
+mod tests{
+    use animal::{Animal, Cat, Rat};
+    use boundary::Boundary;
+    use config;
+    use config::Config;
+    use consts::*;
+    use pvector::PVector;
+    use rand::prelude::*;
+    use scenario::{Scenario, Placement, Population, Speed};
+    
+    macro_rules! assert_float{
+        (
+            $x: expr ,$y: expr
+        ) => {
+            {
+                assert!((($x - $y) / $x).abs() < 1.0e-9);
+            }
+        }
+    }
+    
+    #[test]
+    fn default_test(){
+        let scenario = Scenario::default();
+        assert_eq!(CAT_COUNT, scenario.cats.count);
+        assert_eq!(RAT_COUNT, scenario.rats.count);
+        // 猫は二世代目から増え、ねずみは同じ数
+        assert_eq!(CAT_OFFSPRING, scenario.cats.next_count());
+        assert_eq!(RAT_COUNT, scenario.rats.next_count());
+        assert_eq!(Placement::Uniform, scenario.rats.placement);
+        assert_eq!(Speed::Max, scenario.cats.speed);
+        assert_eq!(0, scenario.overrides.len());
+    }
+    
+    #[test]
+    fn parse_test(){
+        let text = "
+            cat.count = 3
+            cat.speed = uniform 10 20
+            cat.offspring = 6
+            rat.placement = cluster 100 100 10
+            rat.placement = cluster 300 200 20   # 前のclusterに足される
+            cat.placement = point 50 60
+            obstacle = circle 200 200 10
+            dt = 0.02
+        ";
+        let scenario = Scenario::parse(text).unwrap();
+        assert_eq!(3, scenario.cats.count);
+        assert_eq!(6, scenario.cats.next_count());
+        assert_eq!(Speed::Uniform(10.0, 20.0), scenario.cats.speed);
+        assert_eq!(Placement::Clusters(vec![
+            (PVector::new(100.0, 100.0), 10.0),
+            (PVector::new(300.0, 200.0), 20.0),
+        ]), scenario.rats.placement);
+        assert_eq!(Placement::Points(vec![PVector::new(50.0, 60.0)]), scenario.cats.placement);
+        
+        // 残りは設定の上書き
+        let mut config = Config::default();
+        scenario.apply(&mut config).unwrap();
+        assert_eq!(1, config.obstacles.len());
+        assert_float!(0.02, config.dt);
+    }
+    
+    #[test]
+    fn parse_error_test(){
+        assert!(Scenario::parse("cat.count = many").is_err());
+        assert!(Scenario::parse("rat.placement = cluster 1 2").is_err());
+        assert!(Scenario::parse("rat.placement = spiral").is_err());
+        assert!(Scenario::parse("rat.placement =").is_err());
+        assert!(Scenario::parse("cat.offspring = some").is_err());
+        assert!(Scenario::parse("cat.speed = uniform 20 10").is_err());
+        assert!(Scenario::parse("cat.speed = uniform -5 10").is_err());
+        assert!(Scenario::parse("cat.speed = fixed -1").is_err());
+        assert!(Scenario::parse("cat.speed = fixed NaN").is_err());
+        assert!(Scenario::parse("cat.count = 0").is_err());
+        assert!(Scenario::parse("cat.offspring = 0").is_err());
+        assert!(Scenario::parse("cat.view_angle = 400").is_err());
+        assert!(Scenario::parse("dog.count = 1").is_err());
+        let message = Scenario::parse("\ncat.count = 1\ncat.count = -1").unwrap_err();
+        assert!(message.starts_with("line 3"));
+    }
+    
+    #[test]
+    fn built_in_test(){
+        for name in Scenario::names() {
+            assert!(Scenario::find(name).is_ok());
+        }
+        assert_eq!(1, Scenario::find("single_hunter").unwrap().cats.count);
+        assert_eq!(1, Scenario::find("single_hunter").unwrap().cats.next_count());
+        assert!(Scenario::find("no_such_scenario").is_err());
+    }
+    
+    #[test]
+    fn placement_test(){
+        let mut rng = thread_rng();
+        let ring = Placement::Ring { center: PVector::new(300.0, 200.0), radious: 100.0 };
+        assert_eq!(PVector::new(400.0, 200.0), ring.position(0, 4, &mut rng));
+        let quarter = ring.position(1, 4, &mut rng);
+        assert!((quarter.x - 300.0).abs() < 1.0e-9);
+        assert_float!(300.0, quarter.y);
+        
+        // 二つの群れに交互に入る
+        let clusters = Placement::Clusters(vec![(PVector::new(100.0, 100.0), 10.0), (PVector::new(400.0, 300.0), 5.0)]);
+        for n in 0..20 {
+            let position = clusters.position(n, 20, &mut rng);
+            if n % 2 == 0 {
+                assert!(PVector::new(100.0, 100.0).offset(&position, Boundary::Torus).len() <= 10.0);
+            } else {
+                assert!(PVector::new(400.0, 300.0).offset(&position, Boundary::Torus).len() <= 5.0);
+            }
+        }
+        
+        // 画面の外はつながっていれば反対側、そうでなければ端になる
+        let outside = Placement::Points(vec![PVector::new(-10.0, 100.0)]);
+        assert_eq!(PVector::new(WIDTH - 10.0, 100.0), outside.position(0, 1, &mut rng));
+        config::set(Config { boundary: Boundary::Reflect, ..Config::default() });
+        assert_eq!(PVector::new(0.0, 100.0), outside.position(0, 1, &mut rng));
+        config::set(Config::default());
+    }
+    
+    #[test]
+    fn spawn_test(){
+        let population = Population {
+            count: 5,
+            offspring: None,
+            placement: Placement::Points(vec![PVector::new(10.0, 20.0)]),
+            speed: Speed::Fixed(3.0),
+        };
+        let rats: Vec<Rat> = population.spawn();
+        assert_eq!(5, rats.len());
+        for rat in &rats {
+            assert_eq!(PVector::new(10.0, 20.0), rat.position());
+            assert_float!(3.0, rat.as_velocity().len());
+        }
+        
+        // 置き直しても遺伝子は変わらない
+        let cats = vec![Cat::new(), Cat::new()];
+        let placed = Population { count: 2, offspring: None, placement: Placement::Uniform, speed: Speed::Max }.place(&cats);
+        for (cat, moved) in cats.iter().zip(placed.iter()) {
+            assert_eq!(cat.id(), moved.id());
+            assert_eq!(cat.chase_weight, moved.chase_weight);
+            assert_float!(config::get().cat.motion.max_speed, moved.as_velocity().len());
+        }
+    }
+}
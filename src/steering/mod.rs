@@ -0,0 +1,518 @@
+mod test;
+
+use animal;
+use animal::{Animal, Cat, Rat, Species, Gene};
+use boundary::Boundary;
+use config;
+use config::{FalloffConfig, MotionConfig, parse_f64};
+use consts::*;
+use falloff::{Falloff, weighted_sum};
+use obstacle;
+use pvector::PVector;
+use quad_tree::QuadTree;
+use rand::prelude::*;
+use random;
+
+// 周りの個体を探すための木
+pub struct Neighbors<'a> {
+    pub cats: &'a QuadTree<Cat>,
+    pub rats: &'a QuadTree<Rat>,
+}
+
+impl<'a> Neighbors<'a> {
+    // 距離を測る時の画面の端の扱い。木を作った時の設定に従う
+    pub fn boundary(&self) -> Boundary {
+        self.cats.boundary()
+    }
+}
+
+// 個体と周りの状況から加速度ベクトルを計算する振る舞い
+pub trait SteeringBehavior<A: Animal> {
+    fn force(&self, animal: &A, neighbors: &Neighbors) -> PVector;
+}
+
+// 目標に向かう
+#[derive(Debug, Clone)]
+pub struct Seek {
+    pub target: Species,
+    pub radious: f64,
+    pub falloff: Option<Falloff>, // Noneなら種の設定のchase_falloffを使う
+}
+
+// 目標から逃げる
+#[derive(Debug, Clone)]
+pub struct Flee {
+    pub target: Species,
+    pub radious: f64,
+    pub falloff: Option<Falloff>, // Noneなら種の設定のrun_away_falloffを使う
+}
+
+// 目標の速度からlead_time秒後の位置を予測して先回りする
+// lead_timeを遺伝子にすると、予測するかどうかを進化で決められる
+#[derive(Debug, Clone)]
+pub struct Pursue {
+    pub target: Species,
+    pub radious: f64,
+    pub lead_time: Weight,
+    pub falloff: Option<Falloff>,
+}
+
+// 目標の速度からlead_time秒後の位置を予測して逃げる
+#[derive(Debug, Clone)]
+pub struct Evade {
+    pub target: Species,
+    pub radious: f64,
+    pub lead_time: Weight,
+    pub falloff: Option<Falloff>,
+}
+
+// 進行方向を左右にjitterまでランダムに振る
+#[derive(Debug, Clone)]
+pub struct Wander {
+    pub jitter: f64,
+}
+
+// BOIDの個体同士を引き離す
+#[derive(Debug, Clone)]
+pub struct Separation {
+    pub radious: f64,
+    pub falloff: Option<Falloff>,
+}
+
+// BOIDの整列
+#[derive(Debug, Clone)]
+pub struct Alignment {
+    pub radious: f64,
+    pub falloff: Option<Falloff>,
+}
+
+// BOIDの個体が多い場所に行く
+#[derive(Debug, Clone)]
+pub struct Cohesion {
+    pub radious: f64,
+    pub falloff: Option<Falloff>,
+}
+
+// 進行方向のlookaheadまでにある障害物を避ける
+#[derive(Debug, Clone)]
+pub struct ObstacleAvoidance {
+    pub lookahead: f64,
+}
+
+// 設定ファイルで選べる振る舞い
+#[derive(Debug, Clone)]
+pub enum Behavior {
+    Seek(Seek),
+    Flee(Flee),
+    Pursue(Pursue),
+    Evade(Evade),
+    Wander(Wander),
+    Separation(Separation),
+    Alignment(Alignment),
+    Cohesion(Cohesion),
+    ObstacleAvoidance(ObstacleAvoidance),
+}
+
+// 振る舞いの重みや先読み時間。固定値か、個体の遺伝子の値
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Weight {
+    Fixed(f64),
+    Gene(Gene),
+}
+
+#[derive(Debug, Clone)]
+pub struct WeightedBehavior {
+    pub weight: Weight,
+    pub behavior: Behavior,
+}
+
+// 種の設定にある振る舞いを重み付けして足し合わせる。柔らかい壁の力もここで足す
+pub fn steer<A: Animal>(animal: &A, neighbors: &Neighbors) -> PVector {
+    let config = config::get();
+    let wall = config
+        .boundary
+        .wall_force(&animal.position(), config.wall_margin)
+        .mult(config.wall_weight);
+    config
+        .species(animal.species())
+        .behaviors
+        .iter()
+        .map(|weighted| {
+            weighted
+                .behavior
+                .steering()
+                .force(animal, neighbors)
+                .mult(weighted.weight.of(animal))
+        })
+        .fold(wall, |folded, vector| vector.add(folded))
+}
+
+// 操舵力から1ステップ後の速度を求める。力・旋回角・速さは種ごとの上限に収める
+// 老いた個体は最高速が落ちる
+pub fn integrate<A: Animal>(animal: &A, force: &PVector) -> PVector {
+    let config = config::get();
+    let species = config.species(animal.species());
+    let mut motion = species.motion.clone();
+    motion.max_speed = species.life.max_speed(motion.max_speed, animal::age_seconds(animal));
+    limit(&animal.as_velocity(), force, &motion, config.dt)
+}
+
+// 今の速度に力を足した向きへ最高速で進もうとし、dt秒の間の速度の変化・旋回・速さを制限する
+pub fn limit(velocity: &PVector, force: &PVector, motion: &MotionConfig, dt: f64) -> PVector {
+    let desired = velocity.add(force.clone());
+    if desired.len() == 0.0 {
+        return velocity.truncate(motion.max_speed);
+    }
+    let max_turn = motion.max_turn_rate * dt;
+    let steering = desired
+        .normalize()
+        .mult(motion.max_speed)
+        .add(velocity.mult(-1.0))
+        .truncate(motion.max_force * dt);
+    let next = velocity.add(steering).truncate(motion.max_speed);
+    if velocity.len() == 0.0 || next.len() == 0.0 || velocity.angle_to(&next) <= max_turn {
+        return next;
+    }
+    let turn = if velocity.cross(&next) < 0.0 { -max_turn } else { max_turn };
+    velocity.normalize().rotate(turn).mult(next.len())
+}
+
+// 見えている個体の相対位置と速度を集める
+fn sense<A: Animal>(animal: &A, neighbors: &Neighbors, species: Species, radious: f64) -> Vec<(PVector, PVector)> {
+    let boundary = neighbors.boundary();
+    match species {
+        Species::Cat => animal
+            .collect_near_pvectors(neighbors.cats, neighbors, radious)
+            .into_iter()
+            .map(|other| (animal.offset(&other, boundary), other.as_velocity()))
+            .collect(),
+        Species::Rat => animal
+            .collect_near_pvectors(neighbors.rats, neighbors, radious)
+            .into_iter()
+            .map(|other| (animal.offset(&other, boundary), other.as_velocity()))
+            .collect(),
+    }
+}
+
+// 相対位置を距離で重み付けした方向
+fn direction<A: Animal>(animal: &A, neighbors: &Neighbors, species: Species, radious: f64, falloff: Falloff) -> PVector {
+    let offsets = sense(animal, neighbors, species, radious)
+        .into_iter()
+        .map(|(offset, _)| {
+            let dist = offset.len();
+            (offset, dist)
+        })
+        .collect();
+    weighted_sum(offsets, falloff, radious)
+}
+
+// lead_time後の予測位置を距離で重み付けした方向
+fn predicted_direction<A: Animal>(animal: &A, neighbors: &Neighbors, species: Species, radious: f64, lead_time: f64, falloff: Falloff) -> PVector {
+    let offsets = sense(animal, neighbors, species, radious)
+        .into_iter()
+        .map(|(offset, velocity)| {
+            let dist = offset.len();
+            (offset.add(velocity.mult(lead_time)), dist)
+        })
+        .collect();
+    weighted_sum(offsets, falloff, radious)
+}
+
+// 種の設定にある力ごとの減衰
+fn falloffs<A: Animal>(animal: &A) -> FalloffConfig {
+    config::get().species(animal.species()).falloff.clone()
+}
+
+impl<A: Animal> SteeringBehavior<A> for Seek {
+    fn force(&self, animal: &A, neighbors: &Neighbors) -> PVector {
+        let falloff = self.falloff.unwrap_or_else(|| falloffs(animal).chase);
+        direction(animal, neighbors, self.target, self.radious, falloff)
+    }
+}
+
+impl<A: Animal> SteeringBehavior<A> for Flee {
+    fn force(&self, animal: &A, neighbors: &Neighbors) -> PVector {
+        let falloff = self.falloff.unwrap_or_else(|| falloffs(animal).run_away);
+        direction(animal, neighbors, self.target, self.radious, falloff).mult(-1.0)
+    }
+}
+
+impl<A: Animal> SteeringBehavior<A> for Pursue {
+    fn force(&self, animal: &A, neighbors: &Neighbors) -> PVector {
+        let falloff = self.falloff.unwrap_or_else(|| falloffs(animal).chase);
+        predicted_direction(animal, neighbors, self.target, self.radious, self.lead_time.of(animal), falloff)
+    }
+}
+
+impl<A: Animal> SteeringBehavior<A> for Evade {
+    fn force(&self, animal: &A, neighbors: &Neighbors) -> PVector {
+        let falloff = self.falloff.unwrap_or_else(|| falloffs(animal).run_away);
+        predicted_direction(animal, neighbors, self.target, self.radious, self.lead_time.of(animal), falloff).mult(-1.0)
+    }
+}
+
+impl<A: Animal> SteeringBehavior<A> for Wander {
+    fn force(&self, animal: &A, _neighbors: &Neighbors) -> PVector {
+        let mut rng = random::rng();
+        let velocity = animal.as_velocity();
+        let heading = if velocity.len() == 0.0 {
+            rng.gen::<f64>() * 2.0 * std::f64::consts::PI
+        } else {
+            velocity.y.atan2(velocity.x)
+        };
+        let theta = heading + (rng.gen::<f64>() * 2.0 - 1.0) * self.jitter;
+        PVector::new(theta.cos(), theta.sin())
+    }
+}
+
+impl<A: Animal> SteeringBehavior<A> for Separation {
+    fn force(&self, animal: &A, neighbors: &Neighbors) -> PVector {
+        let falloff = self.falloff.unwrap_or_else(|| falloffs(animal).separate);
+        direction(animal, neighbors, animal.species(), self.radious, falloff).mult(-1.0)
+    }
+}
+
+impl<A: Animal> SteeringBehavior<A> for Alignment {
+    fn force(&self, animal: &A, neighbors: &Neighbors) -> PVector {
+        let falloff = self.falloff.unwrap_or_else(|| falloffs(animal).align);
+        let velocities = sense(animal, neighbors, animal.species(), self.radious)
+            .into_iter()
+            .map(|(offset, velocity)| (velocity, offset.len()))
+            .collect();
+        weighted_sum(velocities, falloff, self.radious)
+    }
+}
+
+impl<A: Animal> SteeringBehavior<A> for Cohesion {
+    fn force(&self, animal: &A, neighbors: &Neighbors) -> PVector {
+        let falloff = self.falloff.unwrap_or_else(|| falloffs(animal).cohension);
+        direction(animal, neighbors, animal.species(), self.radious, falloff)
+    }
+}
+
+impl<A: Animal> SteeringBehavior<A> for ObstacleAvoidance {
+    // 進行方向に沿って点を調べ、最初に障害物に近づいた点で障害物から離れる向きを返す
+    // 近い点で見つかるほど強く避ける
+    fn force(&self, animal: &A, _neighbors: &Neighbors) -> PVector {
+        let velocity = animal.as_velocity();
+        if velocity.len() == 0.0 {
+            return PVector::zero();
+        }
+        let heading = velocity.normalize();
+        let config = config::get();
+        for n in 0..(AVOID_PROBES + 1) {
+            let along = self.lookahead * n as f64 / AVOID_PROBES as f64;
+            let probe = animal.position().add(heading.mult(along));
+            let near = obstacle::blocking(&config, animal.species())
+                .find(|obstacle| obstacle.distance(&probe, config.boundary) < AVOID_MARGIN);
+            if let Some(obstacle) = near {
+                return obstacle.normal(&probe, config.boundary).mult(1.0 - along / (self.lookahead + AVOID_MARGIN));
+            }
+        }
+        PVector::zero()
+    }
+}
+
+impl Behavior {
+    // トレイトオブジェクトとして取り出す
+    pub fn steering<A: Animal>(&self) -> &dyn SteeringBehavior<A> {
+        match *self {
+            Behavior::Seek(ref behavior) => behavior,
+            Behavior::Flee(ref behavior) => behavior,
+            Behavior::Pursue(ref behavior) => behavior,
+            Behavior::Evade(ref behavior) => behavior,
+            Behavior::Wander(ref behavior) => behavior,
+            Behavior::Separation(ref behavior) => behavior,
+            Behavior::Alignment(ref behavior) => behavior,
+            Behavior::Cohesion(ref behavior) => behavior,
+            Behavior::ObstacleAvoidance(ref behavior) => behavior,
+        }
+    }
+
+    // 「名前 引数...」の形式を読む。falloffは省略できる
+    //   seek 種類 半径 [falloff]      flee 種類 半径 [falloff]
+    //   pursue 種類 半径 先読み [falloff]  evade 種類 半径 先読み [falloff]  先読みは数値かgene:lead_time
+    //   wander 角度(度)
+    //   separation 半径 [falloff]  alignment 半径 [falloff]  cohesion 半径 [falloff]
+    //   avoid 先読み距離
+    pub fn parse(text: &str) -> Result<Behavior, String> {
+        let words: Vec<&str> = text.split_whitespace().collect();
+        let name = words.first().cloned().unwrap_or("");
+        let args = if words.is_empty() { &words[..] } else { &words[1..] };
+        let behavior = match name {
+            "seek" => {
+                let (target, radious, falloff) = parse_targeted(args, 0)?;
+                Behavior::Seek(Seek { target, radious, falloff })
+            },
+            "flee" => {
+                let (target, radious, falloff) = parse_targeted(args, 0)?;
+                Behavior::Flee(Flee { target, radious, falloff })
+            },
+            "pursue" => {
+                let (target, radious, falloff) = parse_targeted(args, 1)?;
+                let lead_time = Weight::parse(args[2])?;
+                Behavior::Pursue(Pursue { target, radious, lead_time, falloff })
+            },
+            "evade" => {
+                let (target, radious, falloff) = parse_targeted(args, 1)?;
+                let lead_time = Weight::parse(args[2])?;
+                Behavior::Evade(Evade { target, radious, lead_time, falloff })
+            },
+            "wander" if args.len() == 1 => {
+                Behavior::Wander(Wander { jitter: parse_f64(args[0])?.to_radians() })
+            },
+            "separation" => {
+                let (radious, falloff) = parse_radious(args)?;
+                Behavior::Separation(Separation { radious, falloff })
+            },
+            "alignment" => {
+                let (radious, falloff) = parse_radious(args)?;
+                Behavior::Alignment(Alignment { radious, falloff })
+            },
+            "cohesion" => {
+                let (radious, falloff) = parse_radious(args)?;
+                Behavior::Cohesion(Cohesion { radious, falloff })
+            },
+            "avoid" if args.len() == 1 => {
+                Behavior::ObstacleAvoidance(ObstacleAvoidance { lookahead: parse_f64(args[0])? })
+            },
+            "wander" | "avoid" => return Err(format!("`{}` takes one argument", name)),
+            _ => return Err(format!("unknown behavior `{}`", name)),
+        };
+        Ok(behavior)
+    }
+}
+
+// 種類、半径、extra個の引数、省略できるfalloffの順に並んだ引数を読む
+fn parse_targeted(args: &[&str], extra: usize) -> Result<(Species, f64, Option<Falloff>), String> {
+    if args.len() < 2 + extra || args.len() > 3 + extra {
+        return Err(format!("wrong number of arguments `{}`", args.join(" ")));
+    }
+    let target = Species::parse(args[0])?;
+    let radious = parse_f64(args[1])?;
+    let falloff = match args.get(2 + extra) {
+        Some(name) => Some(Falloff::parse(name)?),
+        None => None,
+    };
+    Ok((target, radious, falloff))
+}
+
+// 半径と省略できるfalloffを読む
+fn parse_radious(args: &[&str]) -> Result<(f64, Option<Falloff>), String> {
+    if args.len() < 1 || args.len() > 2 {
+        return Err(format!("wrong number of arguments `{}`", args.join(" ")));
+    }
+    let radious = parse_f64(args[0])?;
+    let falloff = match args.get(1) {
+        Some(name) => Some(Falloff::parse(name)?),
+        None => None,
+    };
+    Ok((radious, falloff))
+}
+
+impl Weight {
+    // 「数値」か「gene:遺伝子名」を読む
+    pub fn parse(text: &str) -> Result<Weight, String> {
+        if text.starts_with("gene:") {
+            Ok(Weight::Gene(Gene::parse(&text[5..])?))
+        } else {
+            Ok(Weight::Fixed(parse_f64(text)?))
+        }
+    }
+
+    // 個体にとっての重み。遺伝子を持たない個体は0になる
+    pub fn of<A: Animal>(&self, animal: &A) -> f64 {
+        match *self {
+            Weight::Fixed(weight) => weight,
+            Weight::Gene(gene) => animal.gene(gene).unwrap_or(0.0),
+        }
+    }
+}
+
+impl WeightedBehavior {
+    // 「重み 名前 引数...」の形式を読む
+    pub fn parse(text: &str) -> Result<WeightedBehavior, String> {
+        let text = text.trim();
+        let split = text.find(char::is_whitespace).unwrap_or(text.len());
+        Ok(WeightedBehavior {
+            weight: Weight::parse(&text[..split])?,
+            behavior: Behavior::parse(&text[split..])?,
+        })
+    }
+
+    // 使っている遺伝子を全て返す
+    pub fn genes(&self) -> Vec<Gene> {
+        let lead_time = match self.behavior {
+            Behavior::Pursue(ref pursue) => Some(pursue.lead_time),
+            Behavior::Evade(ref evade) => Some(evade.lead_time),
+            _ => None,
+        };
+        vec![Some(self.weight), lead_time]
+            .into_iter()
+            .filter_map(|weight| match weight {
+                Some(Weight::Gene(gene)) => Some(gene),
+                _ => None,
+            })
+            .collect()
+    }
+
+    // 「;」で区切られた振る舞いの一覧を読む
+    pub fn parse_list(text: &str) -> Result<Vec<WeightedBehavior>, String> {
+        text
+            .split(';')
+            .filter(|item| !item.trim().is_empty())
+            .map(WeightedBehavior::parse)
+            .collect()
+    }
+}
+
+// 猫の既定の振る舞い。以前のchase、separate、align、cohensionに先読みと障害物よけを加えたもの
+// 先読みの遺伝子が0なら以前のchaseと同じ
+pub fn cat_behaviors() -> Vec<WeightedBehavior> {
+    vec![
+        WeightedBehavior {
+            weight: Weight::Gene(Gene::Chase),
+            behavior: Behavior::Pursue(Pursue {
+                target: Species::Rat,
+                radious: CHASE_RADIOUS,
+                lead_time: Weight::Gene(Gene::LeadTime),
+                falloff: None,
+            }),
+        },
+        WeightedBehavior {
+            weight: Weight::Gene(Gene::Separate),
+            behavior: Behavior::Separation(Separation { radious: SEPARATE_RADIOUS, falloff: None }),
+        },
+        WeightedBehavior {
+            weight: Weight::Gene(Gene::Align),
+            behavior: Behavior::Alignment(Alignment { radious: ALIGN_RADIOUS, falloff: None }),
+        },
+        WeightedBehavior {
+            weight: Weight::Gene(Gene::Cohension),
+            behavior: Behavior::Cohesion(Cohesion { radious: COHENSION_RADIOUS, falloff: None }),
+        },
+        WeightedBehavior {
+            weight: Weight::Fixed(CAT_AVOID_WEIGHT),
+            behavior: Behavior::ObstacleAvoidance(ObstacleAvoidance { lookahead: AVOID_LOOKAHEAD }),
+        },
+    ]
+}
+
+// ねずみの既定の振る舞い。以前のrun_awayに猫の速度からの先読みと障害物よけを加えたもの
+pub fn rat_behaviors() -> Vec<WeightedBehavior> {
+    vec![
+        WeightedBehavior {
+            weight: Weight::Fixed(1.0),
+            behavior: Behavior::Evade(Evade {
+                target: Species::Cat,
+                radious: RUNAWAY_RADIOUS,
+                lead_time: Weight::Gene(Gene::LeadTime),
+                falloff: None,
+            }),
+        },
+        WeightedBehavior {
+            weight: Weight::Fixed(RAT_AVOID_WEIGHT),
+            behavior: Behavior::ObstacleAvoidance(ObstacleAvoidance { lookahead: AVOID_LOOKAHEAD }),
+        },
+    ]
+}
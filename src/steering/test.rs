@@ -0,0 +1,453 @@
+#[cfg(test)]
+mod tests{
+    use animal::{Animal, Cat, Rat, Species, Gene};
+    use config;
+    use config::Config;
+    use consts::*;
+    use falloff::Falloff;
+    use obstacle::Obstacle;
+    use pvector::PVector;
+    use quad_tree::QuadTree;
+    use steering::*;
+    
+    macro_rules! assert_float{
+        (
+            $x: expr ,$y: expr
+        ) => {
+            {
+                assert!((($x - $y) / $x).abs() < 1.0e-9);
+            }
+        }
+    }
+    
+    fn setpos(animal: &mut Cat, pos: &PVector){
+        *animal = animal.set_position(pos);
+    }
+    
+    fn seek() -> Seek {
+        Seek { target: Species::Rat, radious: CHASE_RADIOUS, falloff: None }
+    }
+    
+    #[test]
+    fn seek_test(){
+        let mut cat = <Cat as Animal>::new();
+        let rat = <Rat as Animal>::new();
+        let chased_diff = CHASE_RADIOUS / 2.0;
+        let dx = 0.6;
+        let dy = 0.8;
+        let mut offset = PVector::new(-1.0 * dx * chased_diff, -1.0 * dy * chased_diff);
+        setpos(&mut cat, &rat.position().add(offset));
+        let mut chased: Vec<Rat> = Vec::with_capacity(100);
+        for _ in 0..100 {
+            chased.push(rat.clone());
+        }
+        let chased_tree = QuadTree::new(&chased);
+        let cats_tree = QuadTree::new(&Vec::new());
+        let neighbors = Neighbors { cats: &cats_tree, rats: &chased_tree };
+        let result = seek().force(&cat, &neighbors).mult(Weight::Gene(Gene::Chase).of(&cat));
+        
+        assert_float!(dx * cat.chase_weight, result.x);
+        assert_float!(dy * cat.chase_weight, result.y);
+        
+        let not_chase_diff = CHASE_RADIOUS;
+        offset = PVector::new(-0.6 * not_chase_diff, -0.8 * not_chase_diff);
+        setpos(&mut cat, &rat.position().add(offset));
+        
+        let not_chase = seek().force(&cat, &neighbors);
+        assert_eq!(not_chase.x, 0.0);
+        assert_eq!(not_chase.y, 0.0);
+    }
+    
+    #[test]
+    fn separation_test(){
+        let mut cat = <Cat as Animal>::new();
+        let other = <Cat as Animal>::new();
+        let separate_diff = SEPARATE_RADIOUS / 2.0;
+        let x = 0.6;
+        let y = 0.8;
+        setpos(&mut cat, &PVector::new(separate_diff * x, separate_diff * y).add(other.position()));
+        let mut others: Vec<Cat> = Vec::with_capacity(100);
+        for _ in 0..100 {
+            others.push(other.clone());
+        }
+        let others_tree = QuadTree::new(&others);
+        let rats_tree = QuadTree::new(&Vec::new());
+        let neighbors = Neighbors { cats: &others_tree, rats: &rats_tree };
+        let separation = Separation { radious: SEPARATE_RADIOUS, falloff: None };
+        let result = separation.force(&cat, &neighbors).mult(cat.separate_weight);
+        
+        assert_float!(x * cat.separate_weight, result.x);
+        assert_float!(y * cat.separate_weight, result.y);
+        
+        let not_separate_diff = SEPARATE_RADIOUS;
+        setpos(&mut cat, &PVector::new(not_separate_diff, not_separate_diff).add(other.position()));
+        
+        let not_separate = separation.force(&cat, &neighbors);
+        assert_eq!(not_separate.x, 0.0);
+        assert_eq!(not_separate.y, 0.0);
+    }
+    
+    #[test]
+    fn alignment_test(){
+        let mut cat = <Cat as Animal>::new();
+        let align_diff = ALIGN_RADIOUS / 2.0;
+        let x = 0.6;
+        let y = 0.8;
+        let other = <Cat as Animal>::new().apply_velocity(&PVector{x: x, y: y});
+        setpos(&mut cat, &PVector::new(-align_diff, -align_diff).add(other.position()));
+        let mut others: Vec<Cat> = Vec::with_capacity(100);
+        for _ in 0..100 {
+            others.push(other.clone());
+        }
+        let others_tree = QuadTree::new(&others);
+        let rats_tree = QuadTree::new(&Vec::new());
+        let neighbors = Neighbors { cats: &others_tree, rats: &rats_tree };
+        let alignment = Alignment { radious: ALIGN_RADIOUS, falloff: None };
+        let result = alignment.force(&cat, &neighbors).mult(cat.align_weight);
+        
+        assert_float!(x * cat.align_weight, result.x);
+        assert_float!(y * cat.align_weight, result.y);
+        
+        let not_align_diff = ALIGN_RADIOUS;
+        setpos(&mut cat, &PVector::new(-not_align_diff, -not_align_diff).add(other.position()));
+        
+        let not_align = alignment.force(&cat, &neighbors);
+        assert_eq!(not_align.x, 0.0);
+        assert_eq!(not_align.y, 0.0);
+    }
+    
+    #[test]
+    fn cohesion_test(){
+        let mut cat = <Cat as Animal>::new();
+        let other = <Cat as Animal>::new();
+        let cohension_diff = COHENSION_RADIOUS / 2.0;
+        let x = 0.6;
+        let y = 0.8;
+        setpos(&mut cat, &PVector::new(-cohension_diff * x, -cohension_diff * y).add(other.position()));
+        let mut others: Vec<Cat> = Vec::with_capacity(100);
+        for _ in 0..100 {
+            others.push(other.clone());
+        }
+        let others_tree = QuadTree::new(&others);
+        let rats_tree = QuadTree::new(&Vec::new());
+        let neighbors = Neighbors { cats: &others_tree, rats: &rats_tree };
+        let cohesion = Cohesion { radious: COHENSION_RADIOUS, falloff: None };
+        let result = cohesion.force(&cat, &neighbors).mult(cat.cohension_weight);
+        
+        assert_float!(x * cat.cohension_weight, result.x);
+        assert_float!(y * cat.cohension_weight, result.y);
+        
+        let not_cohension_diff = COHENSION_RADIOUS;
+        setpos(&mut cat, &PVector::new(-not_cohension_diff, -not_cohension_diff).add(other.position()));
+        
+        let not_cohension = cohesion.force(&cat, &neighbors);
+        assert_eq!(not_cohension.x, 0.0);
+        assert_eq!(not_cohension.y, 0.0);
+    }
+    
+    #[test]
+    fn flee_test(){
+        let cat = <Cat as Animal>::new();
+        let runaway_diff = RUNAWAY_RADIOUS / 2.0;
+        let x = 0.6;
+        let y = 0.8;
+        let mut rat = <Rat as Animal>::new()
+            .set_position(&PVector::new(runaway_diff * x, runaway_diff * y).add(cat.position()));
+        let mut cats: Vec<Cat> = Vec::with_capacity(100);
+        for _ in 0..100 {
+            cats.push(cat.clone());
+        }
+        let cats_tree = QuadTree::new(&cats);
+        let rats_tree = QuadTree::new(&Vec::new());
+        let neighbors = Neighbors { cats: &cats_tree, rats: &rats_tree };
+        let flee = Flee { target: Species::Cat, radious: RUNAWAY_RADIOUS, falloff: None };
+        let result = flee.force(&rat, &neighbors);
+        
+        assert_float!(x, result.x);
+        assert_float!(y, result.y);
+        
+        let not_chase_diff = CHASE_RADIOUS;
+        rat = rat.set_position(&PVector::new(not_chase_diff, not_chase_diff).add(cat.position()));
+        
+        let not_chase = flee.force(&rat, &neighbors);
+        assert_eq!(not_chase.x, 0.0);
+        assert_eq!(not_chase.y, 0.0);
+    }
+    
+    #[test]
+    fn pursue_evade_test(){
+        // 右に5.5離れたねずみが上に動いている
+        let cat = Cat::new().set_position(&PVector::new(100.5, 100.0));
+        let rat = Rat::new()
+            .set_position(&PVector::new(106.0, 100.0))
+            .apply_velocity(&PVector::new(0.0, 1.0));
+        let rats_tree = QuadTree::new(&vec![rat.clone()]);
+        let cats_tree = QuadTree::new(&vec![cat.clone()]);
+        let neighbors = Neighbors { cats: &cats_tree, rats: &rats_tree };
+        
+        // 先読みしなければ今の位置に向かう
+        let now = Pursue { target: Species::Rat, radious: 10.0, lead_time: Weight::Fixed(0.0), falloff: None };
+        assert_eq!(seek().force(&cat, &neighbors), now.force(&cat, &neighbors));
+        
+        // 5.5フレーム先の位置に向かう
+        let ahead = Pursue { target: Species::Rat, radious: 10.0, lead_time: Weight::Fixed(5.5), falloff: None };
+        let result = ahead.force(&cat, &neighbors);
+        assert_float!(0.5_f64.sqrt(), result.x);
+        assert_float!(0.5_f64.sqrt(), result.y);
+        
+        // 猫の予測位置から離れる
+        let chasing = cat.apply_velocity(&PVector::new(0.0, 1.0));
+        let cats_tree = QuadTree::new(&vec![chasing]);
+        let neighbors = Neighbors { cats: &cats_tree, rats: &rats_tree };
+        let evade = Evade { target: Species::Cat, radious: 10.0, lead_time: Weight::Fixed(5.5), falloff: None };
+        let result = evade.force(&rat, &neighbors);
+        assert_float!(0.5_f64.sqrt(), result.x);
+        assert_float!(-(0.5_f64.sqrt()), result.y);
+    }
+    
+    #[test]
+    fn lead_time_gene_test(){
+        // 先読み時間を遺伝子から読む
+        let mut cat = Cat::new().set_position(&PVector::new(100.5, 100.0));
+        let rat = Rat::new()
+            .set_position(&PVector::new(106.0, 100.0))
+            .apply_velocity(&PVector::new(0.0, 1.0));
+        let rats_tree = QuadTree::new(&vec![rat.clone()]);
+        let cats_tree = QuadTree::new(&Vec::new());
+        let neighbors = Neighbors { cats: &cats_tree, rats: &rats_tree };
+        let pursue = Pursue { target: Species::Rat, radious: 10.0, lead_time: Weight::Gene(Gene::LeadTime), falloff: None };
+        
+        cat.lead_time = 0.0;
+        assert_eq!(seek().force(&cat, &neighbors), pursue.force(&cat, &neighbors));
+        cat.lead_time = 5.5;
+        let result = pursue.force(&cat, &neighbors);
+        assert_float!(0.5_f64.sqrt(), result.x);
+        assert_float!(0.5_f64.sqrt(), result.y);
+        
+        // ねずみも先読み時間の遺伝子を持つ
+        assert_eq!(Some(rat.lead_time), rat.gene(Gene::LeadTime));
+        assert!(0.0 <= rat.lead_time && rat.lead_time <= LEAD_TIME_MAX);
+    }
+    
+    #[test]
+    fn wander_test(){
+        let cat = Cat::new().apply_velocity(&PVector::new(2.0, 0.0));
+        let cats_tree = QuadTree::new(&Vec::new());
+        let rats_tree = QuadTree::new(&Vec::new());
+        let neighbors = Neighbors { cats: &cats_tree, rats: &rats_tree };
+        let wander = Wander { jitter: 0.5 };
+        for _ in 0..100 {
+            let result = wander.force(&cat, &neighbors);
+            assert_float!(1.0, result.len());
+            assert!(cat.as_velocity().angle_to(&result) <= 0.5 + 1.0e-9);
+        }
+    }
+    
+    #[test]
+    fn obstacle_avoidance_test(){
+        let cat = Cat::new()
+            .set_position(&PVector::new(100.0, 100.0))
+            .apply_velocity(&PVector::new(1.0, 0.0));
+        let cats_tree = QuadTree::new(&Vec::new());
+        let rats_tree = QuadTree::new(&Vec::new());
+        let neighbors = Neighbors { cats: &cats_tree, rats: &rats_tree };
+        let avoid = ObstacleAvoidance { lookahead: 20.0 };
+        
+        // 障害物がなければ何もしない
+        assert_eq!(PVector::zero(), avoid.force(&cat, &neighbors));
+        
+        // 少し上にずれた障害物に向かっているので下へ避ける
+        let mut with_obstacle = Config::default();
+        with_obstacle.obstacles.push(Obstacle::Circle { center: PVector::new(115.0, 98.0), radious: 4.0 });
+        config::set(with_obstacle);
+        let result = avoid.force(&cat, &neighbors);
+        
+        // 遠くの障害物は弱く避ける
+        let mut far_obstacle = Config::default();
+        far_obstacle.obstacles.push(Obstacle::Circle { center: PVector::new(120.0, 98.0), radious: 4.0 });
+        config::set(far_obstacle);
+        let far = avoid.force(&cat, &neighbors);
+        config::set(Config::default());
+        
+        assert!(result.y > 0.0);
+        assert!(result.len() > 0.0 && result.len() <= 1.0);
+        assert!(far.len() < result.len());
+    }
+    
+    #[test]
+    fn steer_test(){
+        let cat = Cat::new().set_position(&PVector::new(100.0, 100.0));
+        let other = Cat::new().set_position(&PVector::new(103.0, 104.0));
+        let rat = Rat::new().set_position(&PVector::new(94.0, 92.0));
+        let cats_tree = QuadTree::new(&vec![cat.clone(), other]);
+        let rats_tree = QuadTree::new(&vec![rat]);
+        let neighbors = Neighbors { cats: &cats_tree, rats: &rats_tree };
+        
+        // 既定の振る舞いは以前のchase、separate、align、cohensionに先読みを加えたものの和
+        let pursue = Pursue { target: Species::Rat, radious: CHASE_RADIOUS, lead_time: Weight::Gene(Gene::LeadTime), falloff: None };
+        let expect = pursue.force(&cat, &neighbors).mult(cat.chase_weight)
+            .add(Separation { radious: SEPARATE_RADIOUS, falloff: None }.force(&cat, &neighbors).mult(cat.separate_weight))
+            .add(Alignment { radious: ALIGN_RADIOUS, falloff: None }.force(&cat, &neighbors).mult(cat.align_weight))
+            .add(Cohesion { radious: COHENSION_RADIOUS, falloff: None }.force(&cat, &neighbors).mult(cat.cohension_weight));
+        let result = steer(&cat, &neighbors);
+        assert_float!(expect.x, result.x);
+        assert_float!(expect.y, result.y);
+        
+        // 設定で振る舞いを入れ替える
+        let mut flee_only = Config::default();
+        flee_only.parse("cat.behaviors = 2.0 flee rat 20 linear").unwrap();
+        config::set(flee_only);
+        let result = steer(&cat, &neighbors);
+        config::set(Config::default());
+        assert_float!(2.0 * 0.6 * 0.5, result.x);
+        assert_float!(2.0 * 0.8 * 0.5, result.y);
+    }
+    
+    #[test]
+    fn falloff_default_test(){
+        // falloffを省略した振る舞いは種の設定を使う
+        let cat = Cat::new().set_position(&PVector::new(100.0, 100.0));
+        let rat = Rat::new().set_position(&PVector::new(105.5, 100.0));
+        let cats_tree = QuadTree::new(&Vec::new());
+        let rats_tree = QuadTree::new(&vec![rat]);
+        let neighbors = Neighbors { cats: &cats_tree, rats: &rats_tree };
+        assert_float!(1.0, seek().force(&cat, &neighbors).x);
+        let mut linear = Config::default();
+        linear.cat.falloff.chase = Falloff::Linear;
+        config::set(linear);
+        let result = seek().force(&cat, &neighbors);
+        config::set(Config::default());
+        assert_float!(0.45, result.x);
+    }
+    
+    #[test]
+    fn parse_behavior_test(){
+        match Behavior::parse("seek rat 12.5 gaussian").unwrap() {
+            Behavior::Seek(seek) => {
+                assert_eq!(seek.target, Species::Rat);
+                assert_eq!(seek.radious, 12.5);
+                assert_eq!(seek.falloff, Some(Falloff::Gaussian));
+            },
+            other => panic!("{:?}", other),
+        }
+        match Behavior::parse("evade cat 10 3").unwrap() {
+            Behavior::Evade(evade) => {
+                assert_eq!(evade.target, Species::Cat);
+                assert_eq!(evade.lead_time, Weight::Fixed(3.0));
+                assert_eq!(evade.falloff, None);
+            },
+            other => panic!("{:?}", other),
+        }
+        match Behavior::parse("wander 90").unwrap() {
+            Behavior::Wander(wander) => assert_float!(std::f64::consts::PI / 2.0, wander.jitter),
+            other => panic!("{:?}", other),
+        }
+        assert!(Behavior::parse("flee cat 10").is_ok());
+        assert!(Behavior::parse("pursue rat 10 2 linear").is_ok());
+        assert!(Behavior::parse("separation 5").is_ok());
+        assert!(Behavior::parse("alignment 5 inverse").is_ok());
+        assert!(Behavior::parse("cohesion 5").is_ok());
+        assert!(Behavior::parse("avoid 20").is_ok());
+        
+        assert!(Behavior::parse("seek dog 10").is_err());
+        assert!(Behavior::parse("seek rat").is_err());
+        assert!(Behavior::parse("pursue rat 10").is_err());
+        assert!(Behavior::parse("separation 5 cubic").is_err());
+        assert!(Behavior::parse("wander").is_err());
+        assert!(Behavior::parse("dance 5").is_err());
+        assert!(Behavior::parse("").is_err());
+    }
+    
+    #[test]
+    fn parse_weighted_test(){
+        let list = WeightedBehavior::parse_list("gene:chase seek rat 10; 0.5 wander 30;").unwrap();
+        assert_eq!(list.len(), 2);
+        assert_eq!(list[0].weight, Weight::Gene(Gene::Chase));
+        assert_eq!(list[1].weight, Weight::Fixed(0.5));
+        assert_eq!(list[0].genes(), vec![Gene::Chase]);
+        let pursue = WeightedBehavior::parse("gene:chase pursue rat 10 gene:lead_time").unwrap();
+        assert_eq!(pursue.genes(), vec![Gene::Chase, Gene::LeadTime]);
+        assert!(WeightedBehavior::parse_list("gene:tail seek rat 10").is_err());
+        assert!(WeightedBehavior::parse_list("heavy seek rat 10").is_err());
+        
+        // ねずみは先読み時間以外の遺伝子を使えない
+        let mut config = Config::default();
+        assert!(config.parse("rat.behaviors = gene:chase flee cat 10").is_err());
+        assert!(config.parse("rat.behaviors = 1 evade cat 10 gene:align").is_err());
+        assert!(config.parse("rat.behaviors = 1 evade cat 10 gene:lead_time").is_ok());
+        assert!(config.parse("cat.behaviors = gene:chase pursue rat 10 gene:lead_time").is_ok());
+        assert!(config.parse("rat.behaviors = 1 flee cat 10; 0.2 wander 45").is_ok());
+        assert_eq!(config.rat.behaviors.len(), 2);
+    }
+    
+    #[test]
+    fn weight_test(){
+        let cat = Cat::new();
+        let rat = Rat::new();
+        assert_eq!(cat.align_weight, Weight::Gene(Gene::Align).of(&cat));
+        assert_eq!(0.0, Weight::Gene(Gene::Align).of(&rat));
+        assert_eq!(1.5, Weight::Fixed(1.5).of(&rat));
+    }
+    
+    fn motion(max_force: f64, max_turn_rate: f64) -> MotionConfig {
+        MotionConfig { max_force, max_turn_rate, max_speed: 2.0 }
+    }
+    
+    #[test]
+    fn limit_force_test(){
+        // 真後ろへの力でも一度には反転できない
+        let velocity = PVector::new(2.0, 0.0);
+        let next = limit(&velocity, &PVector::new(-100.0, 0.0), &motion(0.5, std::f64::consts::PI), 1.0);
+        assert_float!(1.5, next.x);
+        assert_eq!(0.0, next.y);
+        // 変化できる量は1ステップの秒数に比例する
+        let next = limit(&velocity, &PVector::new(-100.0, 0.0), &motion(0.5, std::f64::consts::PI), 0.5);
+        assert_float!(1.75, next.x);
+        
+        // 力がなければ最高速まで加速する
+        let next = limit(&PVector::new(1.0, 0.0), &PVector::zero(), &motion(0.5, 1.0), 1.0);
+        assert_float!(1.5, next.x);
+        let next = limit(&PVector::new(1.9, 0.0), &PVector::zero(), &motion(0.5, 1.0), 1.0);
+        assert_float!(2.0, next.x);
+    }
+    
+    #[test]
+    fn limit_turn_test(){
+        let turn_rate = 10.0_f64.to_radians();
+        let velocity = PVector::new(2.0, 0.0);
+        for force in vec![PVector::new(0.0, 100.0), PVector::new(0.0, -100.0), PVector::new(-100.0, 1.0)] {
+            let next = limit(&velocity, &force, &motion(10.0, turn_rate), 1.0);
+            assert!(velocity.angle_to(&next) <= turn_rate + 1.0e-9);
+            assert!(next.len() <= 2.0 + 1.0e-9);
+            // 力の向きへ曲がる
+            assert!(velocity.cross(&next) * velocity.cross(&force) > 0.0);
+        }
+        
+        // 小さな方向転換はそのまま
+        let next = limit(&velocity, &PVector::new(0.0, 0.1), &motion(10.0, turn_rate), 1.0);
+        assert!(velocity.angle_to(&next) < turn_rate);
+        assert_float!(2.0, next.len());
+        
+        // 力は今の速度に足されるので、弱い力ほど少しだけ曲がる
+        let weak = limit(&velocity, &PVector::new(0.0, 0.1), &motion(10.0, turn_rate), 1.0);
+        let strong = limit(&velocity, &PVector::new(0.0, 0.2), &motion(10.0, turn_rate), 1.0);
+        assert!(velocity.angle_to(&weak) < velocity.angle_to(&strong));
+    }
+    
+    #[test]
+    fn integrate_test(){
+        // 種ごとの最高速を守る
+        let mut cat = Cat::new();
+        cat = cat.apply_velocity(&PVector::new(500.0, 0.0));
+        let next = integrate(&cat, &PVector::zero());
+        assert_float!(config::get().cat.motion.max_speed, next.len());
+        
+        // ねずみは猫より大きく曲がれる
+        let rat = Rat::new().apply_velocity(&PVector::new(1.0, 0.0));
+        let cat = Cat::new().apply_velocity(&PVector::new(1.0, 0.0));
+        let force = PVector::new(0.0, 100.0);
+        let rat_turn = rat.as_velocity().angle_to(&integrate(&rat, &force));
+        let cat_turn = cat.as_velocity().angle_to(&integrate(&cat, &force));
+        assert!(rat_turn > cat_turn);
+    }
+}
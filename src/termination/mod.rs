@@ -0,0 +1,139 @@
+mod test;
+
+// 世代を終える条件
+#[derive(Debug, Clone, PartialEq)]
+pub enum Condition {
+    Frames(u64),      // 進めたステップ数がこれに達した
+    WallClock(f64),   // 世代が始まってから実時間でこの秒数が経った
+    CatsDead,         // 猫が全滅した
+    RatsBelow(usize), // ねずみがこの数より少なくなった
+    Stagnation(u64),  // このステップ数のあいだ誰も食べられていない
+}
+
+// 条件の組み合わせ方
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Combine {
+    Any, // どれか一つを満たせば終わる
+    All, // すべてを満たしたら終わる
+}
+
+// 世代の終わり方
+#[derive(Debug, Clone)]
+pub struct Termination {
+    pub conditions: Vec<Condition>,
+    pub combine: Combine,
+}
+
+// 終わりの判定に使う世代の様子
+pub struct Progress {
+    pub steps: u64,
+    pub elapsed: f64, // 実時間の秒数
+    pub cats: usize,
+    pub rats: usize,
+    pub since_kill: u64, // 最後に食べられてから進めたステップ数
+}
+
+impl Condition {
+    // 「frames 数」「wall_clock 秒」「cats_dead」「rats_below 数」「stagnation 数」を読む
+    pub fn parse(text: &str) -> Result<Condition, String> {
+        let words: Vec<&str> = text.split_whitespace().collect();
+        let word = |name: &str| -> Result<&str, String> {
+            if words.len() != 2 {
+                return Err(format!("`{}` needs one number", name));
+            }
+            Ok(words[1])
+        };
+        let number = |name: &str| -> Result<f64, String> {
+            let word = word(name)?;
+            let value = word.parse::<f64>().map_err(|_| format!("`{}` is not a number", word))?;
+            if value <= 0.0 {
+                return Err(format!("`{}` must be positive", word));
+            }
+            Ok(value)
+        };
+        // ステップ数や匹数は小数を切り捨てると意味が変わるので整数だけ受け付ける
+        let count = |name: &str| -> Result<u64, String> {
+            let word = word(name)?;
+            match word.parse::<u64>() {
+                Ok(value) if value > 0 => Ok(value),
+                Ok(_) => Err(format!("`{}` must be positive", word)),
+                Err(_) => Err(format!("`{}` is not a positive integer", word)),
+            }
+        };
+        match words.first() {
+            Some(&"frames") => Ok(Condition::Frames(count("frames")?)),
+            Some(&"wall_clock") => Ok(Condition::WallClock(number("wall_clock")?)),
+            Some(&"cats_dead") if words.len() == 1 => Ok(Condition::CatsDead),
+            Some(&"rats_below") => Ok(Condition::RatsBelow(count("rats_below")? as usize)),
+            Some(&"stagnation") => Ok(Condition::Stagnation(count("stagnation")?)),
+            _ => Err(format!("unknown end condition `{}`", text.trim())),
+        }
+    }
+
+    // 出力で使う名前
+    pub fn name(&self) -> &'static str {
+        match *self {
+            Condition::Frames(_) => "frames",
+            Condition::WallClock(_) => "wall_clock",
+            Condition::CatsDead => "cats_dead",
+            Condition::RatsBelow(_) => "rats_below",
+            Condition::Stagnation(_) => "stagnation",
+        }
+    }
+
+    // 条件を満たしているかどうか
+    pub fn is_met(&self, progress: &Progress) -> bool {
+        match *self {
+            Condition::Frames(frames) => progress.steps >= frames,
+            Condition::WallClock(seconds) => progress.elapsed >= seconds,
+            Condition::CatsDead => progress.cats == 0,
+            Condition::RatsBelow(count) => progress.rats < count,
+            Condition::Stagnation(frames) => progress.since_kill >= frames,
+        }
+    }
+}
+
+impl Combine {
+    // 設定ファイルの名前から読む
+    pub fn parse(text: &str) -> Result<Combine, String> {
+        match text {
+            "any" => Ok(Combine::Any),
+            "all" => Ok(Combine::All),
+            _ => Err(format!("unknown combination `{}`", text)),
+        }
+    }
+}
+
+impl Termination {
+    // 「;」で区切られた条件の一覧を読む
+    pub fn parse_list(text: &str) -> Result<Vec<Condition>, String> {
+        let list = text
+            .split(';')
+            .filter(|item| !item.trim().is_empty())
+            .map(Condition::parse)
+            .collect::<Result<Vec<Condition>, String>>()?;
+        if list.is_empty() {
+            return Err(String::from("no end condition"));
+        }
+        Ok(list)
+    }
+
+    // 世代が終わるなら満たした条件の名前を「+」でつないで返す
+    pub fn check(&self, progress: &Progress) -> Option<String> {
+        let met: Vec<&'static str> = self
+            .conditions
+            .iter()
+            .filter(|condition| condition.is_met(progress))
+            .map(|condition| condition.name())
+            .collect();
+        let ended = match self.combine {
+            Combine::Any => !met.is_empty(),
+            Combine::All => !self.conditions.is_empty() && met.len() == self.conditions.len(),
+        };
+        if ended {
+            Some(met.join("+"))
+        } else {
+            None
+        }
+    }
+}
@@ -0,0 +1,46 @@
+#[cfg(test)]
+mod tests{
+    use termination::{Combine, Condition, Progress, Termination};
+
+    fn progress(steps: u64, cats: usize, rats: usize, since_kill: u64) -> Progress {
+        Progress { steps, elapsed: 0.0, cats, rats, since_kill }
+    }
+
+    #[test]
+    fn parse_test(){
+        assert_eq!(Ok(Condition::Frames(600)), Condition::parse("frames 600"));
+        assert_eq!(Ok(Condition::WallClock(1.5)), Condition::parse(" wall_clock 1.5 "));
+        assert_eq!(Ok(Condition::CatsDead), Condition::parse("cats_dead"));
+        assert_eq!(Ok(Condition::RatsBelow(10)), Condition::parse("rats_below 10"));
+        assert_eq!(Ok(Condition::Stagnation(300)), Condition::parse("stagnation 300"));
+        assert!(Condition::parse("frames").is_err());
+        assert!(Condition::parse("frames 0").is_err());
+        assert!(Condition::parse("frames 0.5").is_err());
+        assert!(Condition::parse("rats_below 0.5").is_err());
+        assert!(Condition::parse("stagnation 1e3").is_err());
+        assert!(Condition::parse("cats_dead 3").is_err());
+        assert!(Condition::parse("forever").is_err());
+        assert_eq!(2, Termination::parse_list("frames 10; cats_dead;").unwrap().len());
+        // 条件がなければ終わらないので読み込めない
+        assert!(Termination::parse_list("").is_err());
+        assert!(Termination::parse_list(" ; ").is_err());
+        assert_eq!(Ok(Combine::All), Combine::parse("all"));
+        assert!(Combine::parse("some").is_err());
+    }
+
+    #[test]
+    fn check_test(){
+        let mut termination = Termination {
+            conditions: vec![Condition::Frames(100), Condition::RatsBelow(5), Condition::Stagnation(50)],
+            combine: Combine::Any,
+        };
+        assert_eq!(None, termination.check(&progress(10, 3, 10, 10)));
+        assert_eq!(Some(String::from("frames")), termination.check(&progress(100, 3, 10, 10)));
+        assert_eq!(Some(String::from("rats_below+stagnation")), termination.check(&progress(10, 3, 4, 50)));
+
+        // allならすべてを満たすまで続く
+        termination.combine = Combine::All;
+        assert_eq!(None, termination.check(&progress(100, 3, 4, 10)));
+        assert_eq!(Some(String::from("frames+rats_below+stagnation")), termination.check(&progress(100, 3, 4, 50)));
+    }
+}
@@ -0,0 +1,88 @@
+mod test;
+
+use animal::Animal;
+use config;
+use config::VisionConfig;
+use consts::*;
+use obstacle;
+use pvector::PVector;
+use quad_tree::QuadTree;
+use rand::prelude::*;
+use random;
+use steering::Neighbors;
+use std::f64::consts::PI;
+
+// 個体ごとの視覚。設定で進化させる場合は遺伝子として子孫に受け継がれる
+#[derive(Debug, Clone)]
+pub struct Vision {
+    pub view_angle: f64,
+    pub blind_spot: f64,
+}
+
+impl Vision {
+    // 種の設定から初期化
+    pub fn new(config: &VisionConfig) -> Vision {
+        Vision {
+            view_angle: config.view_angle,
+            blind_spot: config.blind_spot,
+        }
+    }
+    
+    // 向きに対する相対位置が見えるかどうか。重なっている個体は常に見える
+    pub fn in_sight(&self, heading: &PVector, offset: &PVector) -> bool {
+        if heading.len() == 0.0 || offset.len() == 0.0 {
+            return true;
+        }
+        let angle = heading.angle_to(offset);
+        angle <= self.view_angle / 2.0 && angle <= PI - self.blind_spot / 2.0
+    }
+    
+    // 子孫を残す時に角度を少し変化させる
+    pub fn mutate(&self) -> Vision {
+        let mut rng = random::rng();
+        let mut mutate = |value: f64| {
+            (rng.gen::<f64>() * VISION_MUTATE_ABS * 2.0 - VISION_MUTATE_ABS + value)
+                .min(2.0 * PI)
+                .max(0.0)
+        };
+        Vision {
+            view_angle: mutate(self.view_angle),
+            blind_spot: mutate(self.blind_spot),
+        }
+    }
+}
+
+// 視野の中で一定半径以内にいる個体を集める。設定によっては周りの個体や障害物に遮られたものを除く
+pub fn collect_visible<S: Animal, T: Animal>(observer: &S, animals: &QuadTree<T>, neighbors: &Neighbors, radious: f64) -> Vec<T> {
+    let vision = observer.vision();
+    let heading = observer.as_velocity();
+    let occlusion = config::get().species(observer.species()).vision.occlusion;
+    let boundary = animals.boundary();
+    animals
+        .search_cone(&observer.position(), &heading, vision.view_angle / 2.0, radious)
+        .into_iter()
+        .filter(|animal| !animal.is_same(observer))
+        .filter(|animal| vision.in_sight(&heading, &observer.offset(animal, boundary)))
+        .filter(|animal| !occlusion || !is_occluded(observer, animal, neighbors))
+        .collect()
+}
+
+// 観察者と対象の間に、猫かねずみのどちらかの他の個体か障害物があるかどうか
+pub fn is_occluded<S: Animal, T: Animal>(observer: &S, target: &T, neighbors: &Neighbors) -> bool {
+    let offset = observer.offset(target, neighbors.boundary());
+    if offset.len() == 0.0 {
+        return false;
+    }
+    obstacle::blocks_sight(&observer.position(), &offset)
+        || is_blocked_by(observer, target, &offset, neighbors.cats)
+        || is_blocked_by(observer, target, &offset, neighbors.rats)
+}
+
+// 観察者から対象への線の上に、木の中の他の個体がいるかどうか
+fn is_blocked_by<S: Animal, T: Animal, U: Animal>(observer: &S, target: &T, offset: &PVector, animals: &QuadTree<U>) -> bool {
+    let dist = offset.len();
+    animals
+        .raycast(&observer.position(), offset, dist, OCCLUDER_RADIOUS)
+        .into_iter()
+        .any(|(along, other)| along < dist && !other.is_same(target) && !other.is_same(observer))
+}
@@ -0,0 +1,140 @@
+#[cfg(test)]
+mod tests{
+    use animal::{Animal, Cat, Rat};
+    use config;
+    use config::Config;
+    use consts::*;
+    use pvector::PVector;
+    use obstacle::Obstacle;
+    use quad_tree::QuadTree;
+    use steering::Neighbors;
+    use vision::{Vision, collect_visible, is_occluded};
+    use std::f64::consts::PI;
+    
+    fn positioned_rat(x: f64, y: f64) -> Rat {
+        Rat::new().set_position(&PVector::new(x, y))
+    }
+    
+    fn facing_cat(x: f64, y: f64, heading: PVector) -> Cat {
+        Cat::new().set_position(&PVector::new(x, y)).apply_velocity(&heading)
+    }
+    
+    #[test]
+    fn in_sight_test(){
+        let heading = PVector::new(1.0, 0.0);
+        let front = Vision { view_angle: PI / 2.0, blind_spot: 0.0 };
+        assert!(front.in_sight(&heading, &PVector::new(1.0, 0.9)));
+        assert!(!front.in_sight(&heading, &PVector::new(1.0, 1.1)));
+        assert!(!front.in_sight(&heading, &PVector::new(-1.0, 0.0)));
+        
+        let all = Vision { view_angle: 2.0 * PI, blind_spot: 0.0 };
+        assert!(all.in_sight(&heading, &PVector::new(-1.0, 0.0)));
+        
+        // 真後ろの死角
+        let blind = Vision { view_angle: 2.0 * PI, blind_spot: PI / 2.0 };
+        assert!(!blind.in_sight(&heading, &PVector::new(-1.0, 0.0)));
+        assert!(!blind.in_sight(&heading, &PVector::new(-1.0, 0.9)));
+        assert!(blind.in_sight(&heading, &PVector::new(-1.0, 1.1)));
+        assert!(blind.in_sight(&heading, &PVector::new(0.0, 1.0)));
+        
+        // 止まっている個体や重なっている個体は見える
+        assert!(front.in_sight(&PVector::zero(), &PVector::new(-1.0, 0.0)));
+        assert!(blind.in_sight(&heading, &PVector::zero()));
+    }
+    
+    #[test]
+    fn mutate_test(){
+        let vision = Vision { view_angle: 2.0 * PI, blind_spot: 0.0 };
+        for _ in 0..100 {
+            let child = vision.mutate();
+            assert!(child.view_angle <= 2.0 * PI && 2.0 * PI - child.view_angle < VISION_MUTATE_ABS);
+            assert!(0.0 <= child.blind_spot && child.blind_spot < VISION_MUTATE_ABS);
+        }
+    }
+    
+    #[test]
+    fn collect_visible_view_angle_test(){
+        let mut narrow = Config::default();
+        narrow.cat.vision.view_angle = PI / 2.0;
+        config::set(narrow);
+        let cat = facing_cat(100.0, 100.0, PVector::new(1.0, 0.0));
+        let ahead = positioned_rat(105.5, 101.0);
+        let behind = positioned_rat(94.5, 101.0);
+        let tree = QuadTree::new(&vec![ahead.clone(), behind.clone()]);
+        let cats = QuadTree::new(&vec![cat.clone()]);
+        let neighbors = Neighbors { cats: &cats, rats: &tree };
+        let visible = collect_visible(&cat, &tree, &neighbors, 10.0);
+        config::set(Config::default());
+        
+        assert_eq!(visible.len(), 1);
+        assert!(visible[0].is_same(&ahead));
+    }
+    
+    #[test]
+    fn collect_visible_occlusion_test(){
+        let cat = facing_cat(100.0, 100.0, PVector::new(1.0, 0.0));
+        let near = positioned_rat(103.0, 100.0);
+        let hidden = positioned_rat(106.0, 100.0);
+        let aside = positioned_rat(106.0, 104.0);
+        let rats = vec![near.clone(), hidden.clone(), aside.clone()];
+        let tree = QuadTree::new(&rats);
+        let cats = QuadTree::new(&vec![cat.clone()]);
+        let neighbors = Neighbors { cats: &cats, rats: &tree };
+        assert!(is_occluded(&cat, &hidden, &neighbors));
+        assert!(!is_occluded(&cat, &near, &neighbors));
+        assert!(!is_occluded(&cat, &aside, &neighbors));
+        
+        // 設定で遮蔽を有効にしない限り全員見える
+        assert_eq!(collect_visible(&cat, &tree, &neighbors, 10.0).len(), 3);
+        let mut occlusion = Config::default();
+        occlusion.cat.vision.occlusion = true;
+        config::set(occlusion);
+        let visible = collect_visible(&cat, &tree, &neighbors, 10.0);
+        config::set(Config::default());
+        
+        assert_eq!(visible.len(), 2);
+        assert!(visible.iter().all(|rat| !rat.is_same(&hidden)));
+    }
+    
+    #[test]
+    fn occlusion_by_others_test(){
+        let cat = facing_cat(100.0, 100.0, PVector::new(1.0, 0.0));
+        let rat = positioned_rat(106.0, 100.0);
+        let rats = QuadTree::new(&vec![rat.clone()]);
+        // 間にいる別の猫も視線を遮る
+        let between = facing_cat(103.0, 100.0, PVector::new(0.0, 1.0));
+        let cats = QuadTree::new(&vec![cat.clone(), between]);
+        assert!(is_occluded(&cat, &rat, &Neighbors { cats: &cats, rats: &rats }));
+        let alone = QuadTree::new(&vec![cat.clone()]);
+        assert!(!is_occluded(&cat, &rat, &Neighbors { cats: &alone, rats: &rats }));
+        
+        // 障害物は遮り、隠れ家は遮らない
+        let mut walled = Config::default();
+        walled.refuges.push(Obstacle::Circle { center: PVector::new(103.0, 100.0), radious: 1.0 });
+        config::set(walled.clone());
+        let open = is_occluded(&cat, &rat, &Neighbors { cats: &alone, rats: &rats });
+        walled.obstacles.push(Obstacle::Circle { center: PVector::new(103.0, 100.5), radious: 1.0 });
+        config::set(walled);
+        let blocked = is_occluded(&cat, &rat, &Neighbors { cats: &alone, rats: &rats });
+        config::set(Config::default());
+        assert!(!open);
+        assert!(blocked);
+    }
+    
+    #[test]
+    fn evolve_vision_test(){
+        let parent = Cat::new();
+        // 既定では進化しない
+        for _ in 0..10 {
+            assert_eq!(parent.descendant().vision.view_angle, parent.vision.view_angle);
+        }
+        let mut evolvable = Config::default();
+        evolvable.cat.vision.evolvable = true;
+        evolvable.cat.vision.view_angle = PI;
+        config::set(evolvable);
+        let parent = Cat::new();
+        let changed = (0..10).any(|_| parent.descendant().vision.view_angle != PI);
+        config::set(Config::default());
+        assert!(changed);
+    }
+}
@@ -0,0 +1,434 @@
+mod test;
+
+use animal::{Animal, Cat, Lineage, Rat, Species, age_seconds};
+use config;
+use evaluation;
+use family::Member;
+use fitness;
+use fitness::Score;
+use food::Food;
+use neat;
+use neat::Niche;
+use neural::{Brain, Controller};
+use pareto;
+use predation;
+use predation::Kill;
+use quad_tree::QuadTree;
+use scenario::Scenario;
+use std::collections::HashSet;
+use std::io;
+use std::io::Write;
+use std::time::Instant;
+use termination::Progress;
+
+// ウィンドウを持たないシミュレーションの状態
+pub struct World {
+    pub cats: Vec<Cat>,
+    pub rats: Vec<Rat>,
+    pub cats_tree: QuadTree<Cat>,
+    pub rats_tree: QuadTree<Rat>,
+    pub steps: u64, // 世代が始まってから進めたステップ数
+    pub scenario: Scenario,
+    pub food: Food,
+    pub deaths: Vec<(Species, f64)>, // この世代で死んだ個体の種と死んだ時の年齢(秒)
+    pub kills: Vec<Kill>, // この世代で誰が誰を食べたか
+    pub misses: usize, // この世代で襲ったが逃げられた回数
+    pub ended: Option<String>, // 世代が終わった理由。満たした条件の名前
+    pub scores: Option<Vec<Score>>, // 別の場所で測った猫ごとの値。なければこの場所で測る
+    pub fallen: Vec<(Cat, Score)>, // この世代で死んだ猫と、死ぬ直前に測った値
+    pub births: Vec<Member>, // この世代で生まれた個体。世代の始めにいた個体を含む
+    pub generation: u32, // 何世代目か。1から数える
+    pub niches: Option<(Vec<Niche>, Vec<Niche>)>, // この世代の猫とねずみのNEATの種。speciateで分ける
+    previous: (Vec<Niche>, Vec<Niche>), // 前の世代の種。代表との距離で今の世代を分ける
+    last_kill: u64, // 最後に食べられたステップ
+    started: Instant,
+}
+
+impl World {
+    // シナリオに従って初期化
+    pub fn new(scenario: Scenario) -> World {
+        let cats = scenario.cats.spawn();
+        let rats = scenario.rats.spawn();
+        let mut world = World::with_animals(cats, rats);
+        world.scenario = scenario;
+        world
+    }
+    
+    // 与えられた個体で初期化
+    pub fn with_animals(cats: Vec<Cat>, rats: Vec<Rat>) -> World {
+        let cats_tree = QuadTree::new(&cats);
+        let rats_tree = QuadTree::new(&rats);
+        let births = cats.iter().map(Member::of).chain(rats.iter().map(Member::of)).collect();
+        World {
+            cats,
+            rats,
+            cats_tree,
+            rats_tree,
+            steps: 0,
+            scenario: Scenario::default(),
+            food: Food::new(),
+            deaths: Vec::new(),
+            kills: Vec::new(),
+            misses: 0,
+            ended: None,
+            scores: None,
+            fallen: Vec::new(),
+            births,
+            generation: 1,
+            niches: None,
+            previous: (Vec::new(), Vec::new()),
+            last_kill: 0,
+            started: Instant::now(),
+        }
+    }
+    
+    // 優秀な捕食者だけを次の世代へ。位置と速度はシナリオに従って置き直す
+    // ねずみは生き残った個体から次の世代を作る。NEATで操舵するなら種ごとに交叉させる
+    pub fn next_generation(&mut self){
+        let config = config::get();
+        let generation = self.generation + 1;
+        let niches = self.speciate();
+        let (candidates, scores) = self.candidates();
+        let cats = match config.cat.controller {
+            Controller::Neat => neat::next_generation(&candidates, &self.fitness(Species::Cat), &niches.0, self.scenario.cats.next_count()),
+            _ => Cat::next_generation(&candidates, &scores, self.scenario.cats.next_count()),
+        };
+        let cats = World::stamp(&self.scenario.cats.place(&cats), generation, 0);
+        let rats = match config.rat.controller {
+            Controller::Neat => self.scenario.rats.place(&neat::next_generation(&self.rats, &self.fitness(Species::Rat), &niches.1, self.scenario.rats.next_count())),
+            _ => self.scenario.rats.place(&Rat::next_generation(&self.rats, self.scenario.rats.next_count())),
+        };
+        let rats = World::stamp(&rats, generation, 0);
+        let scenario = self.scenario.clone();
+        *self = World::with_animals(cats, rats);
+        self.scenario = scenario;
+        self.generation = generation;
+        self.previous = niches;
+    }
+    
+    // NEATの脳を持つ個体を前の世代の種をもとに分ける。一つの世代では一度だけ分ける
+    pub fn speciate(&mut self) -> (Vec<Niche>, Vec<Niche>) {
+        if self.niches.is_none() {
+            let cats = neat::speciate(&World::genomes(&self.candidates().0), &self.previous.0);
+            let rats = neat::speciate(&World::genomes(&self.rats), &self.previous.1);
+            self.niches = Some((cats, rats));
+        }
+        self.niches.clone().unwrap()
+    }
+    
+    // NEATの脳を持つ個体の添字と遺伝子
+    fn genomes<A: Animal>(animals: &Vec<A>) -> Vec<(usize, neat::Genome)> {
+        animals
+            .iter()
+            .enumerate()
+            .filter_map(|(n, animal)| match animal.brain() {
+                Some(Brain::Neat(genome)) => Some((n, genome)),
+                _ => None,
+            })
+            .collect()
+    }
+    
+    // 種を選ぶのに使う個体ごとの適応度。猫は選択の候補の値、ねずみは生き延びた秒数
+    pub fn fitness(&self, species: Species) -> Vec<f64> {
+        match species {
+            Species::Cat => self.candidates().1.into_iter().map(|score| score.fitness).collect(),
+            Species::Rat => self.rats.iter().map(|rat| age_seconds(rat)).collect(),
+        }
+    }
+    
+    // 読み直したネットワークを種の合う個体に順番に持たせる。足りなければ最初から繰り返す
+    pub fn replay(&mut self, brains: &Vec<(Species, Brain)>) {
+        let pick = |species: Species| -> Vec<Brain> {
+            brains.iter().filter(|&&(s, _)| s == species).map(|&(_, ref brain)| brain.clone()).collect()
+        };
+        let (cat_brains, rat_brains) = (pick(Species::Cat), pick(Species::Rat));
+        if !cat_brains.is_empty() {
+            self.cats = self.cats.iter().zip(cat_brains.iter().cycle()).map(|(cat, brain)| cat.set_brain(Some(brain.clone()))).collect();
+        }
+        if !rat_brains.is_empty() {
+            self.rats = self.rats.iter().zip(rat_brains.iter().cycle()).map(|(rat, brain)| rat.set_brain(Some(brain.clone()))).collect();
+        }
+        self.niches = None;
+    }
+    
+    // 親はそのままに、generation世代目のbornステップに生まれたことにする
+    fn stamp<A: Animal>(animals: &Vec<A>, generation: u32, born: u64) -> Vec<A> {
+        animals
+            .iter()
+            .map(|animal| animal.set_lineage(&Lineage { born, generation, ..animal.lineage() }))
+            .collect()
+    }
+    
+    // 外から連れてきた猫をシナリオに従って置き、今の猫に加える
+    pub fn admit(&mut self, cats: &Vec<Cat>) {
+        let cats = World::stamp(&self.scenario.cats.place(cats), self.generation, self.steps);
+        self.births.extend(cats.iter().map(Member::of));
+        self.cats.extend(cats);
+        self.cats_tree = QuadTree::new(&self.cats);
+        self.niches = None;
+    }
+    
+    // 1ステップ(config.dt秒)進める。世代が終わったらtrue
+    pub fn step(&mut self) -> bool {
+        let cats = self.cats.clone();
+        let rats = self.rats.clone();
+        // 動く前の位置で捕食をまとめて解決する
+        let predation = predation::resolve(&cats, &rats, &self.cats_tree, &self.rats_tree);
+        if !predation.kills.is_empty() {
+            self.last_kill = self.steps + 1;
+        }
+        self.kills.extend(predation.kills);
+        self.misses += predation.misses;
+        self.cats = <Cat as Animal>::next_states(&predation.cats, &self.cats_tree, &self.rats_tree);
+        self.rats = <Rat as Animal>::next_states(&predation.rats, &self.cats_tree ,&self.rats_tree);
+        World::record_deaths(&mut self.deaths, &cats, &self.cats);
+        World::record_deaths(&mut self.deaths, &rats, &self.rats);
+        World::record_fallen(&mut self.fallen, &predation.cats, &self.cats);
+        World::record_births(&mut self.births, &cats, &self.cats);
+        World::record_births(&mut self.births, &rats, &self.rats);
+        // 動いた先で草を食べ、草が生え直す
+        let food = &mut self.food;
+        self.rats = self.rats.iter().map(|rat| rat.graze(food)).collect();
+        food.regrow(config::get().dt);
+        self.cats_tree = QuadTree::new(&self.cats);
+        self.rats_tree = QuadTree::new(&self.rats);
+        self.steps += 1;
+        self.ended = config::get().termination.check(&self.progress());
+        self.is_finished()
+    }
+    
+    // 終わりの判定に使う今の様子
+    pub fn progress(&self) -> Progress {
+        let elapsed = self.started.elapsed();
+        Progress {
+            steps: self.steps,
+            elapsed: elapsed.as_secs() as f64 + elapsed.subsec_nanos() as f64 * 1.0e-9,
+            cats: self.cats.len(),
+            rats: self.rats.len(),
+            since_kill: self.steps - self.last_kill,
+        }
+    }
+    
+    // 前のステップにいて今はいない個体の年齢を記録する。このステップの分だけ年を取って死んだとみなす
+    fn record_deaths<A: Animal>(deaths: &mut Vec<(Species, f64)>, before: &Vec<A>, after: &Vec<A>) {
+        let alive: HashSet<u64> = after.iter().map(|animal| animal.id()).collect();
+        let dt = config::get().dt;
+        for animal in before.iter().filter(|animal| !alive.contains(&animal.id())) {
+            deaths.push((animal.species(), age_seconds(animal) + dt));
+        }
+    }
+    
+    // 前のステップにいて今はいない猫を、いた時の値と一緒に選択の候補として残す
+    fn record_fallen(fallen: &mut Vec<(Cat, Score)>, before: &Vec<Cat>, after: &Vec<Cat>) {
+        let alive: HashSet<u64> = after.iter().map(|cat| cat.id()).collect();
+        for cat in before.iter().filter(|cat| !alive.contains(&cat.id())) {
+            fallen.push((cat.clone(), fitness::score(cat, before)));
+        }
+    }
+    
+    // 前のステップにいなかった個体を生まれた個体として記録する
+    fn record_births<A: Animal>(births: &mut Vec<Member>, before: &Vec<A>, after: &Vec<A>) {
+        let known: HashSet<u64> = before.iter().map(|animal| animal.id()).collect();
+        births.extend(after.iter().filter(|animal| !known.contains(&animal.id())).map(Member::of));
+    }
+    
+    // 猫を一匹ずつ別の場所で試して値を測る。この場所は進めない
+    pub fn evaluate(&mut self) {
+        self.scores = Some(evaluation::evaluate(&self.cats, &self.scenario));
+        self.ended = Some(String::from("isolated"));
+    }
+    
+    // 選択に使う猫ごとの値
+    pub fn scores(&self) -> Vec<Score> {
+        match self.scores {
+            Some(ref scores) => scores.clone(),
+            None => fitness::score_all(&self.cats),
+        }
+    }
+    
+    // 選択の候補になる猫とその値。生き残った猫に、この世代で死んだ猫を加える
+    pub fn candidates(&self) -> (Vec<Cat>, Vec<Score>) {
+        let mut cats = self.cats.clone();
+        let mut scores = self.scores();
+        for &(ref cat, ref score) in &self.fallen {
+            cats.push(cat.clone());
+            scores.push(score.clone());
+        }
+        (cats, scores)
+    }
+    
+    // 良い順にcount匹と、その値。ほかの島へ移すのに使う
+    pub fn emigrants(&self, count: usize) -> Vec<(Cat, Score)> {
+        let scores = self.scores();
+        fitness::rank(&scores)
+            .into_iter()
+            .take(count)
+            .map(|n| (self.cats[n].clone(), scores[n].clone()))
+            .collect()
+    }
+    
+    // ほかの島から来た個体を一番悪い個体と入れ替える。値も一緒に持ってくる
+    pub fn immigrate(&mut self, migrants: Vec<(Cat, Score)>) {
+        let mut scores = self.scores();
+        let worst: Vec<usize> = fitness::rank(&scores).into_iter().rev().collect();
+        for (n, (cat, score)) in worst.into_iter().zip(migrants) {
+            self.cats[n] = cat;
+            scores[n] = score;
+        }
+        self.scores = Some(scores);
+        self.niches = None;
+    }
+    
+    // ウィンドウなしで世代が終わるまで進める
+    pub fn run(&mut self) {
+        while !self.step() {}
+    }
+    
+    // 設定した終わりの条件を満たしたら世代が終わる
+    pub fn is_finished(&self) -> bool {
+        self.ended.is_some()
+    }
+    
+    // 捕食者のパラメータの平均の計算
+    fn chase_average(animals: &Vec<Cat>) -> f64 {
+        animals
+            .into_iter()
+            .fold(0.0, |a, b| a + b.chase_weight)
+            / animals.len() as f64
+    }
+    
+    // 捕食者のパラメータの平均の計算
+    fn align_average(animals: &Vec<Cat>) -> f64 {
+        animals
+            .into_iter()
+            .fold(0.0, |a, b| a + b.align_weight)
+            / animals.len() as f64
+    }
+    
+    // 捕食者のパラメータの平均の計算
+    fn separate_average(animals: &Vec<Cat>) -> f64 {
+        animals
+            .into_iter()
+            .fold(0.0, |a, b| a + b.separate_weight)
+            / animals.len() as f64
+    }
+    
+    // 捕食者のパラメータの平均の計算
+    fn cohension_average(animals: &Vec<Cat>) -> f64 {
+        animals
+            .into_iter()
+            .fold(0.0, |a, b| a + b.cohension_weight) 
+            / animals.len() as f64
+    }
+
+    // 捕食者の視野角の平均を度で計算
+    fn view_angle_average(animals: &Vec<Cat>) -> f64 {
+        animals
+            .into_iter()
+            .fold(0.0, |a, b| a + b.vision.view_angle.to_degrees())
+            / animals.len() as f64
+    }
+    
+    // 捕食者の死角の平均を度で計算
+    fn blind_spot_average(animals: &Vec<Cat>) -> f64 {
+        animals
+            .into_iter()
+            .fold(0.0, |a, b| a + b.vision.blind_spot.to_degrees())
+            / animals.len() as f64
+    }
+
+    // 捕食者の先読み時間の平均の計算
+    fn lead_time_average(animals: &Vec<Cat>) -> f64 {
+        animals
+            .into_iter()
+            .fold(0.0, |a, b| a + b.lead_time)
+            / animals.len() as f64
+    }
+    
+    // 世代の最後に死んだ時の年齢を「世代,種,年齢」の行で書き出す
+    pub fn write_deaths<W: Write>(&self, out: &mut W, ord: i32) -> io::Result<()> {
+        for &(species, age) in &self.deaths {
+            writeln!(out, "{},{},{}", ord, species.name(), age)?;
+        }
+        Ok(())
+    }
+    
+    // 世代の最後に誰が誰を食べたかを「世代,猫,ねずみ,x,y」の行で書き出す
+    pub fn write_kills<W: Write>(&self, out: &mut W, ord: i32) -> io::Result<()> {
+        for kill in &self.kills {
+            writeln!(out, "{},{},{},{},{}", ord, kill.cat, kill.rat, kill.position.x, kill.position.y)?;
+        }
+        Ok(())
+    }
+    
+    // 世代の最後に猫ごとの適応度を「世代,猫,適応度」の行で書き出す。死んだ猫も含める
+    pub fn write_fitness<W: Write>(&self, out: &mut W, ord: i32) -> io::Result<()> {
+        let (cats, scores) = self.candidates();
+        for (cat, score) in cats.iter().zip(scores) {
+            writeln!(out, "{},{},{}", ord, cat.id(), score.fitness)?;
+        }
+        Ok(())
+    }
+    
+    // 世代の最後に第1フロントの猫を「世代,猫,目的の値...」の行で書き出す
+    pub fn write_front<W: Write>(&self, out: &mut W, ord: i32) -> io::Result<()> {
+        let (cats, scores) = self.candidates();
+        let scores: Vec<Vec<f64>> = scores.into_iter().map(|score| score.objectives).collect();
+        for &n in pareto::fronts(&scores).first().unwrap_or(&Vec::new()) {
+            let values: Vec<String> = scores[n].iter().map(|value| value.to_string()).collect();
+            writeln!(out, "{},{},{}", ord, cats[n].id(), values.join(","))?;
+        }
+        Ok(())
+    }
+    
+    // 適応度の平均。猫がいなければ0
+    pub fn mean_fitness(&self) -> f64 {
+        let scores = self.candidates().1;
+        if scores.is_empty() {
+            return 0.0;
+        }
+        scores.iter().map(|score| score.fitness).sum::<f64>() / scores.len() as f64
+    }
+    
+    // 一番高い適応度。猫がいなければ0
+    fn best_fitness(&self) -> f64 {
+        self
+            .candidates()
+            .1
+            .iter()
+            .map(|score| score.fitness)
+            .fold(None, |best: Option<f64>, score| Some(best.map_or(score, |best| best.max(score))))
+            .unwrap_or(0.0)
+    }
+    
+    // 世代の最後にNEATの種ごとの大きさと構造の大きさを書き出す。speciateの後に呼ぶ
+    pub fn write_species<W: Write>(&self, out: &mut W, ord: i32) -> io::Result<()> {
+        let (cats, rats) = self.niches.clone().unwrap_or_default();
+        neat::write_species(out, ord, &self.candidates().0, &self.fitness(Species::Cat), &cats)?;
+        neat::write_species(out, ord, &self.rats, &self.fitness(Species::Rat), &rats)
+    }
+    
+    // 世代の最後にNEATの種ごとに一番良いネットワークを書き出す。speciateの後に呼ぶ
+    pub fn write_networks<W: Write>(&self, out: &mut W, ord: i32) -> io::Result<()> {
+        let (cats, rats) = self.niches.clone().unwrap_or_default();
+        neat::write_networks(out, ord, &self.candidates().0, &self.fitness(Species::Cat), &cats)?;
+        neat::write_networks(out, ord, &self.rats, &self.fitness(Species::Rat), &rats)
+    }
+    
+    // 世代の最後にパラメータを標準出力へ
+    pub fn print_params(&self, ord: i32) {
+        print!("{},", ord);
+        print!("{},", World::chase_average(&self.cats));
+        print!("{},", World::align_average(&self.cats));
+        print!("{},", World::cohension_average(&self.cats));
+        print!("{},", World::separate_average(&self.cats));
+        print!("{},", World::view_angle_average(&self.cats));
+        print!("{},", World::blind_spot_average(&self.cats));
+        print!("{},", World::lead_time_average(&self.cats));
+        print!("{},", self.best_fitness());
+        print!("{},", self.misses);
+        // 条件を満たす前にウィンドウが閉じられたらclosed
+        print!("{}", self.ended.as_ref().map_or("closed", |reason| reason.as_str()));
+        println!("");
+    }
+}
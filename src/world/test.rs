@@ -0,0 +1,157 @@
+#[cfg(test)]
+mod tests{
+    use animal::{Animal, Cat, Rat, Species};
+    use config;
+    use fitness::{Measure, Survival, WeightedFitness};
+    use consts::*;
+    use pvector::PVector;
+    use scenario::Scenario;
+    use termination::Condition;
+    use world::World;
+    
+    #[test]
+    fn step_test(){
+        // 最高速でまっすぐ進む猫は1秒でmax_speedだけ進む
+        let max_speed = config::get().cat.motion.max_speed;
+        let cat = Cat::new()
+            .set_position(&PVector::new(100.5, 100.5))
+            .apply_velocity(&PVector::new(max_speed, 0.0));
+        let id = cat.id();
+        let mut world = World::with_animals(vec![cat], Vec::new());
+        let steps = (1.0 / config::get().dt).round() as u64;
+        for _ in 0..steps {
+            // ねずみがいないので毎回終わりになる
+            assert!(world.step());
+        }
+        assert_eq!(steps, world.steps);
+        let moved = world.cats.iter().find(|cat| cat.id() == id).unwrap();
+        assert!((moved.position().x - (100.5 + max_speed)).abs() < 1.0e-6);
+        assert!((moved.position().y - 100.5).abs() < 1.0e-6);
+        assert!(moved.position().x < WIDTH);
+    }
+    
+    #[test]
+    fn deaths_test(){
+        // 1ステップで寿命を迎える猫の死が年齢つきで記録される
+        let mut config = config::Config::default();
+        config.cat.life.lifespan = Some(config.dt);
+        let dt = config.dt;
+        config::set(config);
+        let cat = Cat::new().set_position(&PVector::new(100.5, 100.5));
+        let mut world = World::with_animals(vec![cat], Vec::new());
+        world.step();
+        config::set(config::Config::default());
+        assert_eq!(0, world.cats.len());
+        assert_eq!(1, world.deaths.len());
+        assert_eq!(Species::Cat, world.deaths[0].0);
+        assert!((world.deaths[0].1 - dt).abs() < 1.0e-9);
+        
+        let mut out: Vec<u8> = Vec::new();
+        world.write_deaths(&mut out, 3).unwrap();
+        assert!(String::from_utf8(out).unwrap().starts_with("3,cat,"));
+        world.next_generation();
+        assert_eq!(0, world.deaths.len());
+    }
+    
+    #[test]
+    fn fallen_test(){
+        // 先に死んだ猫も死ぬ直前の値で選択の候補に残り、生きた秒数で比べられる
+        let mut config = config::Config::default();
+        config.cat.life.lifespan = Some(config.dt * 2.5);
+        config.fitness = vec![WeightedFitness { weight: 1.0, measure: Measure::Survival(Survival) }];
+        config::set(config);
+        let old = Cat::new().set_position(&PVector::new(100.5, 100.5));
+        let mut world = World::with_animals(vec![old.clone()], vec![Rat::new()]);
+        world.step();
+        world.step();
+        let young = Cat::new().set_position(&PVector::new(300.5, 300.5));
+        world.cats.push(young.clone());
+        world.step();
+        let (cats, scores) = world.candidates();
+        config::set(config::Config::default());
+        assert_eq!(vec![young.id(), old.id()], cats.iter().map(|cat| cat.id()).collect::<Vec<u64>>());
+        assert_eq!(1, world.fallen.len());
+        assert!(scores[1].fitness > scores[0].fitness);
+    }
+    
+    #[test]
+    fn misses_test(){
+        // 必ず逃げられるなら、襲った回数だけ数えて次の世代で0に戻す
+        let mut config = config::Config::default();
+        config.predation.capture.chance = 0.0;
+        config::set(config);
+        let cat = Cat::new().set_position(&PVector::new(100.5, 100.5));
+        let rat = Rat::new().set_position(&PVector::new(100.7, 100.5));
+        let mut world = World::with_animals(vec![cat], vec![rat]);
+        world.step();
+        config::set(config::Config::default());
+        assert_eq!(1, world.misses);
+        assert!(world.kills.is_empty());
+        world.next_generation();
+        assert_eq!(0, world.misses);
+    }
+    
+    #[test]
+    fn termination_test(){
+        // ねずみが残っていてもステップ数の上限で終わり、理由が残る
+        let mut config = config::Config::default();
+        config.termination.conditions = vec![Condition::Frames(3)];
+        config::set(config);
+        let mut world = World::with_animals(vec![Cat::new()], vec![Rat::new()]);
+        assert!(!world.step());
+        assert!(!world.step());
+        assert!(world.step());
+        assert_eq!(Some(String::from("frames")), world.ended);
+        
+        // 誰も食べられないまま続けば停滞で終わる
+        let mut config = config::Config::default();
+        config.termination.conditions = vec![Condition::Stagnation(2)];
+        config::set(config);
+        let cat = Cat::new().set_position(&PVector::new(100.5, 100.5));
+        let rat = Rat::new().set_position(&PVector::new(300.5, 200.5));
+        let mut world = World::with_animals(vec![cat], vec![rat]);
+        assert!(!world.step());
+        assert!(world.step());
+        assert_eq!(Some(String::from("stagnation")), world.ended);
+        config::set(config::Config::default());
+    }
+    
+    #[test]
+    fn next_generation_test(){
+        let mut world = World::new(Scenario::find("single_hunter").unwrap());
+        assert_eq!(1, world.cats.len());
+        world.step();
+        world.next_generation();
+        assert_eq!(0, world.steps);
+        assert!(!world.is_finished());
+        // 世代が変わっても数はシナリオのまま
+        assert_eq!(1, world.cats.len());
+        assert_eq!(RAT_COUNT, world.rats.len());
+    }
+    
+    #[test]
+    fn lineage_test(){
+        let mut world = World::new(Scenario::find("single_hunter").unwrap());
+        assert_eq!(1 + RAT_COUNT, world.births.len());
+        let parent = world.cats[0].id();
+        world.next_generation();
+        // 次の世代の猫は前の世代の猫の子で、世代の始めに生まれる
+        let lineage = world.cats[0].lineage();
+        assert_eq!(Some(parent), lineage.parent);
+        assert_eq!(2, lineage.generation);
+        assert_eq!(0, lineage.born);
+        assert_eq!(2, world.rats[0].lineage().generation);
+        // ねずみも生き残った個体の子になる
+        assert!(world.rats[0].lineage().parent.is_some());
+        assert!(world.births.iter().any(|member| member.id == world.cats[0].id()));
+        
+        // 途中で加えた猫はその世代の加えたステップに生まれたことになる
+        let before = world.births.len();
+        world.steps = 5;
+        world.admit(&vec![Cat::new()]);
+        assert_eq!(before + 1, world.births.len());
+        let admitted = world.births.last().unwrap();
+        assert_eq!(5, admitted.lineage.born);
+        assert_eq!(2, admitted.lineage.generation);
+    }
+}